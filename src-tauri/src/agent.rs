@@ -0,0 +1,289 @@
+// LLM 도구 호출(tool calling) 에이전트
+// OpenAI 형식 tools 정의를 보내고, 모델이 요청한 도구를 실행해 결과를 돌려주는 것을 최종 답이 나올 때까지 반복
+use crate::llm::ChatCompletionRequest;
+use crate::register_map::parse_wide_number;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+pub const DEFAULT_MAX_STEPS: u32 = 8;
+// 사용자가 확인 요청에 답하지 않으면 거부로 처리
+pub const CONFIRM_TIMEOUT: Duration = Duration::from_secs(120);
+const DEFAULT_LOG_LIMIT: usize = 50;
+const MAX_LOG_LIMIT: usize = 500;
+
+#[derive(Deserialize)]
+pub struct AgentRequest {
+    #[serde(flatten)]
+    pub chat: ChatCompletionRequest,
+    pub max_steps: Option<u32>,
+}
+
+// 실행(또는 거부)된 도구 호출 기록
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCallRecord {
+    pub request_id: u64,
+    pub id: String,
+    pub name: String,
+    pub arguments: Value,
+    // "executed" | "denied" | "failed"
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub timestamp: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentResult {
+    pub request_id: u64,
+    pub content: String,
+    // false 면 maxSteps 안에 최종 답을 받지 못함
+    pub finished: bool,
+    pub calls: Vec<ToolCallRecord>,
+}
+
+// 장치 상태를 바꾸는 도구 실행 전 확인 요청 (llm-tool-confirm 이벤트)
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolConfirmRequest {
+    pub request_id: u64,
+    pub confirm_id: u64,
+    pub name: String,
+    pub arguments: Value,
+    // 확인이 필요한 이유 (사용자에게 보여줌)
+    pub reason: String,
+}
+
+// 모델이 요청한 도구 호출
+#[derive(Clone, Debug)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    // 파싱하지 못한 인자는 Err (모델에 오류로 돌려줌)
+    pub arguments: Result<Value, String>,
+}
+
+impl ToolCall {
+    pub fn record(&self, request_id: u64) -> ToolCallRecord {
+        ToolCallRecord {
+            request_id,
+            id: self.id.clone(),
+            name: self.name.clone(),
+            arguments: self.arguments.clone().unwrap_or(Value::Null),
+            status: String::new(),
+            result: None,
+            error: None,
+            timestamp: chrono::Local::now().timestamp_millis().max(0) as u64,
+        }
+    }
+}
+
+// 장치 상태를 바꾸는 도구 (실행 전에 항상 사용자 확인)
+pub fn is_destructive(name: &str) -> bool {
+    matches!(
+        name,
+        "write_register" | "set_voltage" | "set_frequency" | "send_command"
+    )
+}
+
+fn tool(name: &str, description: &str, properties: Value, required: &[&str]) -> Value {
+    json!({
+        "type": "function",
+        "function": {
+            "name": name,
+            "description": description,
+            "parameters": {
+                "type": "object",
+                "properties": properties,
+                "required": required,
+            },
+        },
+    })
+}
+
+pub fn tool_definitions() -> Vec<Value> {
+    let address = json!({
+        "type": ["integer", "string"],
+        "description": "Register address, e.g. 16 or \"0x10\"",
+    });
+    let page = json!({
+        "type": "integer",
+        "description": "Register page for paged register maps (optional)",
+    });
+    vec![
+        tool(
+            "read_register",
+            "Read a register from the connected device. Reading a read-to-clear register needs user confirmation.",
+            json!({ "address": address, "page": page }),
+            &["address"],
+        ),
+        tool(
+            "write_register",
+            "Write a value to a register on the connected device.",
+            json!({
+                "address": address,
+                "value": { "type": ["integer", "string"], "description": "Value, e.g. 255 or \"0xFF\"" },
+                "page": page,
+            }),
+            &["address", "value"],
+        ),
+        tool(
+            "set_voltage",
+            "Set the output voltage in volts.",
+            json!({ "value": { "type": "number" } }),
+            &["value"],
+        ),
+        tool(
+            "set_frequency",
+            "Set the output frequency in hertz.",
+            json!({ "value": { "type": "integer" } }),
+            &["value"],
+        ),
+        tool(
+            "send_command",
+            "Send a raw text command to the connected device (include a trailing newline if the device needs one).",
+            json!({ "data": { "type": "string" } }),
+            &["data"],
+        ),
+        tool(
+            "scan_devices",
+            "List serial, FTDI and CAN devices that can be connected.",
+            json!({}),
+            &[],
+        ),
+        tool(
+            "query_logs",
+            "Query the register transaction history, newest last.",
+            json!({
+                "op": { "type": "string", "enum": ["read", "write", "set"] },
+                "address": address,
+                "success": { "type": "boolean" },
+                "limit": { "type": "integer", "description": "Maximum entries (default 50)" },
+            }),
+            &[],
+        ),
+    ]
+}
+
+// 도구 인자 읽기 (숫자 또는 "0x10" 같은 문자열)
+pub fn number_arg(args: &Value, name: &str) -> Result<Option<u64>, String> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(n)) => n
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| format!("Argument '{}' must be a non-negative integer", name)),
+        Some(Value::String(text)) => parse_wide_number(text)
+            .map(Some)
+            .ok_or_else(|| format!("Argument '{}' is not a number: {}", name, text)),
+        Some(other) => Err(format!("Argument '{}' has invalid value {}", name, other)),
+    }
+}
+
+pub fn required_number(args: &Value, name: &str) -> Result<u64, String> {
+    number_arg(args, name)?.ok_or_else(|| format!("Missing argument '{}'", name))
+}
+
+pub fn u32_arg(args: &Value, name: &str) -> Result<Option<u32>, String> {
+    number_arg(args, name)?
+        .map(|value| {
+            u32::try_from(value).map_err(|_| format!("Argument '{}' is out of range", name))
+        })
+        .transpose()
+}
+
+pub fn log_limit(args: &Value) -> Result<usize, String> {
+    Ok(number_arg(args, "limit")?
+        .map(|limit| (limit as usize).clamp(1, MAX_LOG_LIMIT))
+        .unwrap_or(DEFAULT_LOG_LIMIT))
+}
+
+// 응답에서 assistant 메시지 (다음 요청에 그대로 붙임)
+pub fn response_message(response: &Value) -> Result<Value, String> {
+    response["choices"][0]
+        .get("message")
+        .cloned()
+        .ok_or_else(|| "LLM response has no message".to_string())
+}
+
+pub fn tool_calls(message: &Value) -> Vec<ToolCall> {
+    let Some(calls) = message.get("tool_calls").and_then(|c| c.as_array()) else {
+        return Vec::new();
+    };
+    calls
+        .iter()
+        .map(|call| {
+            let function = &call["function"];
+            // 인자는 JSON 문자열 (일부 서버는 객체를 그대로 보냄)
+            let arguments = match &function["arguments"] {
+                Value::String(text) if text.trim().is_empty() => Ok(json!({})),
+                Value::String(text) => {
+                    serde_json::from_str(text).map_err(|e| format!("Invalid tool arguments: {}", e))
+                }
+                Value::Null => Ok(json!({})),
+                other => Ok(other.clone()),
+            };
+            ToolCall {
+                id: call["id"].as_str().unwrap_or_default().to_string(),
+                name: function["name"].as_str().unwrap_or_default().to_string(),
+                arguments,
+            }
+        })
+        .collect()
+}
+
+// 도구 결과 메시지
+pub fn tool_message(record: &ToolCallRecord) -> Value {
+    let content = match (&record.result, &record.error) {
+        (Some(result), _) => json!({ "ok": true, "result": result }),
+        (None, Some(error)) => json!({ "ok": false, "error": error }),
+        (None, None) => json!({ "ok": true }),
+    };
+    json!({
+        "role": "tool",
+        "tool_call_id": record.id,
+        "content": content.to_string(),
+    })
+}
+
+// 진행 중인 확인 요청 (확인 ID -> 응답 채널)
+#[derive(Default)]
+pub struct Confirmations {
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<u64, oneshot::Sender<bool>>>,
+}
+
+impl Confirmations {
+    pub fn request(&self) -> (u64, oneshot::Receiver<bool>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let (sender, receiver) = oneshot::channel();
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(id, sender);
+        }
+        (id, receiver)
+    }
+
+    // 이미 답했거나 시간이 지난 요청이면 false
+    pub fn resolve(&self, id: u64, approved: bool) -> bool {
+        let sender = self
+            .pending
+            .lock()
+            .ok()
+            .and_then(|mut pending| pending.remove(&id));
+        sender.is_some_and(|sender| sender.send(approved).is_ok())
+    }
+
+    pub fn discard(&self, id: u64) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(&id);
+        }
+    }
+}
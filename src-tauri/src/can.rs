@@ -0,0 +1,637 @@
+// SocketCAN 브릿지
+// CAN 연결 마이크로컨트롤러를 통해 IC를 설정하기 위한 raw CAN 소켓 래퍼 (소켓은 Linux 전용)
+#![cfg_attr(not(target_os = "linux"), allow(dead_code))]
+
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+#[cfg(target_os = "linux")]
+use std::{
+    ffi::CString,
+    io, mem,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    time::{Duration, Instant},
+};
+
+// linux/can.h 상수
+const CAN_EFF_FLAG: u32 = 0x8000_0000;
+const CAN_RTR_FLAG: u32 = 0x4000_0000;
+const CAN_ERR_FLAG: u32 = 0x2000_0000;
+const CAN_SFF_MASK: u32 = 0x0000_07FF;
+const CAN_EFF_MASK: u32 = 0x1FFF_FFFF;
+const CANFD_BRS: u8 = 0x01;
+
+// linux/can.h 의 can_frame(16바이트) / canfd_frame(72바이트) 와 동일한 레이아웃
+#[cfg(target_os = "linux")]
+const CAN_MTU: usize = 16;
+#[cfg(target_os = "linux")]
+const CANFD_MTU: usize = 72;
+const CAN_MAX_DLEN: usize = 8;
+const CANFD_MAX_DLEN: usize = 64;
+
+#[cfg(target_os = "linux")]
+#[repr(C, align(8))]
+struct RawFrame {
+    can_id: u32,
+    len: u8,
+    flags: u8,
+    res0: u8,
+    res1: u8,
+    data: [u8; CANFD_MAX_DLEN],
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CanFrame {
+    pub id: u32,
+    #[serde(default)]
+    pub extended: bool,
+    #[serde(default)]
+    pub fd: bool,
+    #[serde(default)]
+    pub brs: bool,
+    pub data: Vec<u8>,
+}
+
+impl CanFrame {
+    // CAN-FD 길이(0..64)를 DLC 코드(0..15)로 변환
+    pub fn dlc(&self) -> u8 {
+        match self.data.len() {
+            0..=8 => self.data.len() as u8,
+            9..=12 => 9,
+            13..=16 => 10,
+            17..=20 => 11,
+            21..=24 => 12,
+            25..=32 => 13,
+            33..=48 => 14,
+            _ => 15,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let max_id = if self.extended {
+            CAN_EFF_MASK
+        } else {
+            CAN_SFF_MASK
+        };
+        if self.id > max_id {
+            return Err(format!(
+                "CAN ID 0x{:X} exceeds {} range",
+                self.id,
+                id_kind(self.extended)
+            ));
+        }
+        let max_len = if self.fd {
+            CANFD_MAX_DLEN
+        } else {
+            CAN_MAX_DLEN
+        };
+        if self.data.len() > max_len {
+            return Err(format!(
+                "CAN payload of {} bytes exceeds the {} byte limit",
+                self.data.len(),
+                max_len
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn id_kind(extended: bool) -> &'static str {
+    if extended {
+        "29-bit"
+    } else {
+        "11-bit"
+    }
+}
+
+// 프론트엔드로 전달되는 수신 프레임 이벤트
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CanFrameEvent {
+    pub id: u32,
+    pub extended: bool,
+    pub fd: bool,
+    pub dlc: u8,
+    pub data: Vec<u8>,
+    pub timestamp: u64,
+}
+
+impl CanFrameEvent {
+    pub fn new(frame: &CanFrame) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        Self {
+            id: frame.id,
+            extended: frame.extended,
+            fd: frame.fd,
+            dlc: frame.dlc(),
+            data: frame.data.clone(),
+            timestamp,
+        }
+    }
+}
+
+// 로그 패널용 candump 형식 문자열 (예: "123#DEADBEEF", "1ABCDEF0##1DEAD")
+pub fn format_frame(frame: &CanFrame) -> String {
+    let id = if frame.extended {
+        format!("{:08X}", frame.id)
+    } else {
+        format!("{:03X}", frame.id)
+    };
+    if frame.fd {
+        format!(
+            "{}##{}{}",
+            id,
+            if frame.brs { 1 } else { 0 },
+            hex::encode_upper(&frame.data)
+        )
+    } else {
+        format!("{}#{}", id, hex::encode_upper(&frame.data))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CanFilterConfig {
+    pub id: u32,
+    pub mask: u32,
+    #[serde(default)]
+    pub extended: bool,
+}
+
+// 레지스터 읽기/쓰기를 CAN 요청/응답으로 매핑하기 위한 디바이스별 설정
+//
+// 요청: [op, addr(BE, address_bytes), value(BE, value_bytes, 쓰기만)]
+// 응답: [op, addr, value(읽기만)] 또는 [0x7F, op, error_code]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CanRegisterConfig {
+    pub request_id: u32,
+    pub response_id: u32,
+    #[serde(default)]
+    pub extended: bool,
+    #[serde(default)]
+    pub fd: bool,
+    #[serde(default = "default_address_bytes")]
+    pub address_bytes: u8,
+    #[serde(default = "default_value_bytes")]
+    pub value_bytes: u8,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_address_bytes() -> u8 {
+    2
+}
+
+fn default_value_bytes() -> u8 {
+    4
+}
+
+fn default_timeout_ms() -> u64 {
+    200
+}
+
+const OP_READ: u8 = 0x01;
+const OP_WRITE: u8 = 0x02;
+const OP_ERROR: u8 = 0x7F;
+
+impl CanRegisterConfig {
+    // 연결할 때와 요청을 만들 때 확인 (잘못된 크기로 요청을 만들면 push_be 가 범위를 벗어남)
+    pub fn validate(&self) -> Result<(), String> {
+        if !matches!(self.address_bytes, 1 | 2 | 4) || !matches!(self.value_bytes, 1 | 2 | 4) {
+            return Err("CAN register address/value size must be 1, 2 or 4 bytes".into());
+        }
+        let max_len = if self.fd {
+            CANFD_MAX_DLEN
+        } else {
+            CAN_MAX_DLEN
+        };
+        if 1 + self.address_bytes as usize + self.value_bytes as usize > max_len {
+            return Err("CAN register request does not fit into a single frame".into());
+        }
+        Ok(())
+    }
+
+    fn request(&self, op: u8, address: u32, value: Option<u32>) -> Result<CanFrame, String> {
+        self.validate()?;
+        let mut data = vec![op];
+        push_be(&mut data, "Address", address, self.address_bytes)?;
+        if let Some(value) = value {
+            push_be(&mut data, "Value", value, self.value_bytes)?;
+        }
+        Ok(CanFrame {
+            id: self.request_id,
+            extended: self.extended,
+            fd: self.fd,
+            brs: false,
+            data,
+        })
+    }
+}
+
+// 잘라서 보내지 않도록 bytes 에 들어가지 않는 값은 거부
+fn push_be(buf: &mut Vec<u8>, what: &str, value: u32, bytes: u8) -> Result<(), String> {
+    if value & !mask_for(bytes) != 0 {
+        return Err(format!(
+            "{} 0x{:X} does not fit in {} byte(s) of the CAN register request",
+            what, value, bytes
+        ));
+    }
+    buf.extend_from_slice(&value.to_be_bytes()[4 - bytes as usize..]);
+    Ok(())
+}
+
+fn read_be(data: &[u8]) -> u32 {
+    data.iter().fold(0u32, |acc, b| (acc << 8) | *b as u32)
+}
+
+#[cfg(target_os = "linux")]
+pub struct CanSocket {
+    fd: OwnedFd,
+    fd_frames: bool,
+}
+
+#[cfg(target_os = "linux")]
+impl CanSocket {
+    pub fn open(interface: &str, fd_frames: bool) -> io::Result<Self> {
+        let name = CString::new(interface)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "invalid interface name"))?;
+        let ifindex = unsafe { libc::if_nametoindex(name.as_ptr()) };
+        if ifindex == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let raw = unsafe { libc::socket(libc::PF_CAN, libc::SOCK_RAW, libc::CAN_RAW) };
+        if raw < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let fd = unsafe { OwnedFd::from_raw_fd(raw) };
+
+        if fd_frames {
+            let enable: libc::c_int = 1;
+            set_option(&fd, libc::CAN_RAW_FD_FRAMES, &enable)?;
+        }
+
+        let mut addr: libc::sockaddr_can = unsafe { mem::zeroed() };
+        addr.can_family = libc::AF_CAN as libc::sa_family_t;
+        addr.can_ifindex = ifindex as libc::c_int;
+        let ret = unsafe {
+            libc::bind(
+                fd.as_raw_fd(),
+                &addr as *const libc::sockaddr_can as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_can>() as libc::socklen_t,
+            )
+        };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let socket = Self { fd, fd_frames };
+        socket.set_read_timeout(Duration::from_millis(100))?;
+        Ok(socket)
+    }
+
+    pub fn set_read_timeout(&self, timeout: Duration) -> io::Result<()> {
+        let tv = libc::timeval {
+            tv_sec: timeout.as_secs() as libc::time_t,
+            tv_usec: timeout.subsec_micros() as libc::suseconds_t,
+        };
+        let ret = unsafe {
+            libc::setsockopt(
+                self.fd.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_RCVTIMEO,
+                &tv as *const libc::timeval as *const libc::c_void,
+                mem::size_of::<libc::timeval>() as libc::socklen_t,
+            )
+        };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    // 빈 목록이면 모든 프레임 수신
+    pub fn set_filters(&self, filters: &[CanFilterConfig]) -> io::Result<()> {
+        let raw: Vec<[u32; 2]> = if filters.is_empty() {
+            vec![[0, 0]]
+        } else {
+            filters
+                .iter()
+                .map(|f| {
+                    if f.extended {
+                        [
+                            (f.id & CAN_EFF_MASK) | CAN_EFF_FLAG,
+                            (f.mask & CAN_EFF_MASK) | CAN_EFF_FLAG,
+                        ]
+                    } else {
+                        [f.id & CAN_SFF_MASK, (f.mask & CAN_SFF_MASK) | CAN_EFF_FLAG]
+                    }
+                })
+                .collect()
+        };
+        let ret = unsafe {
+            libc::setsockopt(
+                self.fd.as_raw_fd(),
+                libc::SOL_CAN_RAW,
+                libc::CAN_RAW_FILTER,
+                raw.as_ptr() as *const libc::c_void,
+                (raw.len() * mem::size_of::<[u32; 2]>()) as libc::socklen_t,
+            )
+        };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    // 타임아웃 시 Ok(None)
+    pub fn read_frame(&self) -> io::Result<Option<CanFrame>> {
+        let mut raw: RawFrame = unsafe { mem::zeroed() };
+        let n = unsafe {
+            libc::read(
+                self.fd.as_raw_fd(),
+                &mut raw as *mut RawFrame as *mut libc::c_void,
+                CANFD_MTU,
+            )
+        };
+        if n < 0 {
+            let err = io::Error::last_os_error();
+            return match err.kind() {
+                io::ErrorKind::WouldBlock
+                | io::ErrorKind::TimedOut
+                | io::ErrorKind::Interrupted => Ok(None),
+                _ => Err(err),
+            };
+        }
+
+        let fd = match n as usize {
+            CAN_MTU => false,
+            CANFD_MTU => true,
+            _ => return Ok(None),
+        };
+        // 에러/원격 프레임은 데이터 프레임이 아니므로 무시
+        if raw.can_id & (CAN_ERR_FLAG | CAN_RTR_FLAG) != 0 {
+            return Ok(None);
+        }
+        let extended = raw.can_id & CAN_EFF_FLAG != 0;
+        let id = if extended {
+            raw.can_id & CAN_EFF_MASK
+        } else {
+            raw.can_id & CAN_SFF_MASK
+        };
+        let max_len = if fd { CANFD_MAX_DLEN } else { CAN_MAX_DLEN };
+        let len = (raw.len as usize).min(max_len);
+        Ok(Some(CanFrame {
+            id,
+            extended,
+            fd,
+            brs: fd && raw.flags & CANFD_BRS != 0,
+            data: raw.data[..len].to_vec(),
+        }))
+    }
+
+    pub fn write_frame(&self, frame: &CanFrame) -> Result<(), String> {
+        frame.validate()?;
+        if frame.fd && !self.fd_frames {
+            return Err("CAN-FD frames require the interface to be opened in FD mode".into());
+        }
+
+        let mut raw: RawFrame = unsafe { mem::zeroed() };
+        raw.can_id = if frame.extended {
+            frame.id | CAN_EFF_FLAG
+        } else {
+            frame.id
+        };
+        raw.data[..frame.data.len()].copy_from_slice(&frame.data);
+        let mtu = if frame.fd {
+            // FD 프레임 길이는 DLC 테이블 크기로 패딩
+            raw.len = dlc_to_len(frame.dlc()) as u8;
+            if frame.brs {
+                raw.flags = CANFD_BRS;
+            }
+            CANFD_MTU
+        } else {
+            raw.len = frame.data.len() as u8;
+            CAN_MTU
+        };
+
+        let n = unsafe {
+            libc::write(
+                self.fd.as_raw_fd(),
+                &raw as *const RawFrame as *const libc::c_void,
+                mtu,
+            )
+        };
+        if n < 0 {
+            return Err(format!(
+                "Failed to write CAN frame: {}",
+                io::Error::last_os_error()
+            ));
+        }
+        if n as usize != mtu {
+            return Err("Incomplete CAN frame write".into());
+        }
+        Ok(())
+    }
+
+    // 요청 프레임을 보내고 response_id 로 돌아오는 응답을 기다림
+    // 기다리는 동안 받은 다른 프레임은 other 에 모아 호출한 쪽이 이벤트/녹화로 넘기게 함
+    fn transact(
+        &self,
+        config: &CanRegisterConfig,
+        request: CanFrame,
+        other: &mut Vec<CanFrame>,
+    ) -> Result<Vec<u8>, String> {
+        let op = request.data[0];
+        self.write_frame(&request)?;
+
+        let deadline = Instant::now() + Duration::from_millis(config.timeout_ms);
+        while Instant::now() < deadline {
+            let frame = match self.read_frame() {
+                Ok(Some(frame)) => frame,
+                Ok(None) => continue,
+                Err(e) => return Err(format!("Failed to read CAN frame: {}", e)),
+            };
+            if frame.id != config.response_id || frame.extended != config.extended {
+                other.push(frame);
+                continue;
+            }
+            match frame.data.first() {
+                Some(&OP_ERROR) if frame.data.get(1) == Some(&op) => {
+                    let code = frame.data.get(2).copied().unwrap_or(0);
+                    return Err(format!(
+                        "CAN register request rejected (code 0x{:02X})",
+                        code
+                    ));
+                }
+                Some(&resp_op) if resp_op == op => return Ok(frame.data),
+                _ => other.push(frame),
+            }
+        }
+        Err(format!(
+            "CAN register response timed out after {} ms",
+            config.timeout_ms
+        ))
+    }
+
+    pub fn read_register(
+        &self,
+        config: &CanRegisterConfig,
+        address: u32,
+        other: &mut Vec<CanFrame>,
+    ) -> Result<u32, String> {
+        let data = self.transact(config, config.request(OP_READ, address, None)?, other)?;
+        let start = 1 + config.address_bytes as usize;
+        let end = start + config.value_bytes as usize;
+        if data.len() < end {
+            return Err("CAN register response is too short".into());
+        }
+        if read_be(&data[1..start]) != address & mask_for(config.address_bytes) {
+            return Err("CAN register response address mismatch".into());
+        }
+        Ok(read_be(&data[start..end]))
+    }
+
+    pub fn write_register(
+        &self,
+        config: &CanRegisterConfig,
+        address: u32,
+        value: u32,
+        other: &mut Vec<CanFrame>,
+    ) -> Result<(), String> {
+        self.transact(
+            config,
+            config.request(OP_WRITE, address, Some(value))?,
+            other,
+        )?;
+        Ok(())
+    }
+}
+
+fn mask_for(bytes: u8) -> u32 {
+    if bytes >= 4 {
+        u32::MAX
+    } else {
+        (1u32 << (bytes as u32 * 8)) - 1
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn dlc_to_len(dlc: u8) -> usize {
+    match dlc {
+        0..=8 => dlc as usize,
+        9 => 12,
+        10 => 16,
+        11 => 20,
+        12 => 24,
+        13 => 32,
+        14 => 48,
+        _ => 64,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_option(fd: &OwnedFd, name: libc::c_int, value: &libc::c_int) -> io::Result<()> {
+    let ret = unsafe {
+        libc::setsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_CAN_RAW,
+            name,
+            value as *const libc::c_int as *const libc::c_void,
+            mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+// /sys/class/net 에서 CAN 인터페이스(ARPHRD_CAN = 280) 목록 조회
+pub fn available_interfaces() -> Vec<String> {
+    let mut names = Vec::new();
+    if let Ok(entries) = std::fs::read_dir("/sys/class/net") {
+        for entry in entries.flatten() {
+            let kind = std::fs::read_to_string(entry.path().join("type")).unwrap_or_default();
+            if kind.trim() == "280" {
+                names.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+    }
+    names.sort();
+    names
+}
+
+// CanSocket + 디바이스별 설정
+#[cfg(target_os = "linux")]
+pub struct CanBridge {
+    pub socket: CanSocket,
+    pub register: Option<CanRegisterConfig>,
+}
+
+#[cfg(target_os = "linux")]
+impl CanBridge {
+    pub fn register_config(&self) -> Result<&CanRegisterConfig, String> {
+        self.register
+            .as_ref()
+            .ok_or_else(|| "CAN register mapping is not configured for this device".to_string())
+    }
+
+    // 텍스트 명령(VOLT:/FREQ: 등)은 요청 ID로 ASCII 페이로드를 나눠 전송
+    pub fn write_text(&self, text: &str) -> Result<(), String> {
+        let (id, extended, fd) = match &self.register {
+            Some(cfg) => (cfg.request_id, cfg.extended, cfg.fd),
+            None => return Err("CAN request ID is not configured for this device".into()),
+        };
+        let chunk = if fd { CANFD_MAX_DLEN } else { CAN_MAX_DLEN };
+        for part in text.as_bytes().chunks(chunk) {
+            self.socket.write_frame(&CanFrame {
+                id,
+                extended,
+                fd,
+                brs: false,
+                data: part.to_vec(),
+            })?;
+        }
+        Ok(())
+    }
+}
+
+// cansend 형식 문자열 파싱 ("123#DEADBEEF", "12345678#00", "123##1AABB")
+pub fn parse_frame(text: &str) -> Result<CanFrame, String> {
+    let text = text.trim();
+    let (id_part, rest) = text
+        .split_once('#')
+        .ok_or_else(|| format!("Invalid CAN frame '{}': expected <id>#<data>", text))?;
+    let id =
+        u32::from_str_radix(id_part, 16).map_err(|_| format!("Invalid CAN ID '{}'", id_part))?;
+    let extended = id_part.len() > 3;
+
+    let (fd, brs, data_part) = if let Some(fd_rest) = rest.strip_prefix('#') {
+        let mut chars = fd_rest.chars();
+        let flags = chars
+            .next()
+            .and_then(|c| c.to_digit(16))
+            .ok_or_else(|| "CAN-FD frame requires a flags nibble after '##'".to_string())?;
+        (true, flags & CANFD_BRS as u32 != 0, chars.as_str())
+    } else {
+        (false, false, rest)
+    };
+    let cleaned: String = data_part.chars().filter(|c| *c != '.').collect();
+    let data = hex::decode(&cleaned).map_err(|e| format!("Invalid CAN payload: {}", e))?;
+
+    let frame = CanFrame {
+        id,
+        extended,
+        fd,
+        brs,
+        data,
+    };
+    frame.validate()?;
+    Ok(frame)
+}
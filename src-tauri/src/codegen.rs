@@ -0,0 +1,524 @@
+// 레지스터 맵 산출물 생성
+// 저장된 레지스터 맵에서 펌웨어용 C 헤더, 테스트용 Python 모듈, 사람이 읽는 Markdown/HTML 레퍼런스를 생성
+use crate::diff::escape_html;
+use crate::register_map::{Conversion, Field, MapIssue, Register, RegisterMap};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+// C/Python 식별자: 영숫자 외 문자는 '_' 로, 숫자로 시작하면 '_' 를 앞에 붙임
+fn identifier(name: &str) -> String {
+    let mut id: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if id.is_empty() || id.starts_with(|c: char| c.is_ascii_digit()) {
+        id.insert(0, '_');
+    }
+    id
+}
+
+// C 헤더/Python 모듈이 정의하는 상수 이름 (접두사 제외)과 그 출처 (경로, 원래 이름)
+fn symbols(map: &RegisterMap) -> Vec<(String, String, String)> {
+    let mut out = Vec::new();
+    for (i, register) in map.registers.iter().enumerate() {
+        let path = format!("registers[{}]", i);
+        let name = identifier(&register.name);
+        let mut push = |suffix: &str| {
+            out.push((
+                format!("{}_{}", name, suffix),
+                path.clone(),
+                register.name.clone(),
+            ))
+        };
+        push("ADDR");
+        if register.page.is_some() {
+            push("PAGE");
+        }
+        if register.reset.is_some() {
+            push("RESET");
+        }
+        for (j, field) in register.fields.iter().enumerate() {
+            let path = format!("registers[{}].fields[{}]", i, j);
+            let label = format!("{}.{}", register.name, field.name);
+            let field_name = format!("{}_{}", name, identifier(&field.name));
+            for suffix in ["SHIFT", "WIDTH", "MASK"] {
+                out.push((
+                    format!("{}_{}", field_name, suffix),
+                    path.clone(),
+                    label.clone(),
+                ));
+            }
+            for entry in &field.enums {
+                out.push((
+                    format!("{}_{}", field_name, identifier(&entry.name)),
+                    path.clone(),
+                    format!("{}.{}", label, entry.name),
+                ));
+            }
+        }
+    }
+    out
+}
+
+// 서로 다른 이름이 식별자로 바뀌면서 같은 상수를 만들면 생성된 코드가 컴파일되지 않음
+// 완전히 같은 이름의 중복은 register_map::validate 가 이미 보고하므로 여기서는 건너뜀
+pub fn identifier_issues(map: &RegisterMap) -> Vec<MapIssue> {
+    let mut defined: HashMap<String, String> = HashMap::new();
+    let mut reported = HashSet::new();
+    let mut issues = Vec::new();
+    for (symbol, path, name) in symbols(map) {
+        match defined.get(&symbol) {
+            Some(other) if *other != name => {
+                // 한 항목에서 여러 상수가 겹쳐도 한 번만 보고
+                if reported.insert(path.clone()) {
+                    issues.push(MapIssue {
+                        line: None,
+                        column: None,
+                        path,
+                        message: format!(
+                            "'{}' generates identifier {} which is already defined by '{}'",
+                            name, symbol, other
+                        ),
+                    });
+                }
+            }
+            Some(_) => {}
+            None => {
+                defined.insert(symbol, name);
+            }
+        }
+    }
+    issues
+}
+
+fn bits(field: &Field) -> String {
+    if field.size > 1 {
+        format!("[{}:{}]", field.msb(), field.bit)
+    } else {
+        format!("[{}]", field.bit)
+    }
+}
+
+fn hex_digits(register: &Register) -> usize {
+    (register.width as usize).div_ceil(4).max(2)
+}
+
+// 32비트보다 넓은 레지스터의 상수는 unsigned long long
+fn c_suffix(register: &Register) -> &'static str {
+    if register.width > 32 {
+        "ull"
+    } else {
+        "u"
+    }
+}
+
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn c_header(map: &RegisterMap, prefix: &str, source: &str) -> String {
+    let prefix = if prefix.is_empty() {
+        String::new()
+    } else {
+        format!("{}_", identifier(prefix))
+    };
+    let guard = format!("{}REGISTERS_H", prefix);
+    let mut out = String::new();
+    let _ = writeln!(out, "/* Generated from {} - do not edit. */", source);
+    let _ = writeln!(out, "#ifndef {}\n#define {}\n", guard, guard);
+    for register in &map.registers {
+        let name = format!("{}{}", prefix, identifier(&register.name));
+        let digits = hex_digits(register);
+        let suffix = c_suffix(register);
+        let _ = writeln!(
+            out,
+            "/* {} ({}-bit, {}){} */",
+            register.name,
+            register.width,
+            register.access.label(),
+            match one_line(&register.description) {
+                desc if desc.is_empty() => String::new(),
+                desc => format!(": {}", desc.replace("*/", "* /")),
+            }
+        );
+        let _ = writeln!(out, "#define {}_ADDR 0x{:04X}u", name, register.address);
+        if let Some(page) = register.page {
+            let _ = writeln!(out, "#define {}_PAGE {}u", name, page);
+        }
+        if let Some(reset) = register.reset {
+            let _ = writeln!(
+                out,
+                "#define {}_RESET 0x{:0w$X}{}",
+                name,
+                reset,
+                suffix,
+                w = digits
+            );
+        }
+        for field in &register.fields {
+            let field_name = format!("{}_{}", name, identifier(&field.name));
+            let _ = writeln!(out, "#define {}_SHIFT {}u", field_name, field.bit);
+            let _ = writeln!(out, "#define {}_WIDTH {}u", field_name, field.size);
+            let _ = writeln!(
+                out,
+                "#define {}_MASK 0x{:0w$X}{}",
+                field_name,
+                field.mask(),
+                suffix,
+                w = digits
+            );
+            for entry in &field.enums {
+                let _ = writeln!(
+                    out,
+                    "#define {}_{} 0x{:X}{}",
+                    field_name,
+                    identifier(&entry.name),
+                    entry.value,
+                    suffix
+                );
+            }
+        }
+        out.push('\n');
+    }
+    let _ = writeln!(out, "#endif /* {} */", guard);
+    out
+}
+
+fn python_string(text: &str) -> String {
+    format!(
+        "\"{}\"",
+        text.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+pub fn python_module(map: &RegisterMap, source: &str) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Generated from {} - do not edit.\n", source);
+    for register in &map.registers {
+        let name = identifier(&register.name);
+        let digits = hex_digits(register);
+        let _ = writeln!(
+            out,
+            "# {} ({}-bit, {})",
+            register.name,
+            register.width,
+            register.access.label()
+        );
+        let _ = writeln!(out, "{}_ADDR = 0x{:04X}", name, register.address);
+        if let Some(page) = register.page {
+            let _ = writeln!(out, "{}_PAGE = {}", name, page);
+        }
+        if let Some(reset) = register.reset {
+            let _ = writeln!(out, "{}_RESET = 0x{:0w$X}", name, reset, w = digits);
+        }
+        for field in &register.fields {
+            let field_name = format!("{}_{}", name, identifier(&field.name));
+            let _ = writeln!(out, "{}_SHIFT = {}", field_name, field.bit);
+            let _ = writeln!(out, "{}_WIDTH = {}", field_name, field.size);
+            let _ = writeln!(
+                out,
+                "{}_MASK = 0x{:0w$X}",
+                field_name,
+                field.mask(),
+                w = digits
+            );
+            for entry in &field.enums {
+                let _ = writeln!(
+                    out,
+                    "{}_{} = 0x{:X}",
+                    field_name,
+                    identifier(&entry.name),
+                    entry.value
+                );
+            }
+        }
+        out.push('\n');
+    }
+
+    // 이름으로 찾아 쓰는 테스트 스크립트용 표
+    out.push_str("REGISTERS = {\n");
+    for register in &map.registers {
+        let _ = writeln!(out, "    {}: {{", python_string(&register.name));
+        let _ = writeln!(out, "        \"address\": 0x{:04X},", register.address);
+        let _ = writeln!(out, "        \"width\": {},", register.width);
+        let _ = writeln!(
+            out,
+            "        \"access\": {},",
+            python_string(register.access.label())
+        );
+        let _ = writeln!(
+            out,
+            "        \"reset\": {},",
+            register
+                .reset
+                .map(|reset| format!("0x{:X}", reset))
+                .unwrap_or_else(|| "None".into())
+        );
+        out.push_str("        \"fields\": {\n");
+        for field in &register.fields {
+            let _ = writeln!(
+                out,
+                "            {}: {{\"bit\": {}, \"width\": {}, \"access\": {}}},",
+                python_string(&field.name),
+                field.bit,
+                field.size,
+                python_string(field.effective_access(register).label())
+            );
+        }
+        out.push_str("        },\n    },\n");
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn reset_text(register: &Register, field: Option<&Field>) -> String {
+    match field {
+        Some(field) => field
+            .reset
+            .map(|reset| format!("0x{:X}", reset))
+            .unwrap_or_else(|| "-".into()),
+        None => register
+            .reset
+            .map(|reset| format!("0x{:0w$X}", reset, w = hex_digits(register)))
+            .unwrap_or_else(|| "-".into()),
+    }
+}
+
+fn field_description(field: &Field) -> String {
+    let mut text = one_line(&field.description);
+    if !field.enums.is_empty() {
+        let values: Vec<String> = field
+            .enums
+            .iter()
+            .map(|e| format!("{} = {}", e.value, e.name))
+            .collect();
+        if !text.is_empty() {
+            text.push_str(" — ");
+        }
+        text.push_str(&values.join(", "));
+    }
+    let conversion = match &field.conversion {
+        Some(Conversion::Linear { unit, offset, step }) => {
+            format!("{} {} + code × {} {}", offset, unit, step, unit)
+        }
+        Some(Conversion::Lookup { unit, table }) => table
+            .iter()
+            .map(|e| format!("{} = {} {}", e.raw, e.value, unit))
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => String::new(),
+    };
+    if !conversion.is_empty() {
+        if !text.is_empty() {
+            text.push_str(" — ");
+        }
+        text.push_str(conversion.trim_end());
+    }
+    text
+}
+
+// 페이지 레지스터가 하나라도 있으면 표에 Page 열을 넣음
+fn has_pages(map: &RegisterMap) -> bool {
+    map.registers.iter().any(|r| r.page.is_some())
+}
+
+fn page_text(register: &Register) -> String {
+    register
+        .page
+        .map(|page| page.to_string())
+        .unwrap_or_else(|| "-".into())
+}
+
+// 레지스터 상세 제목 아래 줄 ("Address 0x0010, page 1, 8-bit, RW, reset 0x00")
+fn summary_line(register: &Register) -> String {
+    let page = register
+        .page
+        .map(|page| format!(", page {}", page))
+        .unwrap_or_default();
+    format!(
+        "Address 0x{:04X}{}, {}-bit, {}, reset {}",
+        register.address,
+        page,
+        register.width,
+        register.access.label(),
+        reset_text(register, None)
+    )
+}
+
+// HTML 앵커: 같은 주소가 여러 페이지에 있을 수 있으므로 페이지도 포함
+fn html_anchor(register: &Register) -> String {
+    match register.page {
+        Some(page) => format!("reg-p{}-{:X}", page, register.address),
+        None => format!("reg-{:X}", register.address),
+    }
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+pub fn markdown(map: &RegisterMap, title: &str) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}\n", title);
+    let paged = has_pages(map);
+    if paged {
+        out.push_str("| Address | Page | Register | Width | Access | Reset | Description |\n");
+        out.push_str("|---|---|---|---|---|---|---|\n");
+    } else {
+        out.push_str("| Address | Register | Width | Access | Reset | Description |\n");
+        out.push_str("|---|---|---|---|---|---|\n");
+    }
+    for register in &map.registers {
+        let page = if paged {
+            format!(" {} |", page_text(register))
+        } else {
+            String::new()
+        };
+        let _ = writeln!(
+            out,
+            "| 0x{:04X} |{} [{}](#{}) | {} | {} | {} | {} |",
+            register.address,
+            page,
+            markdown_cell(&register.name),
+            register.name.to_lowercase(),
+            register.width,
+            register.access.label(),
+            reset_text(register, None),
+            markdown_cell(&one_line(&register.description))
+        );
+    }
+    for register in &map.registers {
+        let _ = writeln!(
+            out,
+            "\n## {}\n\n{}\n",
+            register.name,
+            summary_line(register)
+        );
+        if !register.description.trim().is_empty() {
+            let _ = writeln!(out, "{}\n", one_line(&register.description));
+        }
+        if register.fields.is_empty() {
+            continue;
+        }
+        out.push_str("| Bits | Field | Access | Reset | Description |\n");
+        out.push_str("|---|---|---|---|---|\n");
+        for field in register.fields.iter().rev() {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} |",
+                bits(field),
+                markdown_cell(&field.name),
+                field.effective_access(register).label(),
+                reset_text(register, Some(field)),
+                markdown_cell(&field_description(field))
+            );
+        }
+    }
+    out
+}
+
+pub fn html(map: &RegisterMap, title: &str) -> String {
+    let title = escape_html(title);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>",
+        title
+    );
+    out.push_str(
+        "<style>\
+body{font-family:sans-serif;font-size:14px}\
+table{border-collapse:collapse;margin-bottom:16px}\
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}\
+td.mono{font-family:monospace}\
+</style>\n</head>\n<body>\n",
+    );
+    let _ = writeln!(out, "<h1>{}</h1>", title);
+    let paged = has_pages(map);
+    let _ = writeln!(
+        out,
+        "<table>\n<tr><th>Address</th>{}<th>Register</th><th>Width</th><th>Access</th><th>Reset</th><th>Description</th></tr>",
+        if paged { "<th>Page</th>" } else { "" }
+    );
+    for register in &map.registers {
+        let page = if paged {
+            format!("<td>{}</td>", page_text(register))
+        } else {
+            String::new()
+        };
+        let _ = writeln!(
+            out,
+            "<tr><td class=\"mono\">0x{:04X}</td>{}<td><a href=\"#{}\">{}</a></td><td>{}</td><td>{}</td><td class=\"mono\">{}</td><td>{}</td></tr>",
+            register.address,
+            page,
+            html_anchor(register),
+            escape_html(&register.name),
+            register.width,
+            register.access.label(),
+            reset_text(register, None),
+            escape_html(&one_line(&register.description))
+        );
+    }
+    out.push_str("</table>\n");
+    for register in &map.registers {
+        let _ = writeln!(
+            out,
+            "<h2 id=\"{}\">{}</h2>\n<p>{}</p>",
+            html_anchor(register),
+            escape_html(&register.name),
+            summary_line(register)
+        );
+        if !register.description.trim().is_empty() {
+            let _ = writeln!(out, "<p>{}</p>", escape_html(&register.description));
+        }
+        if register.fields.is_empty() {
+            continue;
+        }
+        out.push_str(
+            "<table>\n<tr><th>Bits</th><th>Field</th><th>Access</th><th>Reset</th><th>Description</th></tr>\n",
+        );
+        for field in register.fields.iter().rev() {
+            let _ = writeln!(
+                out,
+                "<tr><td class=\"mono\">{}</td><td>{}</td><td>{}</td><td class=\"mono\">{}</td><td>{}</td></tr>",
+                bits(field),
+                escape_html(&field.name),
+                field.effective_access(register).label(),
+                reset_text(register, Some(field)),
+                escape_html(&field_description(field))
+            );
+        }
+        out.push_str("</table>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+// format: "c" | "python" | "markdown" | "html"
+pub fn render(
+    map: &RegisterMap,
+    format: &str,
+    prefix: &str,
+    source: &str,
+) -> Result<String, String> {
+    let title = if prefix.is_empty() {
+        "Register reference".to_string()
+    } else {
+        format!("{} register reference", prefix)
+    };
+    match format {
+        "c" | "h" => Ok(c_header(map, prefix, source)),
+        "python" | "py" => Ok(python_module(map, source)),
+        "markdown" | "md" => Ok(markdown(map, &title)),
+        "html" => Ok(html(map, &title)),
+        _ => Err(format!("Unsupported export format: {}", format)),
+    }
+}
@@ -0,0 +1,318 @@
+// LLM 제공자 자격 증명 저장소
+// 앱 데이터 디렉터리의 credentials/ 에 제공자 ID 별 API 키를 AES-256-GCM 으로 암호화해 저장
+// 암호화 키는 처음 실행할 때 만든 무작위 키 파일과 머신 ID 로 만들어, 파일만 다른 PC 로 복사하면 풀 수 없음
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const STORE_FILE: &str = "credentials.json";
+const KEY_FILE: &str = "credentials.key";
+const KEY_LEN: usize = 32;
+const KEY_CONTEXT: &[u8] = b"ic-controller credential store v1";
+const MAX_ID_LEN: usize = 64;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredCredential {
+    // hex
+    nonce: String,
+    ciphertext: String,
+    updated: u64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoreFile {
+    // 암호화 키에 머신 ID 를 섞었는지 (이전 버전 파일은 None)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    machine_bound: Option<bool>,
+    #[serde(default)]
+    credentials: BTreeMap<String, StoredCredential>,
+}
+
+// 저장된 자격 증명 목록 (키 자체는 돌려주지 않음)
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialInfo {
+    pub provider_id: String,
+    // 키 끝 4자리 (복호화할 수 없으면 None)
+    pub hint: Option<String>,
+    pub updated: u64,
+}
+
+pub struct CredentialStore {
+    // 앱 시작(setup) 전이나 열기에 실패하면 None
+    dir: Option<PathBuf>,
+    cipher: Option<Aes256Gcm>,
+    store: StoreFile,
+}
+
+impl CredentialStore {
+    pub fn new() -> Self {
+        Self {
+            dir: None,
+            cipher: None,
+            store: StoreFile::default(),
+        }
+    }
+
+    pub fn open(dir: PathBuf) -> Result<Self, String> {
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create credential directory: {}", e))?;
+        let key = load_or_create_key(&dir.join(KEY_FILE))?;
+        let path = dir.join(STORE_FILE);
+        let mut store: StoreFile = if path.exists() {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?
+        } else {
+            StoreFile::default()
+        };
+        // 처음 정한 키 방식을 계속 사용. 머신 ID 를 잠시 읽지 못했다고 다른 키로 열면
+        // 저장된 키를 모두 복호화할 수 없게 되므로 그때는 열기에 실패함
+        let machine = machine_id();
+        let recorded = store.machine_bound;
+        let bound = recorded.unwrap_or(machine.is_some());
+        if bound && machine.is_none() {
+            return Err(
+                "Machine ID could not be read; stored credentials cannot be decrypted until it is available"
+                    .to_string(),
+            );
+        }
+        store.machine_bound = Some(bound);
+        let credentials = Self {
+            dir: Some(dir),
+            cipher: Some(Aes256Gcm::new(&derive_key(
+                &key,
+                machine.as_deref().filter(|_| bound),
+            ))),
+            store,
+        };
+        if recorded.is_none() {
+            credentials.save()?;
+        }
+        Ok(credentials)
+    }
+
+    fn cipher(&self) -> Result<&Aes256Gcm, String> {
+        self.cipher
+            .as_ref()
+            .ok_or_else(|| "Credential store is not available".to_string())
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let dir = self
+            .dir
+            .as_ref()
+            .ok_or_else(|| "Credential store is not available".to_string())?;
+        let path = dir.join(STORE_FILE);
+        let json = serde_json::to_string_pretty(&self.store)
+            .map_err(|e| format!("Failed to serialize credentials: {}", e))?;
+        fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn set(&mut self, provider_id: &str, secret: &str) -> Result<CredentialInfo, String> {
+        let id = valid_id(provider_id)?;
+        let secret = secret.trim();
+        if secret.is_empty() {
+            return Err("API key is empty".to_string());
+        }
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        // 제공자 ID 를 추가 인증 데이터로 써서 항목을 다른 ID 로 옮기면 복호화되지 않게 함
+        let ciphertext = self
+            .cipher()?
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: secret.as_bytes(),
+                    aad: id.as_bytes(),
+                },
+            )
+            .map_err(|_| "Failed to encrypt credential".to_string())?;
+        let updated = chrono::Local::now().timestamp_millis().max(0) as u64;
+        self.store.credentials.insert(
+            id.clone(),
+            StoredCredential {
+                nonce: hex::encode(nonce),
+                ciphertext: hex::encode(ciphertext),
+                updated,
+            },
+        );
+        self.save()?;
+        Ok(CredentialInfo {
+            provider_id: id,
+            hint: Some(hint(secret)),
+            updated,
+        })
+    }
+
+    // 저장된 키가 없으면 None (키 없이 쓰는 로컬 서버)
+    pub fn get(&self, provider_id: &str) -> Result<Option<String>, String> {
+        let id = provider_id.trim();
+        self.store
+            .credentials
+            .get(id)
+            .map(|stored| self.decrypt(id, stored))
+            .transpose()
+    }
+
+    fn decrypt(&self, id: &str, stored: &StoredCredential) -> Result<String, String> {
+        let corrupt = || format!("Stored credential for provider '{}' is corrupt", id);
+        let nonce = hex::decode(&stored.nonce).map_err(|_| corrupt())?;
+        let ciphertext = hex::decode(&stored.ciphertext).map_err(|_| corrupt())?;
+        if nonce.len() != 12 {
+            return Err(corrupt());
+        }
+        let plain = self
+            .cipher()?
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: id.as_bytes(),
+                },
+            )
+            .map_err(|_| {
+                format!(
+                    "Stored credential for provider '{}' cannot be decrypted on this machine; set it again",
+                    id
+                )
+            })?;
+        String::from_utf8(plain).map_err(|_| corrupt())
+    }
+
+    // 없던 ID 면 false
+    pub fn remove(&mut self, provider_id: &str) -> Result<bool, String> {
+        if self.store.credentials.remove(provider_id.trim()).is_none() {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    pub fn list(&self) -> Vec<CredentialInfo> {
+        self.store
+            .credentials
+            .iter()
+            .map(|(id, stored)| CredentialInfo {
+                provider_id: id.clone(),
+                hint: self.decrypt(id, stored).ok().map(|secret| hint(&secret)),
+                updated: stored.updated,
+            })
+            .collect()
+    }
+}
+
+fn valid_id(provider_id: &str) -> Result<String, String> {
+    let id = provider_id.trim();
+    if id.is_empty() {
+        return Err("Provider ID is empty".to_string());
+    }
+    if id.len() > MAX_ID_LEN || id.chars().any(char::is_control) {
+        return Err(format!("Invalid provider ID '{}'", id));
+    }
+    Ok(id.to_string())
+}
+
+fn hint(secret: &str) -> String {
+    let tail: String = secret
+        .chars()
+        .rev()
+        .take(4)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    format!("…{}", tail)
+}
+
+// 키 파일 (없으면 무작위로 만들고 소유자만 읽을 수 있게 함)
+fn load_or_create_key(path: &Path) -> Result<Vec<u8>, String> {
+    if path.exists() {
+        let key = fs::read(path).map_err(|e| format!("Failed to read credential key: {}", e))?;
+        if key.len() != KEY_LEN {
+            return Err(format!("Credential key {} is invalid", path.display()));
+        }
+        return Ok(key);
+    }
+    let key = Aes256Gcm::generate_key(OsRng).to_vec();
+    write_private(path, &key).map_err(|e| format!("Failed to write credential key: {}", e))?;
+    Ok(key)
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(data)
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    fs::write(path, data)
+}
+
+// machine_id 가 None 이면 키 파일만 사용 (머신 ID 를 얻지 못하는 환경에서 만든 저장소)
+fn derive_key(file_key: &[u8], machine_id: Option<&str>) -> Key<Aes256Gcm> {
+    let mut hasher = Sha256::new();
+    hasher.update(KEY_CONTEXT);
+    hasher.update(file_key);
+    if let Some(id) = machine_id {
+        hasher.update(id.as_bytes());
+    }
+    hasher.finalize()
+}
+
+#[cfg(target_os = "linux")]
+fn machine_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .find(|id| !id.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn machine_id() -> Option<String> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let line = text.lines().find(|line| line.contains("IOPlatformUUID"))?;
+    line.split('"').nth(3).map(str::to_string)
+}
+
+#[cfg(windows)]
+fn machine_id() -> Option<String> {
+    use std::os::windows::process::CommandExt;
+    // CREATE_NO_WINDOW: 콘솔 창을 띄우지 않음
+    let output = std::process::Command::new("reg")
+        .creation_flags(0x0800_0000)
+        .args([
+            "query",
+            r"HKLM\SOFTWARE\Microsoft\Cryptography",
+            "/v",
+            "MachineGuid",
+        ])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let line = text.lines().find(|line| line.contains("MachineGuid"))?;
+    line.split_whitespace().last().map(str::to_string)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn machine_id() -> Option<String> {
+    None
+}
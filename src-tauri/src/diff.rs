@@ -0,0 +1,307 @@
+// 스냅샷 비교
+// 두 칩 상태(스냅샷 또는 스냅샷과 라이브 칩)를 필드 단위까지 비교하고 텍스트/HTML 보고서로 내보냄
+use crate::register_map::RegisterMap;
+use crate::snapshot::{RegisterRead, Snapshot};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    pub name: String,
+    pub bit: u32,
+    pub size: u32,
+    #[serde(serialize_with = "crate::register_map::ser_hex")]
+    pub old: u64,
+    #[serde(serialize_with = "crate::register_map::ser_hex")]
+    pub new: u64,
+    pub old_name: Option<String>,
+    pub new_name: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterChange {
+    pub address: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    pub name: String,
+    // "changed" | "onlyLeft" | "onlyRight"
+    pub status: String,
+    #[serde(serialize_with = "crate::register_map::ser_opt_hex")]
+    pub old: Option<u64>,
+    #[serde(serialize_with = "crate::register_map::ser_opt_hex")]
+    pub new: Option<u64>,
+    pub fields: Vec<FieldChange>,
+    // 어떤 필드에도 속하지 않는 바뀐 비트
+    #[serde(serialize_with = "crate::register_map::ser_hex")]
+    pub unmapped_bits: u64,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotDiff {
+    pub left: String,
+    pub right: String,
+    pub left_created: String,
+    pub right_created: String,
+    pub compared: usize,
+    pub changed: usize,
+    pub only_left: usize,
+    pub only_right: usize,
+    pub changes: Vec<RegisterChange>,
+}
+
+// 값이 있는 레지스터만 비교. 한쪽에만 값이 있으면 onlyLeft/onlyRight 로 보고
+pub fn diff(left: &Snapshot, right: &Snapshot, map: Option<&RegisterMap>) -> SnapshotDiff {
+    // 페이지가 다른 같은 주소는 다른 레지스터
+    let values = |snapshot: &Snapshot| -> BTreeMap<(u32, Option<u32>), RegisterRead> {
+        snapshot
+            .registers
+            .iter()
+            .filter(|r| r.value.is_some())
+            .map(|r| ((r.address, r.page), r.clone()))
+            .collect()
+    };
+    let left_values = values(left);
+    let right_values = values(right);
+
+    let mut keys: Vec<(u32, Option<u32>)> = left_values
+        .keys()
+        .chain(right_values.keys())
+        .copied()
+        .collect();
+    keys.sort_unstable();
+    keys.dedup();
+
+    let mut changes = Vec::new();
+    let mut compared = 0;
+    for (address, page) in keys {
+        let old = left_values.get(&(address, page));
+        let new = right_values.get(&(address, page));
+        let register = map
+            .and_then(|m| m.register_in_page(address, page))
+            .filter(|r| r.on_page(page));
+        let name = register
+            .map(|r| r.name.clone())
+            .or_else(|| old.or(new).map(|r| r.name.clone()))
+            .unwrap_or_default();
+        let mut change = RegisterChange {
+            address,
+            page,
+            name,
+            status: "changed".into(),
+            old: old.and_then(|r| r.value),
+            new: new.and_then(|r| r.value),
+            fields: Vec::new(),
+            unmapped_bits: 0,
+        };
+        let (old_value, new_value) = match (change.old, change.new) {
+            (Some(old_value), Some(new_value)) => (old_value, new_value),
+            (Some(_), None) => {
+                change.status = "onlyLeft".into();
+                changes.push(change);
+                continue;
+            }
+            _ => {
+                change.status = "onlyRight".into();
+                changes.push(change);
+                continue;
+            }
+        };
+        compared += 1;
+        if old_value == new_value {
+            continue;
+        }
+
+        let flipped = old_value ^ new_value;
+        let mut covered = 0;
+        if let Some(register) = register {
+            for field in &register.fields {
+                covered |= field.mask();
+                if flipped & field.mask() == 0 {
+                    continue;
+                }
+                let old = field.extract(old_value);
+                let new = field.extract(new_value);
+                change.fields.push(FieldChange {
+                    name: field.name.clone(),
+                    bit: field.bit,
+                    size: field.size,
+                    old,
+                    new,
+                    old_name: field.enum_name(old).map(str::to_string),
+                    new_name: field.enum_name(new).map(str::to_string),
+                });
+            }
+        }
+        change.unmapped_bits = flipped & !covered;
+        changes.push(change);
+    }
+
+    let count = |status: &str| changes.iter().filter(|c| c.status == status).count();
+    SnapshotDiff {
+        left: left.name.clone(),
+        right: right.name.clone(),
+        left_created: left.created.clone(),
+        right_created: right.created.clone(),
+        compared,
+        changed: count("changed"),
+        only_left: count("onlyLeft"),
+        only_right: count("onlyRight"),
+        changes,
+    }
+}
+
+fn hex(value: Option<u64>) -> String {
+    value
+        .map(|v| format!("0x{:02X}", v))
+        .unwrap_or_else(|| "-".into())
+}
+
+fn field_value(value: u64, name: &Option<String>) -> String {
+    match name {
+        Some(name) => format!("{} ({})", value, name),
+        None => value.to_string(),
+    }
+}
+
+fn field_range(field: &FieldChange) -> String {
+    if field.size > 1 {
+        format!("[{}:{}]", field.bit + field.size - 1, field.bit)
+    } else {
+        format!("[{}]", field.bit)
+    }
+}
+
+pub fn to_text(diff: &SnapshotDiff) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Register diff: {} -> {}", diff.left, diff.right);
+    let _ = writeln!(
+        out,
+        "Captured: {} / {}",
+        diff.left_created, diff.right_created
+    );
+    let _ = writeln!(
+        out,
+        "Compared {} registers: {} changed, {} only in {}, {} only in {}",
+        diff.compared, diff.changed, diff.only_left, diff.left, diff.only_right, diff.right
+    );
+    for change in &diff.changes {
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "0x{:02X} {}: {} -> {}{}",
+            change.address,
+            change.name,
+            hex(change.old),
+            hex(change.new),
+            match change.status.as_str() {
+                "onlyLeft" => format!(" (missing in {})", diff.right),
+                "onlyRight" => format!(" (missing in {})", diff.left),
+                _ => String::new(),
+            }
+        );
+        for field in &change.fields {
+            let _ = writeln!(
+                out,
+                "    {}{}: {} -> {}",
+                field.name,
+                field_range(field),
+                field_value(field.old, &field.old_name),
+                field_value(field.new, &field.new_name)
+            );
+        }
+        if change.unmapped_bits != 0 {
+            let _ = writeln!(out, "    unmapped bits: 0x{:X}", change.unmapped_bits);
+        }
+    }
+    out
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn to_html(diff: &SnapshotDiff) -> String {
+    let title = format!(
+        "Register diff: {} &rarr; {}",
+        escape_html(&diff.left),
+        escape_html(&diff.right)
+    );
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>",
+        title
+    );
+    out.push_str(
+        "<style>\
+body{font-family:sans-serif;font-size:14px}\
+table{border-collapse:collapse}\
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}\
+td.mono{font-family:monospace}\
+tr.onlyLeft,tr.onlyRight{color:#a60}\
+</style>\n</head>\n<body>\n",
+    );
+    let _ = writeln!(out, "<h1>{}</h1>", title);
+    let _ = writeln!(
+        out,
+        "<p>Captured {} / {}<br>Compared {} registers: {} changed, {} only in {}, {} only in {}</p>",
+        escape_html(&diff.left_created),
+        escape_html(&diff.right_created),
+        diff.compared,
+        diff.changed,
+        diff.only_left,
+        escape_html(&diff.left),
+        diff.only_right,
+        escape_html(&diff.right)
+    );
+    out.push_str(
+        "<table>\n<tr><th>Address</th><th>Register</th><th>Old</th><th>New</th><th>Fields</th></tr>\n",
+    );
+    for change in &diff.changes {
+        let mut fields: Vec<String> = change
+            .fields
+            .iter()
+            .map(|field| {
+                format!(
+                    "{}{}: {} &rarr; {}",
+                    escape_html(&field.name),
+                    field_range(field),
+                    escape_html(&field_value(field.old, &field.old_name)),
+                    escape_html(&field_value(field.new, &field.new_name))
+                )
+            })
+            .collect();
+        if change.unmapped_bits != 0 {
+            fields.push(format!("unmapped bits: 0x{:X}", change.unmapped_bits));
+        }
+        let _ = writeln!(
+            out,
+            "<tr class=\"{}\"><td class=\"mono\">0x{:02X}</td><td>{}</td><td class=\"mono\">{}</td><td class=\"mono\">{}</td><td>{}</td></tr>",
+            change.status,
+            change.address,
+            escape_html(&change.name),
+            hex(change.old),
+            hex(change.new),
+            fields.join("<br>")
+        );
+    }
+    out.push_str("</table>\n</body>\n</html>\n");
+    out
+}
+
+// format: "json" | "text" | "html"
+pub fn render(diff: &SnapshotDiff, format: &str) -> Result<String, String> {
+    match format {
+        "json" => serde_json::to_string_pretty(diff).map_err(|e| e.to_string()),
+        "text" | "txt" => Ok(to_text(diff)),
+        "html" => Ok(to_html(diff)),
+        _ => Err(format!("Unsupported diff format: {}", format)),
+    }
+}
@@ -0,0 +1,219 @@
+// 레지스터 트랜잭션 히스토리
+// read_register / write_register / set_register 호출을 메모리에 누적하고 CSV / JSON Lines 로 내보냄
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+// 메모리에 유지하는 최대 트랜잭션 수
+const HISTORY_CAPACITY: usize = 100_000;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+    pub id: u64,
+    pub timestamp: String,
+    pub timestamp_ms: u64,
+    pub op: String,
+    // 접근하기 전에 선택한 페이지 (페이지 없는 접근은 None)
+    pub page: Option<u32>,
+    pub address: Option<u32>,
+    pub value: Option<u32>,
+    pub old_value: Option<u32>,
+    pub success: bool,
+    pub error: Option<String>,
+    pub latency_us: u64,
+    pub transport: String,
+    pub access: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryFilter {
+    pub op: Option<String>,
+    pub page: Option<u32>,
+    pub address: Option<u32>,
+    pub address_min: Option<u32>,
+    pub address_max: Option<u32>,
+    pub since_ms: Option<u64>,
+    pub until_ms: Option<u64>,
+    pub success: Option<bool>,
+    pub transport: Option<String>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+impl HistoryFilter {
+    fn matches(&self, tx: &Transaction) -> bool {
+        if self.op.as_ref().is_some_and(|op| op != &tx.op) {
+            return false;
+        }
+        if self.page.is_some() && self.page != tx.page {
+            return false;
+        }
+        if self.address.is_some() && self.address != tx.address {
+            return false;
+        }
+        if let Some(min) = self.address_min {
+            if tx.address.is_none_or(|a| a < min) {
+                return false;
+            }
+        }
+        if let Some(max) = self.address_max {
+            if tx.address.is_none_or(|a| a > max) {
+                return false;
+            }
+        }
+        if self.since_ms.is_some_and(|since| tx.timestamp_ms < since) {
+            return false;
+        }
+        if self.until_ms.is_some_and(|until| tx.timestamp_ms > until) {
+            return false;
+        }
+        if self.success.is_some_and(|success| success != tx.success) {
+            return false;
+        }
+        if self
+            .transport
+            .as_ref()
+            .is_some_and(|transport| transport != &tx.transport)
+        {
+            return false;
+        }
+        true
+    }
+}
+
+pub struct NewTransaction<'a> {
+    pub op: &'a str,
+    pub page: Option<u32>,
+    pub address: Option<u32>,
+    pub value: Option<u32>,
+    pub error: Option<String>,
+    pub latency_us: u64,
+    pub transport: &'a str,
+}
+
+pub struct TransactionHistory {
+    entries: VecDeque<Transaction>,
+    next_id: u64,
+    // (주소, 페이지)별 마지막으로 알려진 값 (old value 계산용)
+    last_values: HashMap<(u32, Option<u32>), u32>,
+}
+
+impl TransactionHistory {
+    pub fn new() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            next_id: 1,
+            last_values: HashMap::new(),
+        }
+    }
+
+    pub fn push(&mut self, tx: NewTransaction, access: Option<&str>) {
+        let success = tx.error.is_none();
+        let old_value = tx
+            .address
+            .and_then(|a| self.last_values.get(&(a, tx.page)).copied());
+        if let (true, Some(address), Some(value)) = (success, tx.address, tx.value) {
+            self.last_values.insert((address, tx.page), value);
+        }
+
+        let now = chrono::Local::now();
+        let entry = Transaction {
+            id: self.next_id,
+            timestamp: now.to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+            timestamp_ms: now.timestamp_millis().max(0) as u64,
+            op: tx.op.to_string(),
+            page: tx.page,
+            address: tx.address,
+            value: tx.value,
+            old_value,
+            success,
+            error: tx.error,
+            latency_us: tx.latency_us,
+            transport: tx.transport.to_string(),
+            access: access.map(str::to_string),
+        };
+        self.next_id += 1;
+        if self.entries.len() >= HISTORY_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    pub fn query(&self, filter: &HistoryFilter) -> Vec<Transaction> {
+        self.entries
+            .iter()
+            .filter(|tx| filter.matches(tx))
+            .skip(filter.offset.unwrap_or(0))
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.last_values.clear();
+    }
+}
+
+fn hex_opt(value: Option<u32>) -> String {
+    value.map(|v| format!("0x{:02X}", v)).unwrap_or_default()
+}
+
+fn csv_escape(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+// format: "csv" | "jsonl"
+pub fn export(path: &Path, format: &str, transactions: &[Transaction]) -> Result<(), String> {
+    // 지원하지 않는 형식이면 파일을 만들기 전에 거부 (기존 파일을 빈 파일로 덮어쓰지 않도록)
+    let jsonl = match format {
+        "csv" => false,
+        "jsonl" | "json" => true,
+        _ => return Err(format!("Unsupported export format: {}", format)),
+    };
+    let file =
+        File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut out = BufWriter::new(file);
+    let io_err = |e: std::io::Error| format!("Failed to write history: {}", e);
+
+    if jsonl {
+        for tx in transactions {
+            let line = serde_json::to_string(tx).map_err(|e| e.to_string())?;
+            writeln!(out, "{}", line).map_err(io_err)?;
+        }
+    } else {
+        writeln!(
+            out,
+            "id,timestamp,op,page,address,value,old_value,success,error,latency_us,transport,access"
+        )
+        .map_err(io_err)?;
+        for tx in transactions {
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{},{},{},{}",
+                tx.id,
+                tx.timestamp,
+                tx.op,
+                tx.page.map(|page| page.to_string()).unwrap_or_default(),
+                hex_opt(tx.address),
+                hex_opt(tx.value),
+                hex_opt(tx.old_value),
+                tx.success,
+                csv_escape(tx.error.as_deref().unwrap_or("")),
+                tx.latency_us,
+                csv_escape(&tx.transport),
+                tx.access.as_deref().unwrap_or("")
+            )
+            .map_err(io_err)?;
+        }
+    }
+    out.flush().map_err(io_err)
+}
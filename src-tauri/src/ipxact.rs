@@ -0,0 +1,669 @@
+// IP-XACT 가져오기
+// component/memoryMaps/memoryMap/addressBlock/register/field 를 앱 레지스터 모델로 변환
+// 1685-2009/2014/2022 의 access, reset, modifiedWriteValue, readAction, enumeratedValues 를 지원하고
+// 변환하지 못한 구성(dim, registerFile, bank 등)은 경고로 보고
+use crate::register_map::{
+    bit_mask, Access, Field, FieldEnum, MapIssue, Register, RegisterMap, SUPPORTED_WIDTHS,
+};
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+
+// 최소한의 XML 트리 (네임스페이스 접두사는 버림)
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    text: String,
+    children: Vec<Element>,
+    line: usize,
+}
+
+impl Element {
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name).map(|c| c.text.trim())
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    // 직접 자식 또는 2022 형식의 <policies><policy> 아래 자식
+    fn policy(&self, name: &str, policies: &str, policy: &str) -> Option<&Element> {
+        self.child(name).or_else(|| {
+            self.child(policies).and_then(|p| {
+                p.children
+                    .iter()
+                    .filter(|c| c.name == policy)
+                    .find_map(|p| p.child(name))
+            })
+        })
+    }
+}
+
+fn xml_issue(line: usize, message: String) -> MapIssue {
+    MapIssue {
+        line: Some(line),
+        column: None,
+        path: String::new(),
+        message,
+    }
+}
+
+// 바이트 위치 -> 줄 번호 (앞으로만 이동하므로 누적 계산)
+struct LineCounter<'a> {
+    text: &'a str,
+    position: usize,
+    line: usize,
+}
+
+impl LineCounter<'_> {
+    fn line_at(&mut self, position: usize) -> usize {
+        let position = position.min(self.text.len());
+        if position > self.position {
+            self.line += self.text.as_bytes()[self.position..position]
+                .iter()
+                .filter(|&&b| b == b'\n')
+                .count();
+            self.position = position;
+        }
+        self.line
+    }
+}
+
+fn element(start: &BytesStart, line: usize) -> Result<Element, MapIssue> {
+    let mut attributes = Vec::new();
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(|e| xml_issue(line, e.to_string()))?;
+        let value = attribute
+            .normalized_value(XmlVersion::Implicit1_0)
+            .map_err(|e| xml_issue(line, e.to_string()))?;
+        attributes.push((
+            attribute.key.local_name().as_ref().to_string(),
+            value.to_string(),
+        ));
+    }
+    Ok(Element {
+        name: start.local_name().as_ref().to_string(),
+        attributes,
+        text: String::new(),
+        children: Vec::new(),
+        line,
+    })
+}
+
+fn parse_xml(text: &str) -> Result<Element, MapIssue> {
+    let mut reader = Reader::from_str(text);
+    let mut lines = LineCounter {
+        text,
+        position: 0,
+        line: 1,
+    };
+    // 맨 아래는 문서 전체를 담는 가상 루트
+    let mut stack = vec![Element {
+        name: String::new(),
+        attributes: Vec::new(),
+        text: String::new(),
+        children: Vec::new(),
+        line: 1,
+    }];
+    loop {
+        let line = lines.line_at(reader.buffer_position() as usize);
+        let event = reader.read_event().map_err(|e| {
+            let line = lines.line_at(reader.error_position() as usize);
+            xml_issue(line, format!("Invalid XML: {}", e))
+        })?;
+        let top = stack.len() - 1;
+        match event {
+            Event::Start(start) => stack.push(element(&start, line)?),
+            Event::Empty(start) => {
+                let child = element(&start, line)?;
+                stack[top].children.push(child);
+            }
+            Event::End(_) => {
+                let Some(done) = stack.pop() else {
+                    return Err(xml_issue(line, "Unexpected closing tag".into()));
+                };
+                let Some(parent) = stack.last_mut() else {
+                    return Err(xml_issue(line, "Unexpected closing tag".into()));
+                };
+                parent.children.push(done);
+            }
+            Event::Text(content) => stack[top].text.push_str(&content.xml10_content()),
+            Event::CData(content) => stack[top].text.push_str(&content.xml10_content()),
+            Event::GeneralRef(entity) => {
+                let resolved = match entity.resolve_char_ref() {
+                    Ok(Some(ch)) => ch.to_string(),
+                    _ => resolve_predefined_entity(&entity)
+                        .ok_or_else(|| xml_issue(line, format!("Unknown entity &{};", &*entity)))?
+                        .to_string(),
+                };
+                stack[top].text.push_str(&resolved);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    if stack.len() != 1 {
+        return Err(xml_issue(
+            lines.line_at(text.len()),
+            format!("Unclosed element <{}>", stack[stack.len() - 1].name),
+        ));
+    }
+    stack
+        .pop()
+        .and_then(|root| root.children.into_iter().next())
+        .ok_or_else(|| xml_issue(1, "Document has no root element".into()))
+}
+
+// IP-XACT 숫자: 0x1F, 'h1F, 8'h1F, 'b101, 'd10, #1F, 10 (산술식은 지원하지 않음)
+fn parse_value(text: &str) -> Option<u64> {
+    let text = text.trim().replace('_', "");
+    if let Some(hex) = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .or_else(|| text.strip_prefix('#'))
+    {
+        return u64::from_str_radix(hex, 16).ok();
+    }
+    if let Some((_, based)) = text.split_once('\'') {
+        let based = based.trim_start_matches(['s', 'S']);
+        let (radix, digits) = match based.chars().next()? {
+            'h' | 'H' => (16, &based[1..]),
+            'd' | 'D' => (10, &based[1..]),
+            'o' | 'O' => (8, &based[1..]),
+            'b' | 'B' => (2, &based[1..]),
+            _ => return None,
+        };
+        return u64::from_str_radix(digits, radix).ok();
+    }
+    text.parse().ok()
+}
+
+fn parse_access(text: &str) -> Option<Access> {
+    match text.trim() {
+        "read-write" => Some(Access::ReadWrite),
+        "read-only" => Some(Access::ReadOnly),
+        "write-only" => Some(Access::WriteOnly),
+        "read-writeOnce" | "writeOnce" => Some(Access::WriteOnce),
+        _ => None,
+    }
+}
+
+struct Importer {
+    warnings: Vec<MapIssue>,
+}
+
+impl Importer {
+    fn warn(&mut self, element: &Element, path: &str, message: String) {
+        self.warnings.push(MapIssue {
+            line: Some(element.line),
+            column: None,
+            path: path.to_string(),
+            message,
+        });
+    }
+
+    fn number(&mut self, element: &Element, name: &str, path: &str) -> Option<u64> {
+        let child = element.child(name)?;
+        let value = parse_value(&child.text);
+        if value.is_none() {
+            self.warn(
+                child,
+                path,
+                format!(
+                    "Unsupported {} '{}' (only literal numbers are supported)",
+                    name,
+                    child.text.trim()
+                ),
+            );
+        }
+        value
+    }
+
+    fn required(&mut self, element: &Element, name: &str, path: &str) -> Option<u64> {
+        if element.child(name).is_none() {
+            self.warn(
+                element,
+                path,
+                format!("Missing <{}>; element was skipped", name),
+            );
+            return None;
+        }
+        self.number(element, name, path)
+    }
+
+    fn access(&mut self, element: &Element, path: &str) -> Option<Access> {
+        let child = element.policy("access", "accessPolicies", "accessPolicy")?;
+        let access = parse_access(&child.text);
+        if access.is_none() {
+            self.warn(
+                child,
+                path,
+                format!("Unsupported access '{}'", child.text.trim()),
+            );
+        }
+        access
+    }
+
+    // 알고 있지만 변환하지 않는 구성 보고
+    fn unsupported(&mut self, element: &Element, path: &str, names: &[&str]) {
+        for child in &element.children {
+            if names.contains(&child.name.as_str()) {
+                self.warn(
+                    child,
+                    path,
+                    format!("<{}> is not supported and was ignored", child.name),
+                );
+            }
+        }
+    }
+
+    fn component(&mut self, root: &Element) -> Result<RegisterMap, MapIssue> {
+        if root.name != "component" {
+            return Err(xml_issue(
+                root.line,
+                format!(
+                    "Expected an IP-XACT <component> root element, found <{}>",
+                    root.name
+                ),
+            ));
+        }
+        let mut map = RegisterMap::default();
+        let memory_maps = root.child("memoryMaps");
+        for memory_map in memory_maps.iter().flat_map(|m| m.children("memoryMap")) {
+            let map_name = memory_map.child_text("name").unwrap_or_default();
+            // 주소 단위는 바이트(8비트)만 지원. 다른 단위는 주소를 그대로 가져오므로 알려 줌
+            if let Some(unit) = memory_map.child("addressUnitBits") {
+                if parse_value(&unit.text) != Some(8) {
+                    self.warn(
+                        unit,
+                        map_name,
+                        format!(
+                            "addressUnitBits {} is not supported; addresses were imported unchanged",
+                            unit.text.trim()
+                        ),
+                    );
+                }
+            }
+            self.unsupported(
+                memory_map,
+                map_name,
+                &["bank", "subspaceMap", "memoryRemap"],
+            );
+            for block in memory_map.children("addressBlock") {
+                self.address_block(block, map_name, &mut map);
+            }
+        }
+        if map.registers.is_empty() {
+            return Err(xml_issue(
+                root.line,
+                "No registers found under memoryMaps/memoryMap/addressBlock".into(),
+            ));
+        }
+        Ok(map)
+    }
+
+    fn address_block(&mut self, block: &Element, map_name: &str, map: &mut RegisterMap) {
+        let path = format!(
+            "{}/{}",
+            map_name,
+            block.child_text("name").unwrap_or_default()
+        );
+        let base = self.number(block, "baseAddress", &path).unwrap_or(0);
+        let block_access = self.access(block, &path);
+        self.unsupported(block, &path, &["registerFile"]);
+        for register in block.children("register") {
+            if let Some(register) = self.register(register, &path, base, block_access) {
+                map.registers.push(register);
+            }
+        }
+    }
+
+    fn register(
+        &mut self,
+        element: &Element,
+        block_path: &str,
+        base: u64,
+        block_access: Option<Access>,
+    ) -> Option<Register> {
+        let name = element.child_text("name").unwrap_or_default().to_string();
+        let path = format!("{}/{}", block_path, name);
+        self.unsupported(element, &path, &["dim", "alternateRegisters", "isPresent"]);
+
+        let offset = self.required(element, "addressOffset", &path)?;
+        let Some(address) = base
+            .checked_add(offset)
+            .and_then(|address| u32::try_from(address).ok())
+        else {
+            self.warn(
+                element,
+                &path,
+                format!(
+                    "Address 0x{:X} + 0x{:X} does not fit in 32 bits",
+                    base, offset
+                ),
+            );
+            return None;
+        };
+        let width = self.number(element, "size", &path).unwrap_or(32);
+        if !u32::try_from(width).is_ok_and(|w| SUPPORTED_WIDTHS.contains(&w)) {
+            self.warn(
+                element,
+                &path,
+                format!("{}-bit registers are not supported", width),
+            );
+            return None;
+        }
+
+        // 2009 형식은 레지스터 리셋 값, 이후 형식은 필드별 리셋 값
+        let mut reset = None;
+        if let Some(element_reset) = element.child("reset") {
+            reset = self.number(element_reset, "value", &path);
+            if let Some(mask) = self.number(element_reset, "mask", &path) {
+                let full = bit_mask(width as u32);
+                if mask & full != full {
+                    self.warn(
+                        element_reset,
+                        &path,
+                        format!(
+                            "Reset mask 0x{:X} is partial; masked bits were treated as 0",
+                            mask
+                        ),
+                    );
+                    reset = reset.map(|value| value & mask);
+                }
+            }
+        }
+
+        let volatile = element.child_text("volatile") == Some("true");
+        let fields = element
+            .children("field")
+            .filter_map(|field| self.field(field, &path, volatile))
+            .collect();
+        let mut register = Register {
+            address,
+            name,
+            description: element
+                .child_text("description")
+                .unwrap_or_default()
+                .to_string(),
+            width: width as u32,
+            access: Access::default(),
+            reset,
+            page: None,
+            byte_order: None,
+            value: 0,
+            read_only: false,
+            verify: None,
+            fields,
+        };
+        let access = self.access(element, &path).or(block_access);
+        register.finish_import(access);
+        Some(register)
+    }
+
+    fn field(&mut self, element: &Element, register_path: &str, volatile: bool) -> Option<Field> {
+        let name = element.child_text("name").unwrap_or_default().to_string();
+        let path = format!("{}.{}", register_path, name);
+        self.unsupported(element, &path, &["isPresent"]);
+        let bit = self.required(element, "bitOffset", &path)?;
+        let size = self.required(element, "bitWidth", &path)?;
+        let (Ok(bit), Ok(size)) = (u32::try_from(bit), u32::try_from(size)) else {
+            self.warn(
+                element,
+                &path,
+                format!("Field '{}' bit range is out of range", name),
+            );
+            return None;
+        };
+
+        let mut access = self.access(element, &path);
+        let policies = ("fieldAccessPolicies", "fieldAccessPolicy");
+        if let Some(modified) = element.policy("modifiedWriteValue", policies.0, policies.1) {
+            match modified.text.trim() {
+                "oneToClear" => access = Some(Access::WriteOneToClear),
+                "oneToSet" => access = Some(Access::WriteOneToSet),
+                other => self.warn(
+                    modified,
+                    &path,
+                    format!("Unsupported modifiedWriteValue '{}'", other),
+                ),
+            }
+        }
+        if let Some(read_action) = element.policy("readAction", policies.0, policies.1) {
+            match read_action.text.trim() {
+                "clear" => access = Some(Access::ReadClear),
+                other => self.warn(
+                    read_action,
+                    &path,
+                    format!("Unsupported readAction '{}'", other),
+                ),
+            }
+        }
+
+        let reset_element = element
+            .child("resets")
+            .and_then(|r| r.child("reset"))
+            .or_else(|| element.child("reset"));
+        let reset = reset_element.and_then(|r| self.number(r, "value", &path));
+
+        let mut enums = Vec::new();
+        for values in element.children("enumeratedValues") {
+            for value in values.children("enumeratedValue") {
+                let Some(number) = self.number(value, "value", &path) else {
+                    continue;
+                };
+                enums.push(FieldEnum {
+                    value: number,
+                    name: value
+                        .child_text("name")
+                        .or_else(|| value.attribute("name"))
+                        .unwrap_or_default()
+                        .to_string(),
+                    description: value
+                        .child_text("description")
+                        .unwrap_or_default()
+                        .to_string(),
+                });
+            }
+        }
+
+        Some(Field {
+            name,
+            bit,
+            size,
+            description: element
+                .child_text("description")
+                .unwrap_or_default()
+                .to_string(),
+            access,
+            reset,
+            volatile: volatile || element.child_text("volatile") == Some("true"),
+            enums,
+            conversion: None,
+        })
+    }
+}
+
+// IP-XACT XML -> 레지스터 맵 + 변환하지 못한 구성에 대한 경고
+pub fn import(text: &str) -> Result<(RegisterMap, Vec<MapIssue>), MapIssue> {
+    let root = parse_xml(text)?;
+    let mut importer = Importer {
+        warnings: Vec::new(),
+    };
+    let map = importer.component(&root)?;
+    Ok((map, importer.warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn has_warning(warnings: &[MapIssue], text: &str) -> bool {
+        warnings.iter().any(|w| w.message.contains(text))
+    }
+
+    #[test]
+    fn parses_literal_values() {
+        assert_eq!(parse_value("0x1F"), Some(0x1F));
+        assert_eq!(parse_value("0X1f"), Some(0x1F));
+        assert_eq!(parse_value("#1F"), Some(0x1F));
+        assert_eq!(parse_value("'h1F"), Some(0x1F));
+        assert_eq!(parse_value("8'h1F"), Some(0x1F));
+        assert_eq!(parse_value("8'sh1F"), Some(0x1F));
+        assert_eq!(parse_value("'b1010_0101"), Some(0xA5));
+        assert_eq!(parse_value("'o17"), Some(0o17));
+        assert_eq!(parse_value("'d10"), Some(10));
+        assert_eq!(parse_value(" 42 "), Some(42));
+        assert_eq!(parse_value("1_000"), Some(1000));
+        assert_eq!(parse_value("4'x3"), None);
+        assert_eq!(parse_value("BASE + 4"), None);
+    }
+
+    const SPIRIT_2009: &str = r#"<?xml version="1.0"?>
+<spirit:component xmlns:spirit="http://www.spiritconsortium.org/XMLSchema/SPIRIT/1.5">
+  <spirit:memoryMaps>
+    <spirit:memoryMap>
+      <spirit:name>regs</spirit:name>
+      <spirit:addressBlock>
+        <spirit:name>pmic</spirit:name>
+        <spirit:baseAddress>0x10</spirit:baseAddress>
+        <spirit:access>read-write</spirit:access>
+        <spirit:register>
+          <spirit:name>CTRL</spirit:name>
+          <spirit:description>Control &amp; status</spirit:description>
+          <spirit:addressOffset>0x4</spirit:addressOffset>
+          <spirit:size>8</spirit:size>
+          <spirit:reset>
+            <spirit:value>0x81</spirit:value>
+            <spirit:mask>0xFF</spirit:mask>
+          </spirit:reset>
+          <spirit:field>
+            <spirit:name>EN</spirit:name>
+            <spirit:bitOffset>0</spirit:bitOffset>
+            <spirit:bitWidth>1</spirit:bitWidth>
+            <spirit:enumeratedValues>
+              <spirit:enumeratedValue>
+                <spirit:name>OFF</spirit:name>
+                <spirit:value>0</spirit:value>
+              </spirit:enumeratedValue>
+              <spirit:enumeratedValue>
+                <spirit:name>ON</spirit:name>
+                <spirit:value>1</spirit:value>
+              </spirit:enumeratedValue>
+            </spirit:enumeratedValues>
+          </spirit:field>
+          <spirit:field>
+            <spirit:name>FAULT</spirit:name>
+            <spirit:bitOffset>7</spirit:bitOffset>
+            <spirit:bitWidth>1</spirit:bitWidth>
+            <spirit:access>read-write</spirit:access>
+            <spirit:modifiedWriteValue>oneToClear</spirit:modifiedWriteValue>
+          </spirit:field>
+        </spirit:register>
+      </spirit:addressBlock>
+    </spirit:memoryMap>
+  </spirit:memoryMaps>
+</spirit:component>
+"#;
+
+    #[test]
+    fn imports_2009_register() {
+        let (map, warnings) = import(SPIRIT_2009).unwrap();
+        assert!(warnings.is_empty());
+        let reg = &map.registers[0];
+        assert_eq!(reg.name, "CTRL");
+        assert_eq!(reg.address, 0x14);
+        assert_eq!(reg.width, 8);
+        assert_eq!(reg.reset, Some(0x81));
+        assert_eq!(reg.description, "Control & status");
+        assert_eq!(reg.fields.len(), 2);
+        assert_eq!(reg.fields[0].enums.len(), 2);
+        assert_eq!(reg.fields[0].enums[1].name, "ON");
+        assert_eq!(reg.fields[1].bit, 7);
+        assert_eq!(reg.fields[1].access, Some(Access::WriteOneToClear));
+    }
+
+    const IPXACT_2014: &str = r#"<?xml version="1.0"?>
+<ipxact:component xmlns:ipxact="http://www.accellera.org/XMLSchema/IPXACT/1685-2014">
+  <ipxact:memoryMaps>
+    <ipxact:memoryMap>
+      <ipxact:name>regs</ipxact:name>
+      <ipxact:addressUnitBits>8</ipxact:addressUnitBits>
+      <ipxact:addressBlock>
+        <ipxact:name>blk</ipxact:name>
+        <ipxact:baseAddress>'h100</ipxact:baseAddress>
+        <ipxact:register>
+          <ipxact:name>STATUS</ipxact:name>
+          <ipxact:addressOffset>8'h08</ipxact:addressOffset>
+          <ipxact:size>16</ipxact:size>
+          <ipxact:access>read-only</ipxact:access>
+          <ipxact:field>
+            <ipxact:name>IRQ</ipxact:name>
+            <ipxact:bitOffset>0</ipxact:bitOffset>
+            <ipxact:resets>
+              <ipxact:reset><ipxact:value>1</ipxact:value></ipxact:reset>
+            </ipxact:resets>
+            <ipxact:bitWidth>4</ipxact:bitWidth>
+            <ipxact:readAction>clear</ipxact:readAction>
+          </ipxact:field>
+        </ipxact:register>
+      </ipxact:addressBlock>
+    </ipxact:memoryMap>
+  </ipxact:memoryMaps>
+</ipxact:component>
+"#;
+
+    #[test]
+    fn imports_2014_register() {
+        let (map, warnings) = import(IPXACT_2014).unwrap();
+        assert!(warnings.is_empty());
+        let reg = &map.registers[0];
+        assert_eq!(reg.name, "STATUS");
+        assert_eq!(reg.address, 0x108);
+        assert_eq!(reg.width, 16);
+        let field = &reg.fields[0];
+        assert_eq!((field.bit, field.size), (0, 4));
+        assert_eq!(field.reset, Some(1));
+        assert_eq!(field.access, Some(Access::ReadClear));
+    }
+
+    #[test]
+    fn reports_unsupported_address_units() {
+        let text = IPXACT_2014.replace("<ipxact:addressUnitBits>8<", "<ipxact:addressUnitBits>32<");
+        let (_, warnings) = import(&text).unwrap();
+        assert!(has_warning(
+            &warnings,
+            "addressUnitBits 32 is not supported"
+        ));
+    }
+
+    // 0xFFFFFFFF + 8 은 32비트를, u64::MAX + 8 은 u64 를 넘음
+    #[test]
+    fn skips_registers_past_32_bits() {
+        const OK_BLOCK: &str = "</ipxact:addressBlock><ipxact:addressBlock>\
+<ipxact:name>ok</ipxact:name><ipxact:baseAddress>0</ipxact:baseAddress>\
+<ipxact:register><ipxact:name>OK</ipxact:name><ipxact:addressOffset>0</ipxact:addressOffset></ipxact:register>\
+</ipxact:addressBlock>";
+        for base in ["0xFFFFFFFF", "0xFFFFFFFFFFFFFFFF"] {
+            let text = IPXACT_2014
+                .replace("'h100", base)
+                .replace("</ipxact:addressBlock>", OK_BLOCK);
+            let (map, warnings) = import(&text).unwrap();
+            assert_eq!(map.registers.len(), 1);
+            assert_eq!(map.registers[0].name, "OK");
+            assert!(has_warning(&warnings, "does not fit in 32 bits"));
+        }
+    }
+}
@@ -1,21 +1,340 @@
 use libftd2xx::FtdiCommon;
 use serde::{Deserialize, Serialize};
 use serialport::{SerialPort, SerialPortType};
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager};
 
+mod agent;
+mod can;
+mod codegen;
+mod credentials;
+mod diff;
+mod history;
+mod ipxact;
+mod llm;
+mod llm_provider;
+mod map_history;
+mod map_library;
+mod pcap;
+mod recorder;
+mod register_map;
+mod replay;
+mod snapshot;
+mod systemrdl;
+mod usage;
+mod watch;
+
+use agent::{
+    AgentRequest, AgentResult, Confirmations, ToolCall, ToolCallRecord, ToolConfirmRequest,
+};
+use can::{CanFilterConfig, CanFrame, CanRegisterConfig};
+use credentials::{CredentialInfo, CredentialStore};
+use diff::SnapshotDiff;
+use history::{HistoryFilter, NewTransaction, Transaction, TransactionHistory};
+use llm::{
+    ChatCompletionRequest, LlmDelta, LlmDone, LlmError, LlmErrorKind, LlmRequests, Provider,
+};
+use map_history::{MapVersion, MapVersionDiff};
+use map_library::{LibraryInfo, MapLibrary};
+use pcap::{PcapExportSummary, PcapOptions};
+use recorder::{RecordEntry, RecordingInfo, RecordingOptions, RecordingStatus, SessionRecorder};
+use register_map::{Access, Bus, ByteOrder, MapIssue, RegisterMap, WireValue};
+use replay::{ReplayOptions, ReplayStatus};
+use snapshot::{
+    RegisterRead, RestoreReport, RestoreResult, Snapshot, SnapshotInfo, SnapshotProgress,
+};
+use usage::{ModelPrice, UsageReport, UsageTracker};
+use watch::{WatchEvent, WatchInfo, WatchList, WatchRequest};
+
 // 시리얼 포트 상태 관리
 // 통신 브릿지: 다양한 하드웨어 인터페이스 추상화
 enum CommBridge {
     Serial(Box<dyn SerialPort>),
     Hid(hidapi::HidDevice),
     Ftdi(libftd2xx::Ftdi), // FT2232D/H 공통 핸들
+    #[cfg(target_os = "linux")]
+    Can(can::CanBridge), // SocketCAN (vcan0 등)
+    Replay(replay::ReplayDevice), // 녹화 세션 재생용 가상 디바이스
+}
+
+// 텍스트 명령 전송 (VOLT:/FREQ:/REG: 등)
+fn write_command(
+    device: &mut CommBridge,
+    recorder: &SessionRecorder,
+    cmd: &str,
+) -> Result<(), String> {
+    match device {
+        CommBridge::Serial(port) => {
+            port.write_all(cmd.as_bytes()).map_err(|e| e.to_string())?;
+            port.flush().map_err(|e| e.to_string())?;
+        }
+        CommBridge::Hid(hid_dev) => {
+            let mut buf = vec![0u8; cmd.len() + 1];
+            buf[1..].copy_from_slice(cmd.as_bytes());
+            hid_dev.write(&buf).map_err(|e| e.to_string())?;
+        }
+        CommBridge::Ftdi(ftdi_dev) => {
+            ftdi_dev
+                .write(cmd.as_bytes())
+                .map_err(|e| format!("{:?}", e))?;
+        }
+        #[cfg(target_os = "linux")]
+        CommBridge::Can(bridge) => {
+            bridge.write_text(cmd)?;
+        }
+        CommBridge::Replay(replay) => replay.write(cmd.as_bytes()),
+    }
+    recorder.record_tx(cmd.as_bytes());
+    Ok(())
+}
+
+// 레지스터 읽기 (CAN은 요청/응답, 그 외는 RREG 명령)
+fn read_register_value(
+    state: &AppState,
+    device: &mut CommBridge,
+    address: u32,
+) -> Result<u32, String> {
+    #[cfg(target_os = "linux")]
+    if let CommBridge::Can(bridge) = &*device {
+        let mut other = Vec::new();
+        let result = bridge
+            .socket
+            .read_register(bridge.register_config()?, address, &mut other);
+        forward_can(state, other);
+        return result;
+    }
+    let cmd = format!("RREG:0x{:02X}\n", address);
+    // 재생 장치도 RREG 를 보내 녹화된 TX 와 비교 순서를 맞춤
+    write_command(device, &state.recorder, &cmd)?;
+    if let CommBridge::Replay(replay) = device {
+        return replay.read_register(address);
+    }
+    read_register_response(state, device, address)
+}
+
+// RREG 응답 대기 시간
+const REGISTER_RESPONSE_TIMEOUT: Duration = Duration::from_millis(500);
+
+// RREG 응답 한 줄을 기다려 값 파싱
+fn read_register_response(
+    state: &AppState,
+    device: &mut CommBridge,
+    address: u32,
+) -> Result<u32, String> {
+    wait_for_response(state, device, address, |line| {
+        parse_register_response(line, address)
+    })
+}
+
+// 응답 줄을 parse 가 값을 돌려줄 때까지 읽음
+// 리더 스레드는 디바이스 잠금을 기다리는 중이므로 여기서 직접 읽고,
+// 응답이 아닌 줄과 응답 뒤에 남은 바이트는 리더 스레드가 화면으로 보내도록 넘김
+fn wait_for_response<T>(
+    state: &AppState,
+    device: &mut CommBridge,
+    address: u32,
+    mut parse: impl FnMut(&str) -> Result<Option<T>, String>,
+) -> Result<T, String> {
+    let hid = matches!(device, CommBridge::Hid(_));
+    let deadline = Instant::now() + REGISTER_RESPONSE_TIMEOUT;
+    let mut buffer = [0u8; 256];
+    let mut pending = Vec::new();
+    let mut other = Vec::new();
+    let mut result = None;
+    while result.is_none() && Instant::now() < deadline {
+        let bytes_read = read_available(device, &mut buffer)?;
+        if bytes_read == 0 {
+            continue;
+        }
+        state.recorder.record_rx(&buffer[..bytes_read]);
+        pending.extend_from_slice(&buffer[..bytes_read]);
+        while let Some(pos) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=pos).collect();
+            match parse(&String::from_utf8_lossy(&line)) {
+                Ok(None) => other.push(line),
+                Ok(Some(value)) => {
+                    result = Some(Ok(value));
+                    break;
+                }
+                Err(e) => {
+                    result = Some(Err(e));
+                    break;
+                }
+            }
+        }
+    }
+    other.push(pending);
+    forward_rx(state, hid, other);
+    result.unwrap_or_else(|| {
+        Err(format!(
+            "Timed out waiting for register 0x{:02X} read response",
+            address
+        ))
+    })
+}
+
+// 응답 대기 중 받은, 응답이 아닌 데이터를 리더 스레드가 내보낼 목록에 넣음
+fn forward_rx(state: &AppState, hid: bool, chunks: Vec<Vec<u8>>) {
+    let Ok(mut queue) = state.forwarded_rx.lock() else {
+        return;
+    };
+    for chunk in chunks.into_iter().filter(|chunk| !chunk.is_empty()) {
+        queue.push(if hid {
+            format!("[HID] {}", hex::encode(&chunk))
+        } else {
+            String::from_utf8_lossy(&chunk).to_string()
+        });
+    }
+}
+
+// CAN 레지스터 응답을 기다리며 받은 다른 프레임을 녹화하고 리더 스레드가 내보낼 목록에 넣음
+#[cfg(target_os = "linux")]
+fn forward_can(state: &AppState, frames: Vec<CanFrame>) {
+    for frame in &frames {
+        state.recorder.record_can(recorder::Direction::Rx, frame);
+    }
+    if let Ok(mut queue) = state.forwarded_can.lock() {
+        queue.extend(frames);
+    }
+}
+
+// 텍스트 전송 계층에서 도착한 바이트 읽기 (없으면 0)
+fn read_available(device: &mut CommBridge, buffer: &mut [u8]) -> Result<usize, String> {
+    match device {
+        CommBridge::Serial(port) => match port.read(buffer) {
+            Ok(bytes_read) => Ok(bytes_read),
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => Ok(0),
+            Err(e) => Err(e.to_string()),
+        },
+        CommBridge::Hid(hid_dev) => {
+            let bytes_read = hid_dev
+                .read_timeout(buffer, 20)
+                .map_err(|e| e.to_string())?;
+            // FT260 UART 입력 리포트: [report id 0xF0~0xFE, 길이, 데이터...]
+            if bytes_read >= 2 && (0xF0..=0xFE).contains(&buffer[0]) {
+                let len = (buffer[1] as usize).min(bytes_read - 2);
+                buffer.copy_within(2..2 + len, 0);
+                return Ok(len);
+            }
+            Ok(bytes_read)
+        }
+        CommBridge::Ftdi(ftdi_dev) => {
+            let queued = ftdi_dev.queue_status().map_err(|e| format!("{:?}", e))?;
+            if queued == 0 {
+                thread::sleep(Duration::from_millis(5));
+                return Ok(0);
+            }
+            let len = queued.min(buffer.len());
+            ftdi_dev
+                .read(&mut buffer[..len])
+                .map_err(|e| format!("{:?}", e))
+        }
+        _ => Ok(0),
+    }
+}
+
+// "RREG:0x01=0x12" (같은 주소) 또는 숫자만 있는 줄 "0x12" 만 응답으로 받음
+// 명령 에코나 다른 줄(VOLT:3.30, OK 등)은 None
+fn parse_register_response(line: &str, address: u32) -> Result<Option<u32>, String> {
+    let line = line.trim();
+    if line.to_ascii_uppercase().starts_with("ERR") {
+        return Err(format!(
+            "Device error reading register 0x{:02X}: {}",
+            address, line
+        ));
+    }
+    let data = match response_data(line, "RREG:", address) {
+        Some(data) => data,
+        None if line.contains(':') || line.contains('=') => return Ok(None),
+        None => line,
+    };
+    Ok(register_map::parse_number(data))
+}
+
+// "<prefix><주소>=<데이터>" 형식이고 주소가 같으면 데이터 부분
+fn response_data<'a>(line: &'a str, prefix: &str, address: u32) -> Option<&'a str> {
+    let head = line.get(..prefix.len())?;
+    if !head.eq_ignore_ascii_case(prefix) {
+        return None;
+    }
+    let (target, data) = line[prefix.len()..].split_once('=')?;
+    (register_map::parse_number(target) == Some(address)).then_some(data)
+}
+
+// 주소 자동 증가 블록 읽기: "RBLK:0x10,4" -> "RBLK:0x10=0x12,0x34,0x56,0x78" 처럼 워드를 주소 순서로 응답
+fn read_block_value(
+    state: &AppState,
+    device: &mut CommBridge,
+    address: u32,
+    count: u32,
+) -> Result<Vec<u32>, String> {
+    let cmd = format!("RBLK:0x{:02X},{}\n", address, count);
+    write_command(device, &state.recorder, &cmd)?;
+    wait_for_response(state, device, address, |line| {
+        parse_block_response(line, address, count)
+    })
+}
+
+// "RBLK:0x10=0x12,0x34" (같은 주소) 또는 쉼표로 나눈 숫자만 있는 줄을 응답으로 받음
+// 모든 항목이 숫자이고 정확히 count 개여야 함 (명령 에코나 다른 줄은 None)
+fn parse_block_response(line: &str, address: u32, count: u32) -> Result<Option<Vec<u32>>, String> {
+    let line = line.trim();
+    if line.to_ascii_uppercase().starts_with("ERR") {
+        return Err(format!(
+            "Device error reading block at 0x{:02X}: {}",
+            address, line
+        ));
+    }
+    let data = match response_data(line, "RBLK:", address) {
+        Some(data) => data,
+        None if line.contains(':') || line.contains('=') => return Ok(None),
+        None => line,
+    };
+    let words: Option<Vec<u32>> = data.split(',').map(register_map::parse_number).collect();
+    match words {
+        Some(words) if words.len() == count as usize => Ok(Some(words)),
+        _ => Ok(None),
+    }
+}
+
+// 주소 자동 증가 블록 쓰기: "WBLK:0x10,0x12,0x34"
+fn write_block_value(
+    device: &mut CommBridge,
+    recorder: &SessionRecorder,
+    address: u32,
+    words: &[u32],
+) -> Result<(), String> {
+    let data: Vec<String> = words.iter().map(|w| format!("0x{:02X}", w)).collect();
+    let cmd = format!("WBLK:0x{:02X},{}\n", address, data.join(","));
+    write_command(device, recorder, &cmd)
+}
+
+// 레지스터 쓰기 (CAN은 요청/응답, 그 외는 WREG 명령)
+fn write_register_value(
+    state: &AppState,
+    device: &mut CommBridge,
+    address: u32,
+    value: u32,
+) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    if let CommBridge::Can(bridge) = &*device {
+        let mut other = Vec::new();
+        let result =
+            bridge
+                .socket
+                .write_register(bridge.register_config()?, address, value, &mut other);
+        forward_can(state, other);
+        return result;
+    }
+    let cmd = format!("WREG:0x{:02X},0x{:02X}\n", address, value);
+    write_command(device, &state.recorder, &cmd)
 }
 
 // 시리얼 포트 상태 관리
@@ -23,6 +342,7 @@ struct SerialState {
     device: Option<Arc<Mutex<CommBridge>>>,
     reader_thread: Option<thread::JoinHandle<()>>,
     stop_signal: Arc<AtomicBool>,
+    transport: String,
 }
 
 impl SerialState {
@@ -31,13 +351,499 @@ impl SerialState {
             device: None,
             reader_thread: None,
             stop_signal: Arc::new(AtomicBool::new(false)),
+            transport: String::new(),
         }
     }
 }
 
+// write-once 레지스터별로 이미 쓴 비트와 그 값 ((주소, 페이지) -> (비트 마스크, 쓴 값))
+type WriteOnceBits = HashMap<(u32, Option<u32>), (u64, u64)>;
+
 // 전역 상태
 struct AppState {
     serial: Mutex<SerialState>,
+    recorder: Arc<SessionRecorder>,
+    history: Mutex<TransactionHistory>,
+    // 마지막으로 불러온 레지스터 맵 (파싱 실패 시 None)
+    register_map: Mutex<Option<RegisterMap>>,
+    // 이번 연결에서 이미 쓴 write-once 비트
+    write_once: Mutex<WriteOnceBits>,
+    // 페이지 선택 레지스터에 마지막으로 쓴(읽은) 페이지 (모르면 None)
+    current_page: Mutex<Option<u32>>,
+    write_verify: Mutex<WriteVerifyOptions>,
+    watch: Mutex<WatchList>,
+    // 감시 폴링 스레드는 첫 add_watch 에서 한 번만 시작
+    watch_started: AtomicBool,
+    map_library: Mutex<MapLibrary>,
+    // 진행 중인 LLM 스트리밍/에이전트 요청
+    llm: LlmRequests,
+    // 에이전트 도구 실행 확인 대기
+    tool_confirmations: Confirmations,
+    // 암호화된 LLM API 키
+    credentials: Mutex<CredentialStore>,
+    // LLM 토큰 사용량
+    usage: Mutex<UsageTracker>,
+    // 레지스터 응답을 기다리며 읽은, 응답이 아닌 수신 데이터 (리더 스레드가 serial-data-received 로 보냄)
+    forwarded_rx: Mutex<Vec<String>>,
+    // CAN 레지스터 응답을 기다리며 받은 다른 프레임 (리더 스레드가 can-frame-received 로 보냄)
+    forwarded_can: Mutex<Vec<CanFrame>>,
+    // 디바이스 잠금을 기다리는 사용자 명령 수 (감시 폴링 스레드가 0 이 될 때까지 양보)
+    pending_commands: AtomicUsize,
+}
+
+// 레지스터 쓰기 후 다시 읽어 확인하는 전역 설정
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WriteVerifyOptions {
+    #[serde(default)]
+    enabled: bool,
+    // 불일치 시 다시 쓰고 읽는 횟수
+    #[serde(default = "default_verify_retries")]
+    retries: u32,
+    #[serde(default = "default_verify_retry_delay")]
+    retry_delay_ms: u64,
+}
+
+fn default_verify_retries() -> u32 {
+    2
+}
+
+fn default_verify_retry_delay() -> u64 {
+    10
+}
+
+impl Default for WriteVerifyOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retries: default_verify_retries(),
+            retry_delay_ms: default_verify_retry_delay(),
+        }
+    }
+}
+
+// 맵의 페이지 선택 레지스터 정의
+fn map_paging(state: &AppState) -> Option<register_map::Paging> {
+    with_register_map(state, |map| map.paging.clone())
+        .ok()
+        .flatten()
+}
+
+// 페이지 선택 레지스터를 직접 읽거나 쓴 경우 현재 페이지 캐시 갱신
+fn track_page_register(state: &AppState, address: u32, value: Option<u32>) {
+    let Some(paging) = map_paging(state).filter(|p| p.address == address) else {
+        return;
+    };
+    if let Ok(mut current) = state.current_page.lock() {
+        *current = value.map(|value| paging.page_of(value));
+    }
+}
+
+// page 가 현재 페이지와 다르면 페이지 선택 레지스터를 먼저 씀 (None 이면 그대로)
+fn select_page(
+    state: &AppState,
+    device: &mut CommBridge,
+    transport: &str,
+    page: Option<u32>,
+) -> Result<(), String> {
+    let Some(page) = page else {
+        return Ok(());
+    };
+    let paging = map_paging(state)
+        .ok_or_else(|| format!("Page {} requested but the register map has no paging", page))?;
+    if *state.current_page.lock().map_err(|e| e.to_string())? == Some(page) {
+        return Ok(());
+    }
+    write_register_logged(
+        state,
+        device,
+        transport,
+        None,
+        paging.address,
+        paging.select_value(page),
+    )
+    .map_err(|e| format!("Failed to select page {}: {}", page, e))
+}
+
+// 레지스터 읽기 + 히스토리 기록
+fn read_register_logged(
+    state: &AppState,
+    device: &mut CommBridge,
+    transport: &str,
+    page: Option<u32>,
+    address: u32,
+) -> Result<u32, String> {
+    select_page(state, device, transport, page)?;
+    let started = Instant::now();
+    let result = read_register_value(state, device, address);
+    if page.is_none() {
+        if let Ok(value) = &result {
+            track_page_register(state, address, Some(*value));
+        }
+    }
+    log_register(
+        state,
+        NewTransaction {
+            op: "read",
+            page,
+            address: Some(address),
+            value: result.as_ref().ok().copied(),
+            error: result.clone().err(),
+            latency_us: started.elapsed().as_micros() as u64,
+            transport,
+        },
+    );
+    result
+}
+
+// 레지스터 쓰기 + 히스토리 기록
+fn write_register_logged(
+    state: &AppState,
+    device: &mut CommBridge,
+    transport: &str,
+    page: Option<u32>,
+    address: u32,
+    value: u32,
+) -> Result<(), String> {
+    select_page(state, device, transport, page)?;
+    let started = Instant::now();
+    let result = write_register_value(state, device, address, value);
+    if page.is_none() {
+        // 실패하면 칩이 어느 페이지에 있는지 알 수 없음
+        track_page_register(state, address, result.as_ref().ok().map(|_| value));
+    }
+    log_register(
+        state,
+        NewTransaction {
+            op: "write",
+            page,
+            address: Some(address),
+            value: Some(value),
+            error: result.clone().err(),
+            latency_us: started.elapsed().as_micros() as u64,
+            transport,
+        },
+    );
+    result
+}
+
+// 레지스터 하나를 버스로 전송하는 방법
+struct Transfer {
+    page: Option<u32>,
+    width: u32,
+    // 레지스터가 차지하는 주소(버스 워드) 수
+    words: u32,
+    order: ByteOrder,
+    bus: Bus,
+}
+
+// 맵에 없는 레지스터는 주소 하나로 전송
+fn register_transfer(
+    state: &AppState,
+    reg: Option<&register_map::Register>,
+    page: Option<u32>,
+) -> Transfer {
+    let bus = with_register_map(state, |map| map.bus.clone())
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    Transfer {
+        page: page.or(reg.and_then(|r| r.page)),
+        width: reg.map_or(32, |r| r.width),
+        words: reg.map_or(1, |r| bus.words(r.width)),
+        order: reg.map_or(bus.byte_order, |r| r.byte_order(Some(&bus))),
+        bus,
+    }
+}
+
+// 블록 전송(RBLK/WBLK)은 텍스트 명령을 쓰는 전송 계층에서만 가능
+fn supports_burst(device: &CommBridge, bus: &Bus) -> bool {
+    bus.burst
+        && bus.max_burst > 1
+        && matches!(
+            device,
+            CommBridge::Serial(_) | CommBridge::Hid(_) | CommBridge::Ftdi(_)
+        )
+}
+
+// 블록 읽기 + 워드별 히스토리 기록
+fn read_block_logged(
+    state: &AppState,
+    device: &mut CommBridge,
+    transport: &str,
+    page: Option<u32>,
+    address: u32,
+    count: u32,
+) -> Result<Vec<u32>, String> {
+    select_page(state, device, transport, page)?;
+    let started = Instant::now();
+    let result = read_block_value(state, device, address, count);
+    log_block(
+        state,
+        "read",
+        (address, page),
+        count,
+        &result,
+        started,
+        transport,
+    );
+    result
+}
+
+fn write_block_logged(
+    state: &AppState,
+    device: &mut CommBridge,
+    transport: &str,
+    page: Option<u32>,
+    address: u32,
+    words: &[u32],
+) -> Result<(), String> {
+    select_page(state, device, transport, page)?;
+    let started = Instant::now();
+    let result = write_block_value(device, &state.recorder, address, words).map(|_| words.to_vec());
+    log_block(
+        state,
+        "write",
+        (address, page),
+        words.len() as u32,
+        &result,
+        started,
+        transport,
+    );
+    result.map(|_| ())
+}
+
+// 블록 전송은 주소별 트랜잭션으로 나누어 기록 (재생/히스토리는 주소 단위)
+// target: (시작 주소, 선택한 페이지)
+fn log_block(
+    state: &AppState,
+    op: &str,
+    (address, page): (u32, Option<u32>),
+    count: u32,
+    result: &Result<Vec<u32>, String>,
+    started: Instant,
+    transport: &str,
+) {
+    let latency_us = started.elapsed().as_micros() as u64 / count.max(1) as u64;
+    match result {
+        Ok(words) => {
+            for (offset, word) in words.iter().enumerate() {
+                log_register(
+                    state,
+                    NewTransaction {
+                        op,
+                        page,
+                        address: Some(address + offset as u32),
+                        value: Some(*word),
+                        error: None,
+                        latency_us,
+                        transport,
+                    },
+                );
+            }
+        }
+        Err(e) => log_register(
+            state,
+            NewTransaction {
+                op,
+                page,
+                address: Some(address),
+                value: None,
+                error: Some(e.clone()),
+                latency_us,
+                transport,
+            },
+        ),
+    }
+}
+
+// 레지스터 전체 읽기. 여러 워드로 나뉘면 블록 읽기(지원 시) 또는 주소를 하나씩 읽어 바이트 순서대로 합침
+fn read_register_full(
+    state: &AppState,
+    device: &mut CommBridge,
+    transport: &str,
+    transfer: &Transfer,
+    address: u32,
+) -> Result<u64, String> {
+    if transfer.words <= 1 {
+        return read_register_logged(state, device, transport, transfer.page, address)
+            .map(u64::from);
+    }
+    let mut parts = Vec::with_capacity(transfer.words as usize);
+    if supports_burst(device, &transfer.bus) {
+        while parts.len() < transfer.words as usize {
+            let offset = parts.len() as u32;
+            let count = (transfer.words - offset).min(transfer.bus.max_burst);
+            parts.extend(read_block_logged(
+                state,
+                device,
+                transport,
+                transfer.page,
+                address + offset,
+                count,
+            )?);
+        }
+    } else {
+        for offset in 0..transfer.words {
+            parts.push(read_register_logged(
+                state,
+                device,
+                transport,
+                transfer.page,
+                address + offset,
+            )?);
+        }
+    }
+    Ok(transfer.bus.join(&parts, transfer.order))
+}
+
+// 히스토리에 남기지 않는 전체 읽기 (감시 폴링용, 페이지는 호출하는 쪽에서 선택)
+fn read_register_words(
+    state: &AppState,
+    device: &mut CommBridge,
+    transfer: &Transfer,
+    address: u32,
+) -> Result<u64, String> {
+    if transfer.words <= 1 {
+        return read_register_value(state, device, address).map(u64::from);
+    }
+    let parts = if supports_burst(device, &transfer.bus) && transfer.words <= transfer.bus.max_burst
+    {
+        read_block_value(state, device, address, transfer.words)?
+    } else {
+        (0..transfer.words)
+            .map(|offset| read_register_value(state, device, address + offset))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    Ok(transfer.bus.join(&parts, transfer.order))
+}
+
+// 레지스터 전체 쓰기. 워드는 주소 오름차순으로 씀
+fn write_register_full(
+    state: &AppState,
+    device: &mut CommBridge,
+    transport: &str,
+    transfer: &Transfer,
+    address: u32,
+    value: u64,
+) -> Result<(), String> {
+    if transfer.words <= 1 {
+        let value = u32::try_from(value).map_err(|_| {
+            format!(
+                "Value 0x{:X} for register 0x{:02X} does not fit in 32 bits",
+                value, address
+            )
+        })?;
+        return write_register_logged(state, device, transport, transfer.page, address, value);
+    }
+    let parts = transfer.bus.split(value, transfer.width, transfer.order);
+    if supports_burst(device, &transfer.bus) {
+        for (index, chunk) in parts.chunks(transfer.bus.max_burst as usize).enumerate() {
+            let offset = index as u32 * transfer.bus.max_burst;
+            write_block_logged(
+                state,
+                device,
+                transport,
+                transfer.page,
+                address + offset,
+                chunk,
+            )?;
+        }
+    } else {
+        for (offset, part) in parts.iter().enumerate() {
+            write_register_logged(
+                state,
+                device,
+                transport,
+                transfer.page,
+                address + offset as u32,
+                *part,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+// 쓰기 검증 여부: 호출별 지정 > 레지스터 맵의 verify > 전역 설정
+// write-once 비트가 있는 레지스터는 다시 쓸 수 없으므로 재시도하지 않음
+fn write_verify_settings(
+    state: &AppState,
+    reg: Option<&register_map::Register>,
+    requested: Option<bool>,
+) -> Option<WriteVerifyOptions> {
+    let mut options = state.write_verify.lock().ok()?.clone();
+    let enabled = requested
+        .or(reg.and_then(|r| r.verify))
+        .unwrap_or(options.enabled);
+    if !enabled {
+        return None;
+    }
+    if reg.is_some_and(|r| r.access_mask(Access::WriteOnce) != 0) {
+        options.retries = 0;
+    }
+    Some(options)
+}
+
+// 다시 읽어 mask 안의 비트를 비교. 다르면 rewrite 로 다시 쓰고 retries 번까지 재시도
+fn verify_write(
+    state: &AppState,
+    device: &mut CommBridge,
+    transport: &str,
+    reg: Option<&register_map::Register>,
+    transfer: &Transfer,
+    address: u32,
+    expected: u64,
+    options: &WriteVerifyOptions,
+    mut rewrite: impl FnMut(&mut CommBridge) -> Result<(), String>,
+) -> Result<(), String> {
+    // 맵에 없는 레지스터는 모든 비트를 비교
+    let mask = reg.map_or(u32::MAX as u64, |r| r.verify_mask());
+    if mask == 0 {
+        return Ok(());
+    }
+    let mut attempts = 1;
+    loop {
+        let read_back = read_register_full(state, device, transport, transfer, address)?;
+        let mismatched = (read_back ^ expected) & mask;
+        if mismatched == 0 {
+            return Ok(());
+        }
+        if attempts > options.retries {
+            return Err(format!(
+                "Write verify failed for register 0x{:02X}{}: wrote 0x{:X}, read back 0x{:X} \
+                 (compare mask 0x{:X}, mismatched bits 0x{:X}) after {} attempt(s)",
+                address,
+                reg.map(|r| format!(" ({})", r.name)).unwrap_or_default(),
+                expected,
+                read_back,
+                mask,
+                mismatched,
+                attempts
+            ));
+        }
+        attempts += 1;
+        thread::sleep(Duration::from_millis(options.retry_delay_ms));
+        rewrite(device)?;
+    }
+}
+
+// 레지스터 트랜잭션을 세션 레코더와 히스토리에 기록
+// 맵에 있는 레지스터는 접근 타입도 함께 기록
+fn log_register(state: &AppState, tx: NewTransaction) {
+    let access = tx.address.and_then(|address| {
+        let page = tx.page.or(*state.current_page.lock().ok()?);
+        let cached = state.register_map.lock().ok()?;
+        cached
+            .as_ref()?
+            .register_in_page(address, page)
+            .map(|reg| reg.access.label())
+    });
+    state
+        .recorder
+        .record_register(tx.op, tx.address, tx.value, tx.error.clone(), access);
+    if let Ok(mut history) = state.history.lock() {
+        history.push(tx, access);
+    }
 }
 
 #[tauri::command]
@@ -80,6 +886,11 @@ fn scan_serial_devices() -> Vec<String> {
         }
     }
 
+    #[cfg(target_os = "linux")]
+    for name in can::available_interfaces() {
+        available_ports.push(format!("{} (SocketCAN)", name));
+    }
+
     available_ports
 }
 
@@ -96,6 +907,10 @@ fn connect_device(
     ftdi_mode: Option<String>,
     ft260_mode: Option<String>,
     ft260_i2c_speed: Option<u32>,
+    can_fd: Option<bool>,
+    can_filters: Option<Vec<CanFilterConfig>>,
+    can_register: Option<CanRegisterConfig>,
+    replay_options: Option<ReplayOptions>,
     state: tauri::State<AppState>,
     app: AppHandle,
 ) -> Result<(), String> {
@@ -122,6 +937,18 @@ fn connect_device(
         ft260_i2c_speed
     );
 
+    // 세션 기록용 인터페이스 이름
+    let interface = match device_type.as_str() {
+        "ft2232d" | "ft2232h" => {
+            format!("{}-{}", device_type, ftdi_channel.as_deref().unwrap_or("A"))
+        }
+        "ft260" => device_type.clone(),
+        _ => port_name
+            .as_deref()
+            .map(|name| name.split(" (").next().unwrap_or(name).to_string())
+            .unwrap_or_default(),
+    };
+
     let device = match device_type.as_str() {
         "serialport" => {
             let name = port_name.ok_or("Port name is required for serial mode")?;
@@ -182,19 +1009,59 @@ fn connect_device(
 
             CommBridge::Ftdi(device)
         }
+        #[cfg(target_os = "linux")]
+        "socketcan" => {
+            let name = port_name.ok_or("Interface name is required for SocketCAN mode")?;
+            let interface = name.split(" (").next().unwrap_or(&name);
+            if let Some(config) = &can_register {
+                config.validate()?;
+            }
+            let socket = can::CanSocket::open(interface, can_fd.unwrap_or(false))
+                .map_err(|e| format!("Failed to open CAN interface {}: {}", interface, e))?;
+            socket
+                .set_filters(can_filters.as_deref().unwrap_or(&[]))
+                .map_err(|e| format!("Failed to set CAN filters: {}", e))?;
+            CommBridge::Can(can::CanBridge {
+                socket,
+                register: can_register,
+            })
+        }
+        #[cfg(not(target_os = "linux"))]
+        "socketcan" => {
+            let _ = (can_fd, can_filters, can_register);
+            return Err("SocketCAN is only supported on Linux".to_string());
+        }
+        "replay" => {
+            let path = port_name.ok_or("Recording path is required for replay mode")?;
+            let device = replay::ReplayDevice::open(&path, replay_options.unwrap_or_default())?;
+            CommBridge::Replay(device)
+        }
         _ => return Err(format!("Unsupported device type: {}", device_type)),
     };
 
     let device_arc = Arc::new(Mutex::new(device));
     serial_state.device = Some(device_arc.clone());
+    serial_state.transport = device_type.clone();
+    state.recorder.set_source(&device_type, &interface);
+    // 새 연결은 리셋된 칩으로 간주 (현재 페이지도 알 수 없음)
+    if let Ok(mut written) = state.write_once.lock() {
+        written.clear();
+    }
+    if let Ok(mut page) = state.current_page.lock() {
+        *page = None;
+    }
 
     // 백그라운드 리더 스레드
     let app_clone = app.clone();
     let device_clone = device_arc.clone();
     let stop_signal_clone = serial_state.stop_signal.clone();
+    let recorder_clone = state.recorder.clone();
+    // 이전 연결 해제 시 설정된 중지 신호 초기화
+    serial_state.stop_signal.store(false, Ordering::SeqCst);
 
     let handle = thread::spawn(move || {
         let mut buffer = vec![0u8; 1024];
+        let mut replay_finished = false;
         loop {
             if stop_signal_clone.load(Ordering::SeqCst) {
                 break;
@@ -205,9 +1072,30 @@ fn connect_device(
                 Err(_) => break,
             };
 
+            // 레지스터 응답을 기다리던 쪽이 대신 읽은 데이터
+            let forwarded = app_clone
+                .state::<AppState>()
+                .forwarded_rx
+                .lock()
+                .map(|mut queue| std::mem::take(&mut *queue))
+                .unwrap_or_default();
+            for data in forwarded {
+                let _ = app_clone.emit("serial-data-received", data);
+            }
+            let forwarded_can = app_clone
+                .state::<AppState>()
+                .forwarded_can
+                .lock()
+                .map(|mut queue| std::mem::take(&mut *queue))
+                .unwrap_or_default();
+            for frame in &forwarded_can {
+                emit_can_frame(&app_clone, frame);
+            }
+
             match &mut *device_guard {
                 CommBridge::Serial(port) => match port.read(&mut buffer) {
                     Ok(bytes_read) if bytes_read > 0 => {
+                        recorder_clone.record_rx(&buffer[..bytes_read]);
                         let data = String::from_utf8_lossy(&buffer[..bytes_read]);
                         let _ = app_clone.emit("serial-data-received", data.to_string());
                     }
@@ -217,6 +1105,7 @@ fn connect_device(
                     // HID 리포트 읽기
                     match hid_dev.read_timeout(&mut buffer, 100) {
                         Ok(bytes_read) if bytes_read > 0 => {
+                            recorder_clone.record_rx(&buffer[..bytes_read]);
                             let data = hex::encode(&buffer[..bytes_read]); // 바이너리 앱의 경우 헥사 표시 선호
                             let _ =
                                 app_clone.emit("serial-data-received", format!("[HID] {}", data));
@@ -228,12 +1117,39 @@ fn connect_device(
                     // FTDI D2XX 읽기
                     match ftdi_dev.read(&mut buffer) {
                         Ok(bytes_read) if bytes_read > 0 => {
+                            recorder_clone.record_rx(&buffer[..bytes_read]);
                             let data = String::from_utf8_lossy(&buffer[..bytes_read]);
                             let _ = app_clone.emit("serial-data-received", data.to_string());
                         }
                         _ => {}
                     }
                 }
+                #[cfg(target_os = "linux")]
+                CommBridge::Can(bridge) => {
+                    // 소켓 수신 타임아웃(100ms) 동안 대기
+                    if let Ok(Some(frame)) = bridge.socket.read_frame() {
+                        recorder_clone.record_can(recorder::Direction::Rx, &frame);
+                        emit_can_frame(&app_clone, &frame);
+                    }
+                }
+                CommBridge::Replay(replay) => {
+                    for entry in replay.due_rx() {
+                        match &entry.can {
+                            Some(frame) => {
+                                recorder_clone.record_can(recorder::Direction::Rx, frame)
+                            }
+                            None => recorder_clone.record_rx(&entry.data),
+                        }
+                        emit_replayed_rx(&app_clone, &entry);
+                    }
+                    for mismatch in replay.take_mismatches() {
+                        let _ = app_clone.emit("replay-tx-mismatch", mismatch);
+                    }
+                    if replay.is_finished() && !replay_finished {
+                        replay_finished = true;
+                        let _ = app_clone.emit("replay-finished", replay.status());
+                    }
+                }
             }
             drop(device_guard);
             thread::sleep(Duration::from_millis(10));
@@ -244,6 +1160,44 @@ fn connect_device(
     Ok(())
 }
 
+// 수신한 CAN 프레임을 프레임 이벤트와 터미널 표시 형식으로 내보냄
+fn emit_can_frame(app: &AppHandle, frame: &CanFrame) {
+    let _ = app.emit("can-frame-received", can::CanFrameEvent::new(frame));
+    let _ = app.emit(
+        "serial-data-received",
+        format!("[CAN] {}", can::format_frame(frame)),
+    );
+}
+
+// 재생된 RX 엔트리를 원래 전송 방식의 표시 형식으로 내보냄
+fn emit_replayed_rx(app: &AppHandle, entry: &RecordEntry) {
+    if let Some(frame) = &entry.can {
+        emit_can_frame(app, frame);
+    } else if entry.transport == "ft260" {
+        let _ = app.emit(
+            "serial-data-received",
+            format!("[HID] {}", hex::encode(&entry.data)),
+        );
+    } else {
+        let data = String::from_utf8_lossy(&entry.data);
+        let _ = app.emit("serial-data-received", data.to_string());
+    }
+}
+
+#[tauri::command]
+fn replay_status(state: tauri::State<AppState>) -> Result<ReplayStatus, String> {
+    let serial_state = state.serial.lock().map_err(|e| e.to_string())?;
+    let device_arc = serial_state
+        .device
+        .as_ref()
+        .ok_or_else(|| "Device is not connected".to_string())?;
+    let device = lock_device(&state, device_arc)?;
+    match &*device {
+        CommBridge::Replay(replay) => Ok(replay.status()),
+        _ => Err("Connected device is not a replay session".into()),
+    }
+}
+
 #[tauri::command]
 fn disconnect_serial(state: tauri::State<AppState>) -> Result<(), String> {
     let mut serial_state = state.serial.lock().map_err(|e| e.to_string())?;
@@ -262,7 +1216,7 @@ fn send_serial_data(data: String, state: tauri::State<AppState>) -> Result<(), S
     let serial_state = state.serial.lock().map_err(|e| e.to_string())?;
 
     if let Some(ref device_arc) = serial_state.device {
-        let mut device = device_arc.lock().map_err(|e| e.to_string())?;
+        let mut device = lock_device(&state, device_arc)?;
         match &mut *device {
             CommBridge::Serial(port) => {
                 port.write(data.as_bytes())
@@ -281,7 +1235,20 @@ fn send_serial_data(data: String, state: tauri::State<AppState>) -> Result<(), S
                     .write(data.as_bytes())
                     .map_err(|e| format!("Failed to write to FTDI: {:?}", e))?;
             }
+            #[cfg(target_os = "linux")]
+            CommBridge::Can(bridge) => {
+                // "123#DEADBEEF" 형식이면 프레임 그대로, 아니면 텍스트 페이로드로 전송
+                if data.contains('#') {
+                    let frame = can::parse_frame(&data)?;
+                    bridge.socket.write_frame(&frame)?;
+                    state.recorder.record_can(recorder::Direction::Tx, &frame);
+                    return Ok(());
+                }
+                bridge.write_text(&data)?;
+            }
+            CommBridge::Replay(replay) => replay.write(data.as_bytes()),
         }
+        state.recorder.record_tx(data.as_bytes());
         Ok(())
     } else {
         Err("Device is not connected".to_string())
@@ -292,26 +1259,9 @@ fn send_serial_data(data: String, state: tauri::State<AppState>) -> Result<(), S
 async fn set_voltage(state: tauri::State<'_, AppState>, value: f64) -> Result<(), String> {
     let serial_state = state.serial.lock().map_err(|e| e.to_string())?;
     if let Some(ref device_arc) = serial_state.device {
-        let mut device = device_arc.lock().map_err(|e| e.to_string())?;
+        let mut device = lock_device(&state, device_arc)?;
         let cmd = format!("VOLT:{:.2}\n", value);
-
-        match &mut *device {
-            CommBridge::Serial(port) => {
-                port.write_all(cmd.as_bytes()).map_err(|e| e.to_string())?;
-                port.flush().map_err(|e| e.to_string())?;
-            }
-            CommBridge::Hid(hid_dev) => {
-                let mut buf = vec![0u8; cmd.len() + 1];
-                buf[1..].copy_from_slice(cmd.as_bytes());
-                hid_dev.write(&buf).map_err(|e| e.to_string())?;
-            }
-            CommBridge::Ftdi(ftdi_dev) => {
-                ftdi_dev
-                    .write(cmd.as_bytes())
-                    .map_err(|e| format!("{:?}", e))?;
-            }
-        }
-        Ok(())
+        write_command(&mut device, &state.recorder, &cmd)
     } else {
         Err("Device is not connected".into())
     }
@@ -321,118 +1271,985 @@ async fn set_voltage(state: tauri::State<'_, AppState>, value: f64) -> Result<()
 async fn set_frequency(state: tauri::State<'_, AppState>, value: u64) -> Result<(), String> {
     let serial_state = state.serial.lock().map_err(|e| e.to_string())?;
     if let Some(ref device_arc) = serial_state.device {
-        let mut device = device_arc.lock().map_err(|e| e.to_string())?;
+        let mut device = lock_device(&state, device_arc)?;
         let cmd = format!("FREQ:{}\n", value);
-        match &mut *device {
-            CommBridge::Serial(port) => {
-                port.write_all(cmd.as_bytes()).map_err(|e| e.to_string())?;
-                port.flush().map_err(|e| e.to_string())?;
-            }
-            CommBridge::Hid(hid_dev) => {
-                let mut buf = vec![0u8; cmd.len() + 1];
-                buf[1..].copy_from_slice(cmd.as_bytes());
-                hid_dev.write(&buf).map_err(|e| e.to_string())?;
-            }
-            CommBridge::Ftdi(ftdi_dev) => {
-                ftdi_dev
-                    .write(cmd.as_bytes())
-                    .map_err(|e| format!("{:?}", e))?;
-            }
-        }
-        Ok(())
+        write_command(&mut device, &state.recorder, &cmd)
     } else {
         Err("Device is not connected".into())
     }
 }
 
+// REG 명령에는 주소가 없어 어느 레지스터에 쓰였는지 알 수 없으므로 쓰기 검증을 하지 않음
+// (검증이 필요하면 write_register 사용)
 #[tauri::command]
 async fn set_register(state: tauri::State<'_, AppState>, value: u32) -> Result<(), String> {
     let serial_state = state.serial.lock().map_err(|e| e.to_string())?;
     if let Some(ref device_arc) = serial_state.device {
-        let mut device = device_arc.lock().map_err(|e| e.to_string())?;
+        let mut device = lock_device(&state, device_arc)?;
         let cmd = format!("REG:0x{:08X}\n", value);
-        match &mut *device {
-            CommBridge::Serial(port) => {
-                port.write_all(cmd.as_bytes()).map_err(|e| e.to_string())?;
-                port.flush().map_err(|e| e.to_string())?;
-            }
-            CommBridge::Hid(hid_dev) => {
-                let mut buf = vec![0u8; cmd.len() + 1];
-                buf[1..].copy_from_slice(cmd.as_bytes());
-                hid_dev.write(&buf).map_err(|e| e.to_string())?;
-            }
-            CommBridge::Ftdi(ftdi_dev) => {
-                ftdi_dev
-                    .write(cmd.as_bytes())
-                    .map_err(|e| format!("{:?}", e))?;
-            }
-        }
-        Ok(())
+        let started = Instant::now();
+        let result = write_command(&mut device, &state.recorder, &cmd);
+        log_register(
+            &state,
+            NewTransaction {
+                op: "set",
+                page: None,
+                address: None,
+                value: Some(value),
+                error: result.clone().err(),
+                latency_us: started.elapsed().as_micros() as u64,
+                transport: &serial_state.transport,
+            },
+        );
+        result
     } else {
         Err("Device is not connected".into())
     }
 }
 
+// 맵에 정의된 레지스터 (맵을 불러올 수 없으면 None)
+// 페이지를 지정하지 않으면 현재 페이지에서 보이는 레지스터
+// 페이지를 주지 않으면 현재 페이지에서 찾고, 그 주소가 다른 페이지에만 있으면 그 레지스터 (페이지를 바꿔 접근)
+// 페이지를 줬는데 그 주소가 다른 페이지에만 있으면 오류
+fn mapped_register(
+    state: &AppState,
+    address: u32,
+    page: Option<u32>,
+) -> Result<Option<register_map::Register>, String> {
+    let current = state.current_page.lock().ok().and_then(|p| *p);
+    let reg = with_register_map(state, |map| {
+        map.register_in_page(address, page.or(current)).cloned()
+    })
+    .ok()
+    .flatten();
+    match reg {
+        Some(reg) if !reg.on_page(page) => Err(format!(
+            "Register {} at 0x{:02X} exists only on page {}",
+            reg.name,
+            address,
+            reg.page.unwrap_or_default()
+        )),
+        reg => Ok(reg),
+    }
+}
+
+// page: 페이지 레지스터의 페이지 번호 (없으면 맵의 레지스터 정의 또는 현재 페이지)
 #[tauri::command]
-async fn read_register(state: tauri::State<'_, AppState>, address: u32) -> Result<u32, String> {
+async fn read_register(
+    state: tauri::State<'_, AppState>,
+    address: u32,
+    page: Option<u32>,
+) -> Result<WireValue, String> {
+    let reg = mapped_register(&state, address, page)?;
+    if let Some(reg) = reg.as_ref().filter(|r| !r.is_readable()) {
+        return Err(format!("Register {} is write-only", reg.name));
+    }
+    let transfer = register_transfer(&state, reg.as_ref(), page);
     let serial_state = state.serial.lock().map_err(|e| e.to_string())?;
     if let Some(ref device_arc) = serial_state.device {
-        let mut device = device_arc.lock().map_err(|e| e.to_string())?;
-        let cmd = format!("RREG:0x{:02X}\n", address);
-        match &mut *device {
-            CommBridge::Serial(port) => {
-                port.write_all(cmd.as_bytes()).map_err(|e| e.to_string())?;
-                port.flush().map_err(|e| e.to_string())?;
-            }
-            CommBridge::Hid(hid_dev) => {
-                let mut buf = vec![0u8; cmd.len() + 1];
-                buf[1..].copy_from_slice(cmd.as_bytes());
-                hid_dev.write(&buf).map_err(|e| e.to_string())?;
-            }
-            CommBridge::Ftdi(ftdi_dev) => {
-                ftdi_dev
-                    .write(cmd.as_bytes())
-                    .map_err(|e| format!("{:?}", e))?;
-            }
+        let mut device = lock_device(&state, device_arc)?;
+        let value = read_register_full(
+            &state,
+            &mut device,
+            &serial_state.transport,
+            &transfer,
+            address,
+        )?;
+        if let Some(reg) = &reg {
+            cache_register_value(&state, reg, reg.value_after_read(value));
         }
-        Ok(0)
+        Ok(WireValue(value))
     } else {
         Err("Device is not connected".into())
     }
 }
 
+// 맵에 있는 레지스터는 접근 타입을 적용 (RO 거부, 예약 비트 마스킹, write-once 확인)
 #[tauri::command]
 async fn write_register(
     state: tauri::State<'_, AppState>,
     address: u32,
-    value: u32,
+    value: WireValue,
+    page: Option<u32>,
+    verify: Option<bool>,
 ) -> Result<(), String> {
+    let reg = mapped_register(&state, address, page)?;
+    let WireValue(value) = value;
+    let value = match &reg {
+        Some(reg) => reg.prepare_write(value)?,
+        None => value,
+    };
+    let transfer = register_transfer(&state, reg.as_ref(), page);
     let serial_state = state.serial.lock().map_err(|e| e.to_string())?;
     if let Some(ref device_arc) = serial_state.device {
-        let mut device = device_arc.lock().map_err(|e| e.to_string())?;
-        let cmd = format!("WREG:0x{:02X},0x{:02X}\n", address, value);
-        match &mut *device {
-            CommBridge::Serial(port) => {
-                port.write_all(cmd.as_bytes()).map_err(|e| e.to_string())?;
-                port.flush().map_err(|e| e.to_string())?;
-            }
-            CommBridge::Hid(hid_dev) => {
-                let mut buf = vec![0u8; cmd.len() + 1];
-                buf[1..].copy_from_slice(cmd.as_bytes());
-                hid_dev.write(&buf).map_err(|e| e.to_string())?;
-            }
-            CommBridge::Ftdi(ftdi_dev) => {
-                ftdi_dev
-                    .write(cmd.as_bytes())
-                    .map_err(|e| format!("{:?}", e))?;
-            }
+        let mut device = lock_device(&state, device_arc)?;
+        let transport = &serial_state.transport;
+        let once = match &reg {
+            Some(reg) => check_write_once(&state, reg, reg.value_mask(), value)?,
+            None => 0,
+        };
+        write_register_full(&state, &mut device, transport, &transfer, address, value)?;
+        if let Some(reg) = &reg {
+            mark_write_once(&state, reg, once, value);
+            cache_register_value(&state, reg, value);
+        }
+        match write_verify_settings(&state, reg.as_ref(), verify) {
+            Some(options) => verify_write(
+                &state,
+                &mut device,
+                transport,
+                reg.as_ref(),
+                &transfer,
+                address,
+                value,
+                &options,
+                |device| write_register_full(&state, device, transport, &transfer, address, value),
+            ),
+            None => Ok(()),
         }
-        Ok(())
     } else {
         Err("Device is not connected".into())
     }
 }
 
+#[tauri::command]
+fn get_write_verify(state: tauri::State<AppState>) -> Result<WriteVerifyOptions, String> {
+    Ok(state
+        .write_verify
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone())
+}
+
+#[tauri::command]
+fn set_write_verify(
+    options: WriteVerifyOptions,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    *state.write_verify.lock().map_err(|e| e.to_string())? = options;
+    Ok(())
+}
+
+// 읽을 레지스터: 주소만 주거나 { address, page } 로 페이지까지 지정
+#[derive(Clone, Copy, Deserialize)]
+#[serde(untagged)]
+enum RegisterTarget {
+    Address(u32),
+    Paged { address: u32, page: Option<u32> },
+}
+
+impl RegisterTarget {
+    fn parts(self) -> (u32, Option<u32>) {
+        match self {
+            RegisterTarget::Address(address) => (address, None),
+            RegisterTarget::Paged { address, page } => (address, page),
+        }
+    }
+}
+
+// (주소, 페이지, 맵 정의)
+type ResolvedTarget = (u32, Option<u32>, Option<register_map::Register>);
+
+// 읽을 레지스터 목록. 지정하지 않으면 맵 전체
+// 페이지를 주지 않은 주소는 read_register 처럼 현재 페이지에서 찾음
+fn register_targets(
+    state: &AppState,
+    targets: Option<&[RegisterTarget]>,
+) -> Result<Vec<ResolvedTarget>, String> {
+    let current = state.current_page.lock().ok().and_then(|p| *p);
+    with_register_map(state, |map| match targets {
+        Some(list) => list
+            .iter()
+            .map(|target| {
+                let (address, page) = target.parts();
+                let reg = map
+                    .register_in_page(address, page.or(current))
+                    .filter(|reg| reg.on_page(page))
+                    .cloned();
+                let page = page.or(reg.as_ref().and_then(|r| r.page));
+                (address, page, reg)
+            })
+            .collect(),
+        None => map
+            .registers
+            .iter()
+            .map(|reg| (reg.address, reg.page, Some(reg.clone())))
+            .collect(),
+    })
+}
+
+// 디바이스 잠금을 쥔 채로 여러 레지스터를 차례로 읽음. 실패는 레지스터별로 기록하고 계속 진행
+// include_read_clear 가 false 면 읽기만으로 값이 지워지는 RC 레지스터를 건너뜀. WO 레지스터는 항상 건너뜀
+fn read_register_targets(
+    state: &AppState,
+    device: &mut CommBridge,
+    transport: &str,
+    targets: Vec<ResolvedTarget>,
+    include_read_clear: bool,
+    mut progress: impl FnMut(usize, usize, &RegisterRead),
+) -> Vec<RegisterRead> {
+    let total = targets.len();
+    let mut results = Vec::with_capacity(total);
+    for (index, (address, page, reg)) in targets.into_iter().enumerate() {
+        let mut read = RegisterRead::new(address, reg.as_ref());
+        read.page = page;
+        let skipped = match &reg {
+            Some(r) if !r.is_readable() => Some("write-only"),
+            Some(r) if !include_read_clear && r.has_read_side_effects() => Some("read-to-clear"),
+            _ => None,
+        };
+        if let Some(reason) = skipped {
+            read.skipped = Some(reason.to_string());
+        } else {
+            let transfer = register_transfer(state, reg.as_ref(), page);
+            match read_register_full(state, device, transport, &transfer, address) {
+                Ok(value) => {
+                    if let Some(reg) = &reg {
+                        cache_register_value(state, reg, reg.value_after_read(value));
+                    }
+                    read.value = Some(value);
+                }
+                Err(e) => read.error = Some(e),
+            }
+        }
+        progress(index + 1, total, &read);
+        results.push(read);
+    }
+    results
+}
+
+// 여러 레지스터를 한 번에 읽음 (주소를 지정하지 않으면 맵 전체, 이때 RC 레지스터는 건너뜀)
+// addresses: 주소 또는 { address, page } 목록
+#[tauri::command]
+async fn refresh_registers(
+    state: tauri::State<'_, AppState>,
+    addresses: Option<Vec<RegisterTarget>>,
+) -> Result<Vec<RegisterRead>, String> {
+    let targets = register_targets(&state, addresses.as_deref())?;
+    let serial_state = state.serial.lock().map_err(|e| e.to_string())?;
+    let device_arc = serial_state
+        .device
+        .as_ref()
+        .ok_or_else(|| "Device is not connected".to_string())?;
+    let mut device = lock_device(&state, device_arc)?;
+    Ok(read_register_targets(
+        &state,
+        &mut device,
+        &serial_state.transport,
+        targets,
+        addresses.is_some(),
+        |_, _, _| {},
+    ))
+}
+
+fn emit_snapshot_progress(
+    app: &AppHandle,
+    operation: &str,
+    done: usize,
+    total: usize,
+    address: u32,
+    name: &str,
+) {
+    let _ = app.emit(
+        "snapshot-progress",
+        SnapshotProgress {
+            operation: operation.to_string(),
+            done,
+            total,
+            address,
+            name: name.to_string(),
+        },
+    );
+}
+
+// 현재 칩 상태를 읽어 스냅샷으로 만듦 (진행 상황은 snapshot-progress 이벤트)
+fn capture_snapshot(
+    state: &AppState,
+    app: &AppHandle,
+    operation: &str,
+    name: &str,
+    description: Option<String>,
+    addresses: Option<&[RegisterTarget]>,
+    include_read_clear: bool,
+) -> Result<Snapshot, String> {
+    let targets = register_targets(state, addresses)?;
+    let serial_state = state.serial.lock().map_err(|e| e.to_string())?;
+    let device_arc = serial_state
+        .device
+        .as_ref()
+        .ok_or_else(|| "Device is not connected".to_string())?;
+    let mut device = lock_device(state, device_arc)?;
+    let registers = read_register_targets(
+        state,
+        &mut device,
+        &serial_state.transport,
+        targets,
+        include_read_clear,
+        |done, total, read| {
+            emit_snapshot_progress(app, operation, done, total, read.address, &read.name)
+        },
+    );
+    Ok(Snapshot::new(
+        name,
+        &serial_state.transport,
+        description,
+        registers,
+    ))
+}
+
+// 읽을 수 있는 레지스터를 모두 읽어 이름 붙인 스냅샷으로 저장
+#[tauri::command]
+async fn dump_registers(
+    name: String,
+    description: Option<String>,
+    addresses: Option<Vec<RegisterTarget>>,
+    overwrite: Option<bool>,
+    state: tauri::State<'_, AppState>,
+    app: AppHandle,
+) -> Result<SnapshotInfo, String> {
+    let directory = app_data_subdir(&app, "snapshots")?;
+    let snapshot = capture_snapshot(
+        &state,
+        &app,
+        "dump",
+        &name,
+        description,
+        addresses.as_deref(),
+        addresses.is_some(),
+    )?;
+    snapshot::save(&directory, &snapshot, overwrite.unwrap_or(false))
+}
+
+// 스냅샷의 쓰기 가능한 레지스터를 정해진 순서로 복원 (verify 면 다시 읽어 비교)
+#[tauri::command]
+async fn restore_snapshot(
+    name: String,
+    verify: Option<bool>,
+    order: Option<Vec<u32>>,
+    state: tauri::State<'_, AppState>,
+    app: AppHandle,
+) -> Result<RestoreReport, String> {
+    let snapshot = snapshot::load(&app_data_subdir(&app, "snapshots")?, &name)?;
+    let (steps, mut results) = with_register_map(&state, |map| {
+        snapshot::restore_plan(&snapshot, map, order.as_deref().unwrap_or(&[]))
+    })?;
+    let verify = verify.unwrap_or(false);
+
+    let serial_state = state.serial.lock().map_err(|e| e.to_string())?;
+    let device_arc = serial_state
+        .device
+        .as_ref()
+        .ok_or_else(|| "Device is not connected".to_string())?;
+    let mut device = lock_device(&state, device_arc)?;
+    let transport = &serial_state.transport;
+
+    let total = steps.len();
+    for (index, step) in steps.into_iter().enumerate() {
+        let reg = &step.register;
+        let mut result = RestoreResult::new(reg.address, &reg.name, "written");
+        let transfer = register_transfer(&state, Some(reg), None);
+        let outcome = reg.prepare_write(step.value).and_then(|value| {
+            result.value = Some(value);
+            let once = check_write_once(&state, reg, reg.value_mask(), value)?;
+            write_register_full(
+                &state,
+                &mut device,
+                transport,
+                &transfer,
+                reg.address,
+                value,
+            )?;
+            mark_write_once(&state, reg, once, value);
+            cache_register_value(&state, reg, value);
+            Ok(value)
+        });
+        match outcome {
+            Ok(value) if verify && step.verify_mask != 0 => {
+                match read_register_full(&state, &mut device, transport, &transfer, reg.address) {
+                    Ok(read_back) => {
+                        result.read_back = Some(read_back);
+                        if (read_back ^ value) & step.verify_mask == 0 {
+                            result.status = "verified".into();
+                        } else {
+                            result.status = "mismatch".into();
+                            result.message = Some(format!(
+                                "Read back 0x{:X}, expected 0x{:X} (mask 0x{:X})",
+                                read_back, value, step.verify_mask
+                            ));
+                        }
+                    }
+                    Err(e) => {
+                        result.status = "mismatch".into();
+                        result.message = Some(format!("Verify read failed: {}", e));
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                result.status = "failed".into();
+                result.message = Some(e);
+            }
+        }
+        emit_snapshot_progress(&app, "restore", index + 1, total, reg.address, &reg.name);
+        results.push(result);
+    }
+    Ok(RestoreReport::new(&snapshot.name, results))
+}
+
+// right 를 생략하면 left 스냅샷과 라이브 칩을 비교 (RC 레지스터는 읽지 않음)
+fn compute_snapshot_diff(
+    state: &AppState,
+    app: &AppHandle,
+    left: &str,
+    right: Option<&str>,
+) -> Result<SnapshotDiff, String> {
+    let directory = app_data_subdir(app, "snapshots")?;
+    let left = snapshot::load(&directory, left)?;
+    let right = match right {
+        Some(name) => snapshot::load(&directory, name)?,
+        None => {
+            let addresses: Vec<RegisterTarget> = left
+                .registers
+                .iter()
+                .filter(|r| r.value.is_some())
+                .map(|r| RegisterTarget::Paged {
+                    address: r.address,
+                    page: r.page,
+                })
+                .collect();
+            capture_snapshot(state, app, "diff", "live", None, Some(&addresses), false)?
+        }
+    };
+    // 맵을 불러올 수 없으면 필드 분해 없이 레지스터 값만 비교
+    let map = with_register_map(state, |map| map.clone()).ok();
+    Ok(diff::diff(&left, &right, map.as_ref()))
+}
+
+#[tauri::command]
+async fn diff_snapshots(
+    left: String,
+    right: Option<String>,
+    state: tauri::State<'_, AppState>,
+    app: AppHandle,
+) -> Result<SnapshotDiff, String> {
+    compute_snapshot_diff(&state, &app, &left, right.as_deref())
+}
+
+// 버그 티켓 첨부용 보고서 (format: "json" | "text" | "html")
+#[tauri::command]
+async fn export_snapshot_diff(
+    left: String,
+    right: Option<String>,
+    path: String,
+    format: String,
+    state: tauri::State<'_, AppState>,
+    app: AppHandle,
+) -> Result<SnapshotDiff, String> {
+    let result = compute_snapshot_diff(&state, &app, &left, right.as_deref())?;
+    let content = diff::render(&result, &format)?;
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write diff report: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+fn list_snapshots(app: AppHandle) -> Result<Vec<SnapshotInfo>, String> {
+    snapshot::list(&app_data_subdir(&app, "snapshots")?)
+}
+
+#[tauri::command]
+fn load_snapshot(name: String, app: AppHandle) -> Result<Snapshot, String> {
+    snapshot::load(&app_data_subdir(&app, "snapshots")?, &name)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FieldAccess {
+    register: String,
+    address: u32,
+    field: String,
+    #[serde(serialize_with = "register_map::ser_hex")]
+    value: u64,
+    #[serde(serialize_with = "register_map::ser_hex")]
+    register_value: u64,
+    #[serde(flatten)]
+    decoded: DecodedValue,
+}
+
+impl FieldAccess {
+    fn new(reg: &register_map::Register, field: &register_map::Field, register_value: u64) -> Self {
+        let value = field.extract(register_value);
+        Self {
+            register: reg.name.clone(),
+            address: reg.address,
+            field: field.name.clone(),
+            value,
+            register_value,
+            decoded: DecodedValue::new(field, value),
+        }
+    }
+}
+
+// 필드 원시 값의 해석 (enum 이름 또는 단위가 붙은 물리 값)
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DecodedValue {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    physical: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unit: Option<String>,
+}
+
+impl DecodedValue {
+    fn new(field: &register_map::Field, raw: u64) -> Self {
+        Self {
+            display: field.describe_value(raw),
+            physical: field.decode(raw),
+            unit: field
+                .conversion
+                .as_ref()
+                .map(|c| c.unit().to_string())
+                .filter(|unit| !unit.is_empty()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FieldConversion {
+    register: String,
+    field: String,
+    #[serde(serialize_with = "register_map::ser_hex")]
+    raw: u64,
+    #[serde(flatten)]
+    decoded: DecodedValue,
+}
+
+// 원시 값 -> enum 이름/물리 값
+#[tauri::command]
+fn decode_field(
+    register: String,
+    field: String,
+    raw: WireValue,
+    state: tauri::State<AppState>,
+) -> Result<FieldConversion, String> {
+    let WireValue(raw) = raw;
+    let (reg, field) = resolve_field(&state, &register, &field)?;
+    if raw > field.max_value() {
+        return Err(format!(
+            "Value 0x{:X} does not fit in {}-bit field {}.{}",
+            raw, field.size, reg.name, field.name
+        ));
+    }
+    Ok(FieldConversion {
+        register: reg.name,
+        decoded: DecodedValue::new(&field, raw),
+        field: field.name,
+        raw,
+    })
+}
+
+// "PWM", "1.2 V", "0x1F" 같은 입력 -> 원시 값 (장치에는 쓰지 않음)
+#[tauri::command]
+fn encode_field(
+    register: String,
+    field: String,
+    value: String,
+    state: tauri::State<AppState>,
+) -> Result<FieldConversion, String> {
+    let (reg, field) = resolve_field(&state, &register, &field)?;
+    let raw = field.parse_value(&value)?;
+    Ok(FieldConversion {
+        register: reg.name,
+        decoded: DecodedValue::new(&field, raw),
+        field: field.name,
+        raw,
+    })
+}
+
+// 물리 값/enum 이름으로 필드 쓰기 (예: VSEL 을 "1.2 V" 로)
+#[tauri::command]
+async fn set_field_value(
+    state: tauri::State<'_, AppState>,
+    register: String,
+    field: String,
+    value: String,
+    verify: Option<bool>,
+) -> Result<FieldAccess, String> {
+    let (_, resolved) = resolve_field(&state, &register, &field)?;
+    let raw = resolved.parse_value(&value)?;
+    write_field(state, register, field, WireValue(raw), verify).await
+}
+
+// 캐시된 레지스터 맵에 접근 (아직 없으면 불러옴)
+fn with_register_map<T>(state: &AppState, f: impl FnOnce(&RegisterMap) -> T) -> Result<T, String> {
+    let mut cached = state.register_map.lock().map_err(|e| e.to_string())?;
+    if cached.is_none() {
+        let loaded = load_current_register_map(state)?;
+        if loaded.map.is_none() {
+            return Err(format!(
+                "Register map failed to load:\n{}",
+                register_map::describe_issues(&loaded.issues)
+            ));
+        }
+        *cached = loaded.map;
+    }
+    let map = cached.as_ref().ok_or("Register map is not loaded")?;
+    Ok(f(map))
+}
+
+// 레지스터 맵에서 레지스터/필드 이름 해석
+fn resolve_field(
+    state: &AppState,
+    register: &str,
+    field: &str,
+) -> Result<(register_map::Register, register_map::Field), String> {
+    with_register_map(state, |map| {
+        let reg = map
+            .find_register(register)
+            .ok_or_else(|| format!("Unknown register '{}'", register))?;
+        let found = reg
+            .find_field(field)
+            .ok_or_else(|| format!("Register '{}' has no field '{}'", reg.name, field))?;
+        Ok((reg.clone(), found.clone()))
+    })?
+}
+
+// 캐시된 맵의 레지스터 값 갱신 (WO 레지스터 쓰기의 기준값으로 사용)
+fn cache_register_value(state: &AppState, reg: &register_map::Register, value: u64) {
+    if let Ok(mut cached) = state.register_map.lock() {
+        if let Some(reg) = cached
+            .as_mut()
+            .and_then(|map| map.register_in_page_mut(reg.address, reg.page))
+        {
+            reg.value = value;
+        }
+    }
+}
+
+// bits 중 이번 연결에서 이미 쓴 write-once 비트는 처음 쓴 값과 같아야 함 (다르면 거부)
+// 같은 값을 다시 쓰는 것은 허용해, 다른 필드를 바꾸려는 레지스터 전체 쓰기가 막히지 않게 함
+// 새로 쓰게 될 write-once 비트를 돌려줌
+fn check_write_once(
+    state: &AppState,
+    reg: &register_map::Register,
+    bits: u64,
+    value: u64,
+) -> Result<u64, String> {
+    let once = reg.access_mask(Access::WriteOnce) & bits;
+    if once == 0 {
+        return Ok(0);
+    }
+    let written = state.write_once.lock().map_err(|e| e.to_string())?;
+    let (done, previous) = written
+        .get(&(reg.address, reg.page))
+        .copied()
+        .unwrap_or((0, 0));
+    let changed = (value ^ previous) & done & once;
+    if changed != 0 {
+        return Err(format!(
+            "Write-once bits 0x{:X} of register {} were already written as 0x{:X} since connecting",
+            changed,
+            reg.name,
+            previous & changed
+        ));
+    }
+    Ok(once & !done)
+}
+
+fn mark_write_once(state: &AppState, reg: &register_map::Register, bits: u64, value: u64) {
+    if bits == 0 {
+        return;
+    }
+    if let Ok(mut written) = state.write_once.lock() {
+        let (done, previous) = written.entry((reg.address, reg.page)).or_insert((0, 0));
+        *done |= bits;
+        *previous = (*previous & !bits) | (value & bits);
+    }
+}
+
+#[tauri::command]
+async fn read_field(
+    state: tauri::State<'_, AppState>,
+    register: String,
+    field: String,
+) -> Result<FieldAccess, String> {
+    let (reg, field) = resolve_field(&state, &register, &field)?;
+    if !reg.is_readable() || !field.effective_access(&reg).is_readable() {
+        return Err(format!("Field {}.{} is write-only", reg.name, field.name));
+    }
+    let serial_state = state.serial.lock().map_err(|e| e.to_string())?;
+    let device_arc = serial_state
+        .device
+        .as_ref()
+        .ok_or_else(|| "Device is not connected".to_string())?;
+    let mut device = lock_device(&state, device_arc)?;
+    let transfer = register_transfer(&state, Some(&reg), None);
+    let register_value = read_register_full(
+        &state,
+        &mut device,
+        &serial_state.transport,
+        &transfer,
+        reg.address,
+    )?;
+    cache_register_value(&state, &reg, reg.value_after_read(register_value));
+    Ok(FieldAccess::new(&reg, &field, register_value))
+}
+
+// 필드 단위 읽고-수정-쓰기. 디바이스 잠금을 쥔 채로 읽기와 쓰기를 모두 수행
+// 접근 타입(W1C/W1S/예약 비트 등)은 Register::prepare_field_write 에서 적용
+#[tauri::command]
+async fn write_field(
+    state: tauri::State<'_, AppState>,
+    register: String,
+    field: String,
+    value: WireValue,
+    verify: Option<bool>,
+) -> Result<FieldAccess, String> {
+    let WireValue(value) = value;
+    let (reg, field) = resolve_field(&state, &register, &field)?;
+    // 장치 접근 전에 권한/범위 오류를 먼저 확인
+    reg.prepare_field_write(&field, reg.value, value)?;
+
+    let serial_state = state.serial.lock().map_err(|e| e.to_string())?;
+    let device_arc = serial_state
+        .device
+        .as_ref()
+        .ok_or_else(|| "Device is not connected".to_string())?;
+    let mut device = lock_device(&state, device_arc)?;
+    let transport = &serial_state.transport;
+    let transfer = register_transfer(&state, Some(&reg), None);
+    let once = check_write_once(&state, &reg, field.mask(), field.insert(0, value))?;
+
+    // 쓰기 전용 레지스터나 읽으면 지워지는 레지스터는 읽지 않고 마지막으로 알려진 값을 기준으로 함
+    let current = if !reg.is_readable() || reg.has_read_side_effects() {
+        reg.value
+    } else {
+        read_register_full(&state, &mut device, transport, &transfer, reg.address)?
+    };
+    let (written, register_value) = reg.prepare_field_write(&field, current, value)?;
+    write_register_full(
+        &state,
+        &mut device,
+        transport,
+        &transfer,
+        reg.address,
+        written,
+    )?;
+    mark_write_once(&state, &reg, once, written);
+    if let Some(options) = write_verify_settings(&state, Some(&reg), verify) {
+        verify_write(
+            &state,
+            &mut device,
+            transport,
+            Some(&reg),
+            &transfer,
+            reg.address,
+            written,
+            &options,
+            |device| {
+                write_register_full(&state, device, transport, &transfer, reg.address, written)
+            },
+        )?;
+    }
+
+    cache_register_value(&state, &reg, register_value);
+    Ok(FieldAccess::new(&reg, &field, register_value))
+}
+
+// 감시 폴링 스레드가 할 일이 없을 때 쉬는 간격
+const WATCH_IDLE_SLEEP: Duration = Duration::from_millis(5);
+// 사용자 명령이 디바이스 잠금을 기다리는 동안 폴링 스레드가 쉬는 간격
+const WATCH_YIELD_SLEEP: Duration = Duration::from_millis(1);
+
+// 감시 폴링의 디바이스 잠금. 잠금을 기다리는 사용자 명령이 있으면 그 명령이 먼저 잡을 때까지 기다림
+// (std Mutex 는 공정하지 않아 yield 만으로는 폴링이 잠금을 곧바로 다시 잡을 수 있음)
+fn lock_after_commands<'a>(
+    state: &AppState,
+    device: &'a Mutex<CommBridge>,
+) -> std::sync::LockResult<MutexGuard<'a, CommBridge>> {
+    while state.pending_commands.load(Ordering::SeqCst) > 0 {
+        thread::sleep(WATCH_YIELD_SLEEP);
+    }
+    device.lock()
+}
+
+// 사용자 명령의 디바이스 잠금. 기다리는 동안 pending_commands 에 표시해 감시 폴링이 잠금을 넘겨주게 함
+fn lock_device<'a>(
+    state: &AppState,
+    device: &'a Mutex<CommBridge>,
+) -> Result<MutexGuard<'a, CommBridge>, String> {
+    state.pending_commands.fetch_add(1, Ordering::SeqCst);
+    let guard = device.lock().map_err(|e| e.to_string());
+    state.pending_commands.fetch_sub(1, Ordering::SeqCst);
+    guard
+}
+
+// 감시 목록 폴링 스레드
+// 한 번에 레지스터 하나만 읽고 디바이스 잠금을 바로 풀어, 사용자 명령과 리더 스레드가 사이사이 끼어들 수 있게 함
+fn spawn_watch_poller(app: AppHandle) {
+    thread::spawn(move || loop {
+        let state = app.state::<AppState>();
+        let due = state
+            .watch
+            .lock()
+            .ok()
+            .and_then(|mut watch| watch.next_due(Instant::now()));
+        let Some((address, page)) = due else {
+            thread::sleep(WATCH_IDLE_SLEEP);
+            continue;
+        };
+        let reg = with_register_map(&state, |map| {
+            map.register_in_page(address, page)
+                .filter(|reg| reg.on_page(page))
+                .cloned()
+        })
+        .ok()
+        .flatten();
+        let transfer = register_transfer(&state, reg.as_ref(), page);
+
+        let connection = state
+            .serial
+            .lock()
+            .ok()
+            .and_then(|s| s.device.clone().map(|device| (device, s.transport.clone())));
+        let result = match connection {
+            // 재생 세션의 레지스터 응답은 녹화된 순서대로 소비되므로 폴링하지 않음
+            Some((_, transport)) if transport == "replay" => {
+                Err("Watch polling is disabled during replay".to_string())
+            }
+            Some((device_arc, transport)) => match lock_after_commands(&state, &device_arc) {
+                Ok(mut device) => select_page(&state, &mut device, &transport, page)
+                    .and_then(|_| read_register_words(&state, &mut device, &transfer, address)),
+                Err(e) => Err(e.to_string()),
+            },
+            None => Err("Device is not connected".to_string()),
+        };
+        if let Ok(value) = result {
+            if let Ok(mut cached) = state.register_map.lock() {
+                if let Some(reg) = cached
+                    .as_mut()
+                    .and_then(|map| map.register_in_page_mut(address, page))
+                    .filter(|reg| reg.on_page(page))
+                {
+                    reg.value = value;
+                }
+            }
+        }
+
+        let events = match state.watch.lock() {
+            Ok(mut watch) => watch.apply(address, page, result),
+            Err(_) => Vec::new(),
+        };
+        for event in events {
+            let _ = match event {
+                WatchEvent::Changed(changed) => app.emit("register-changed", changed),
+                WatchEvent::Alert(alert) => app.emit("register-alert", alert),
+            };
+        }
+    });
+}
+
+// 감시 항목 추가. 레지스터(또는 필드)를 intervalMs 마다 읽어 값이 바뀌면 register-changed,
+// condition 이 참/거짓으로 바뀌면 register-alert 이벤트를 냄
+#[tauri::command]
+fn add_watch(
+    request: WatchRequest,
+    state: tauri::State<AppState>,
+    app: AppHandle,
+) -> Result<WatchInfo, String> {
+    let (register, field) = match &request.field {
+        Some(field) => {
+            let (register, field) = resolve_field(&state, &request.register, field)?;
+            (register, Some(field))
+        }
+        None => {
+            let register =
+                with_register_map(&state, |map| map.find_register(&request.register).cloned())?
+                    .ok_or_else(|| format!("Unknown register '{}'", request.register))?;
+            (register, None)
+        }
+    };
+    let info = state
+        .watch
+        .lock()
+        .map_err(|e| e.to_string())?
+        .add(register, field, &request)?;
+    if !state.watch_started.swap(true, Ordering::SeqCst) {
+        spawn_watch_poller(app);
+    }
+    Ok(info)
+}
+
+#[tauri::command]
+fn remove_watch(id: u64, state: tauri::State<AppState>) -> Result<bool, String> {
+    Ok(state.watch.lock().map_err(|e| e.to_string())?.remove(id))
+}
+
+#[tauri::command]
+fn list_watches(state: tauri::State<AppState>) -> Result<Vec<WatchInfo>, String> {
+    Ok(state.watch.lock().map_err(|e| e.to_string())?.list())
+}
+
+#[tauri::command]
+fn clear_watches(state: tauri::State<AppState>) -> Result<(), String> {
+    state.watch.lock().map_err(|e| e.to_string())?.clear();
+    Ok(())
+}
+
+// 연결된 SocketCAN 브릿지에 접근
+#[cfg(target_os = "linux")]
+fn with_can_bridge<T>(
+    state: &AppState,
+    f: impl FnOnce(&can::CanBridge) -> Result<T, String>,
+) -> Result<T, String> {
+    let serial_state = state.serial.lock().map_err(|e| e.to_string())?;
+    let device_arc = serial_state
+        .device
+        .as_ref()
+        .ok_or_else(|| "Device is not connected".to_string())?;
+    let device = lock_device(state, device_arc)?;
+    match &*device {
+        CommBridge::Can(bridge) => f(bridge),
+        _ => Err("Connected device is not a CAN interface".into()),
+    }
+}
+
+#[tauri::command]
+fn send_can_frame(frame: CanFrame, state: tauri::State<AppState>) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        with_can_bridge(&state, |bridge| bridge.socket.write_frame(&frame))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (frame, state);
+        Err("SocketCAN is only supported on Linux".into())
+    }
+}
+
+#[tauri::command]
+fn set_can_filters(
+    filters: Vec<CanFilterConfig>,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        with_can_bridge(&state, |bridge| {
+            bridge
+                .socket
+                .set_filters(&filters)
+                .map_err(|e| format!("Failed to set CAN filters: {}", e))
+        })
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (filters, state);
+        Err("SocketCAN is only supported on Linux".into())
+    }
+}
+
 #[tauri::command]
 fn save_log_to_file(path: String, content: String) -> Result<(), String> {
     let mut file = File::create(&path).map_err(|e| format!("Failed to create file: {}", e))?;
@@ -441,6 +2258,94 @@ fn save_log_to_file(path: String, content: String) -> Result<(), String> {
     Ok(())
 }
 
+// 앱 데이터 디렉터리 아래 하위 폴더 (recordings, snapshots 등)
+fn app_data_subdir(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    Ok(dir.join(name))
+}
+
+#[tauri::command]
+fn start_recording(
+    options: Option<RecordingOptions>,
+    state: tauri::State<AppState>,
+    app: AppHandle,
+) -> Result<RecordingStatus, String> {
+    state.recorder.start(
+        app_data_subdir(&app, "recordings")?,
+        options.unwrap_or_default(),
+    )
+}
+
+#[tauri::command]
+fn stop_recording(state: tauri::State<AppState>) -> Result<RecordingStatus, String> {
+    state.recorder.stop()
+}
+
+#[tauri::command]
+fn recording_status(state: tauri::State<AppState>) -> RecordingStatus {
+    state.recorder.status()
+}
+
+#[tauri::command]
+fn list_recordings(
+    directory: Option<String>,
+    app: AppHandle,
+) -> Result<Vec<RecordingInfo>, String> {
+    let dir = match directory {
+        Some(dir) => PathBuf::from(dir),
+        None => app_data_subdir(&app, "recordings")?,
+    };
+    recorder::list_recordings(&dir)
+}
+
+#[tauri::command]
+fn export_recording_pcapng(
+    source: String,
+    destination: String,
+    options: Option<PcapOptions>,
+) -> Result<PcapExportSummary, String> {
+    let entries = replay::load_entries(std::path::Path::new(&source))?;
+    pcap::export(
+        &entries,
+        std::path::Path::new(&destination),
+        &options.unwrap_or_default(),
+    )
+}
+
+#[tauri::command]
+fn query_register_history(
+    filter: Option<HistoryFilter>,
+    state: tauri::State<AppState>,
+) -> Result<Vec<Transaction>, String> {
+    let history = state.history.lock().map_err(|e| e.to_string())?;
+    Ok(history.query(&filter.unwrap_or_default()))
+}
+
+#[tauri::command]
+fn export_register_history(
+    path: String,
+    format: String,
+    filter: Option<HistoryFilter>,
+    state: tauri::State<AppState>,
+) -> Result<usize, String> {
+    let transactions = {
+        let history = state.history.lock().map_err(|e| e.to_string())?;
+        history.query(&filter.unwrap_or_default())
+    };
+    history::export(std::path::Path::new(&path), &format, &transactions)?;
+    Ok(transactions.len())
+}
+
+#[tauri::command]
+fn clear_register_history(state: tauri::State<AppState>) -> Result<(), String> {
+    let mut history = state.history.lock().map_err(|e| e.to_string())?;
+    history.clear();
+    Ok(())
+}
+
 fn exe_dir() -> Result<PathBuf, String> {
     let exe = std::env::current_exe().map_err(|e| e.to_string())?;
     let dir = exe
@@ -449,220 +2354,869 @@ fn exe_dir() -> Result<PathBuf, String> {
     Ok(dir.to_path_buf())
 }
 
-#[tauri::command]
-fn load_register_map() -> Result<Option<String>, String> {
-    let path = exe_dir()?.join("registers.user.yaml");
-    if !path.exists() {
-        return Ok(None);
-    }
-    std::fs::read_to_string(&path)
-        .map(Some)
-        .map_err(|e| format!("Failed to read register map: {}", e))
+// 예전 버전은 실행 파일 옆 registers.user.yaml 에 사용자 맵을 저장 (첫 실행 때 라이브러리로 옮김)
+fn legacy_register_map_path() -> Result<PathBuf, String> {
+    Ok(exe_dir()?.join("registers.user.yaml"))
 }
 
+// 활성 맵이 라이브러리에 저장된 맵이면 내용, 내장 맵이면 None
 #[tauri::command]
-fn save_register_map(content: String) -> Result<(), String> {
-    let path = exe_dir()?.join("registers.user.yaml");
-    std::fs::write(&path, content).map_err(|e| format!("Failed to write register map: {}", e))
+fn load_register_map(state: tauri::State<AppState>) -> Result<Option<String>, String> {
+    let library = state.map_library.lock().map_err(|e| e.to_string())?;
+    match library.active_path()? {
+        Some(_) => library.read(library.active()).map(Some),
+        None => Ok(None),
+    }
 }
 
-#[derive(Serialize, Deserialize)]
-struct ChatMessage {
-    role: String,
+// author 가 없으면 OS 사용자 이름, note 는 이력에 남길 메모
+#[tauri::command]
+fn save_register_map(
     content: String,
+    author: Option<String>,
+    note: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    state
+        .map_library
+        .lock()
+        .map_err(|e| e.to_string())?
+        .write_active(
+            &content,
+            &author.unwrap_or_else(map_history::default_author),
+            note.as_deref(),
+        )?;
+    let (map, _) = register_map::load(&content);
+    *state.register_map.lock().map_err(|e| e.to_string())? = map;
+    Ok(())
 }
 
-#[derive(Deserialize)]
-struct ChatCompletionRequest {
-    base_url: String,
-    api_key: Option<String>,
-    model: String,
-    messages: Vec<ChatMessage>,
-    temperature: Option<f32>,
-    max_tokens: Option<u32>,
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RegisterMapLoad {
+    // 라이브러리의 맵 이름 ("default" 는 앱 내장 맵)
+    name: String,
+    // "user" (라이브러리에 저장된 맵) 또는 "default" (앱 내장 맵)
+    source: String,
+    path: Option<String>,
+    map: Option<RegisterMap>,
+    issues: Vec<MapIssue>,
 }
 
-#[derive(Deserialize)]
-struct ChatCompletionResponse {
-    choices: Vec<ChatChoice>,
+// 라이브러리의 활성 맵(없으면 내장 기본 맵)을 파싱/검증
+fn load_current_register_map(state: &AppState) -> Result<RegisterMapLoad, String> {
+    let library = state.map_library.lock().map_err(|e| e.to_string())?;
+    let name = library.active().to_string();
+    let path = library.active_path()?;
+    let content = library.read(&name)?;
+    drop(library);
+    let (map, issues) = register_map::load(&content);
+    Ok(RegisterMapLoad {
+        name,
+        source: if path.is_some() { "user" } else { "default" }.to_string(),
+        path: path.map(|path| path.to_string_lossy().to_string()),
+        map,
+        issues,
+    })
 }
 
-#[derive(Deserialize)]
-struct ChatChoice {
-    message: ChatResponseMessage,
+#[tauri::command]
+fn get_register_map(state: tauri::State<AppState>) -> Result<RegisterMapLoad, String> {
+    let loaded = load_current_register_map(&state)?;
+    *state.register_map.lock().map_err(|e| e.to_string())? = loaded.map.clone();
+    Ok(loaded)
 }
 
-#[derive(Deserialize)]
-struct ChatResponseMessage {
-    content: Option<String>,
+#[tauri::command]
+fn validate_register_map(content: String) -> Vec<MapIssue> {
+    register_map::load(&content).1
 }
 
-fn normalize_chat_url(base_url: &str) -> String {
-    let trimmed = base_url.trim_end_matches('/');
-    if trimmed.contains("/chat/completions") || trimmed.contains("/responses") {
-        return trimmed.to_string();
-    }
-    if trimmed.ends_with("/v1") {
-        format!("{}/chat/completions", trimmed)
-    } else {
-        format!("{}/v1/chat/completions", trimmed)
+// 구조화된 맵을 검증 후 활성 맵으로 저장 (검증 오류가 있으면 저장하지 않음)
+// 활성 맵이 내장 맵이면 비어 있는 "user" 이름으로 새 맵을 만들어 저장
+#[tauri::command]
+fn update_register_map(
+    map: RegisterMap,
+    author: Option<String>,
+    note: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    let mut map = map;
+    map.normalize();
+    let content = register_map::to_yaml(&map)?;
+    let issues = register_map::validate(&map, Some(&content));
+    if !issues.is_empty() {
+        return Err(format!(
+            "Register map has {} validation error(s):\n{}",
+            issues.len(),
+            register_map::describe_issues(&issues)
+        ));
     }
+    state
+        .map_library
+        .lock()
+        .map_err(|e| e.to_string())?
+        .write_active(
+            &content,
+            &author.unwrap_or_else(map_history::default_author),
+            note.as_deref(),
+        )?;
+    *state.register_map.lock().map_err(|e| e.to_string())? = Some(map);
+    Ok(())
 }
 
-fn normalize_models_url(base_url: &str) -> String {
-    let trimmed = base_url.trim_end_matches('/');
-    if trimmed.contains("/models") {
-        return trimmed.to_string();
-    }
-    if trimmed.ends_with("/v1") {
-        format!("{}/models", trimmed)
-    } else {
-        format!("{}/v1/models", trimmed)
-    }
+#[tauri::command]
+fn list_register_maps(state: tauri::State<AppState>) -> Result<LibraryInfo, String> {
+    state.map_library.lock().map_err(|e| e.to_string())?.info()
 }
 
-fn model_supports_temperature(model: &str) -> bool {
-    let name = model.trim().to_lowercase();
-    !(name.starts_with("o1") || name.starts_with("o3"))
+// 활성 맵을 바꾸고 새 맵을 불러옴 (session_only 면 다음 실행 때는 이전 기본 맵 사용)
+#[tauri::command]
+fn select_register_map(
+    name: String,
+    session_only: Option<bool>,
+    state: tauri::State<AppState>,
+) -> Result<RegisterMapLoad, String> {
+    state
+        .map_library
+        .lock()
+        .map_err(|e| e.to_string())?
+        .select(&name, !session_only.unwrap_or(false))?;
+    get_register_map(state)
 }
 
+// 장치 프로필에 맵 지정 (name 이 없으면 지정 해제)
 #[tauri::command]
-async fn llm_chat(request: ChatCompletionRequest) -> Result<String, String> {
-    let url = normalize_chat_url(&request.base_url);
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(90))
-        .build()
-        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+fn set_profile_register_map(
+    profile: String,
+    name: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<LibraryInfo, String> {
+    let mut library = state.map_library.lock().map_err(|e| e.to_string())?;
+    library.bind_profile(&profile, name.as_deref())?;
+    library.info()
+}
 
-    let mut body = serde_json::json!({
-        "model": request.model,
-        "messages": request.messages,
-    });
-    if let Some(temp) = request.temperature {
-        if model_supports_temperature(&request.model) {
-            body["temperature"] = serde_json::json!(temp);
+// 장치 프로필에 지정된 맵을 이번 세션의 활성 맵으로 (지정이 없으면 기본 맵 유지)
+#[tauri::command]
+fn select_profile_register_map(
+    profile: String,
+    state: tauri::State<AppState>,
+) -> Result<RegisterMapLoad, String> {
+    {
+        let mut library = state.map_library.lock().map_err(|e| e.to_string())?;
+        if let Some(name) = library.profile_map(&profile).map(str::to_string) {
+            library.select(&name, false)?;
         }
     }
-    if let Some(max_tokens) = request.max_tokens {
-        body["max_completion_tokens"] = serde_json::json!(max_tokens);
-    }
-
-    let api_key = request.api_key.and_then(|key| {
-        let trimmed = key.trim().to_string();
-        if trimmed.is_empty() {
-            None
-        } else {
-            Some(trimmed)
-        }
-    });
-
-    let mut response = send_llm_request(&client, &url, &body, api_key.as_deref()).await?;
+    get_register_map(state)
+}
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let detail = response.text().await.unwrap_or_default();
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LibraryImport {
+    name: String,
+    // "yaml" | "ipxact" | "systemrdl"
+    format: String,
+    warnings: Vec<MapIssue>,
+    issues: Vec<MapIssue>,
+}
 
-        if status.as_u16() == 400 && detail.contains("temperature") {
-            let mut retry_body = body.clone();
-            if let Some(obj) = retry_body.as_object_mut() {
-                obj.remove("temperature");
-            }
-            response = send_llm_request(&client, &url, &retry_body, api_key.as_deref()).await?;
-            if !response.status().is_success() {
-                let retry_status = response.status();
-                let retry_detail = response.text().await.unwrap_or_default();
+// YAML / IP-XACT / SystemRDL 파일을 라이브러리에 새 맵으로 추가 (이름이 없으면 파일 이름)
+#[tauri::command]
+fn import_register_map_file(
+    path: String,
+    name: Option<String>,
+    overwrite: Option<bool>,
+    state: tauri::State<AppState>,
+) -> Result<LibraryImport, String> {
+    let file = std::path::Path::new(&path);
+    let name = match name.filter(|name| !name.trim().is_empty()) {
+        Some(name) => name,
+        None => file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+    let name = MapLibrary::check_name(&name)?;
+    let extension = file
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+    let (format, content, warnings, issues) =
+        if matches!(extension.as_deref(), Some("yaml" | "yml")) {
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            let (map, issues) = register_map::load(&content);
+            if map.is_none() {
                 return Err(format!(
-                    "LLM request failed ({}): {}",
-                    retry_status, retry_detail
+                    "Failed to import {}:\n{}",
+                    path,
+                    register_map::describe_issues(&issues)
                 ));
             }
+            ("yaml".to_string(), content, Vec::new(), issues)
         } else {
-            return Err(format!("LLM request failed ({}): {}", status, detail));
-        }
+            let imported = import_register_map(path.clone(), None)?;
+            let content = register_map::to_yaml(&imported.map)?;
+            (imported.format, content, imported.warnings, imported.issues)
+        };
+    state.map_library.lock().map_err(|e| e.to_string())?.write(
+        &name,
+        &content,
+        overwrite.unwrap_or(false),
+        &map_history::default_author(),
+        Some(&format!("Imported from {}", path)),
+    )?;
+    Ok(LibraryImport {
+        name,
+        format,
+        warnings,
+        issues,
+    })
+}
+
+#[tauri::command]
+fn rename_register_map(
+    name: String,
+    new_name: String,
+    state: tauri::State<AppState>,
+) -> Result<LibraryInfo, String> {
+    let mut library = state.map_library.lock().map_err(|e| e.to_string())?;
+    library.rename(&name, &new_name)?;
+    library.info()
+}
+
+// 활성 맵을 지우면 내장 맵으로 돌아가므로 캐시도 비움
+#[tauri::command]
+fn delete_register_map(name: String, state: tauri::State<AppState>) -> Result<LibraryInfo, String> {
+    let (was_active, info) = {
+        let mut library = state.map_library.lock().map_err(|e| e.to_string())?;
+        let was_active = library.active() == name.trim();
+        library.delete(&name)?;
+        (was_active, library.info()?)
+    };
+    if was_active {
+        *state.register_map.lock().map_err(|e| e.to_string())? = None;
     }
+    Ok(info)
+}
 
-    let payload = response
-        .json::<ChatCompletionResponse>()
-        .await
-        .map_err(|e| format!("Failed to parse LLM response: {}", e))?;
+// 활성 레지스터 맵(라이브러리 맵 또는 내장 맵)에서 C 헤더/Python/Markdown/HTML 생성
+// format: "c" | "python" | "markdown" | "html", prefix 는 C 매크로 접두사와 문서 제목에 사용
+#[tauri::command]
+fn generate_register_artifact(
+    format: String,
+    prefix: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<String, String> {
+    let loaded = load_current_register_map(&state)?;
+    let map = loaded.map.ok_or_else(|| {
+        format!(
+            "Register map failed to load:\n{}",
+            register_map::describe_issues(&loaded.issues)
+        )
+    })?;
+    // 이름/주소나 생성될 식별자가 겹치면 생성된 코드가 컴파일되지 않으므로 먼저 고쳐야 함
+    let mut issues = loaded.issues;
+    issues.extend(codegen::identifier_issues(&map));
+    if !issues.is_empty() {
+        return Err(format!(
+            "Register map has {} validation error(s):\n{}",
+            issues.len(),
+            register_map::describe_issues(&issues)
+        ));
+    }
+    let source = loaded
+        .path
+        .as_deref()
+        .and_then(|path| std::path::Path::new(path).file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "registers.yaml".into());
+    codegen::render(&map, &format, prefix.as_deref().unwrap_or(""), &source)
+}
 
-    let content = payload
-        .choices
-        .first()
-        .and_then(|choice| choice.message.content.clone())
-        .unwrap_or_default();
+#[tauri::command]
+fn export_register_artifact(
+    format: String,
+    path: String,
+    prefix: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    let content = generate_register_artifact(format, prefix, state)?;
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
 
-    Ok(content)
+// name 이 없으면 활성 맵의 이력
+#[tauri::command]
+fn list_register_map_versions(
+    name: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<Vec<MapVersion>, String> {
+    state
+        .map_library
+        .lock()
+        .map_err(|e| e.to_string())?
+        .versions(name.as_deref())
+}
+
+#[tauri::command]
+fn get_register_map_version(
+    name: Option<String>,
+    version: u32,
+    state: tauri::State<AppState>,
+) -> Result<String, String> {
+    state
+        .map_library
+        .lock()
+        .map_err(|e| e.to_string())?
+        .version_content(name.as_deref(), version)
 }
 
-#[derive(Deserialize)]
-struct ModelListResponse {
-    data: Vec<ModelEntry>,
+// 두 버전의 레지스터/필드 단위 비교 (to 가 없으면 현재 맵과 비교)
+#[tauri::command]
+fn diff_register_map_versions(
+    name: Option<String>,
+    from: u32,
+    to: Option<u32>,
+    state: tauri::State<AppState>,
+) -> Result<MapVersionDiff, String> {
+    state
+        .map_library
+        .lock()
+        .map_err(|e| e.to_string())?
+        .diff_versions(name.as_deref(), from, to)
 }
 
-#[derive(Deserialize)]
-struct ModelEntry {
-    id: String,
+// 예전 버전을 새 버전으로 다시 저장. 활성 맵이면 캐시도 갱신
+#[tauri::command]
+fn rollback_register_map(
+    name: Option<String>,
+    version: u32,
+    author: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<Vec<MapVersion>, String> {
+    let (versions, reload) = {
+        let mut library = state.map_library.lock().map_err(|e| e.to_string())?;
+        let versions = library.rollback(
+            name.as_deref(),
+            version,
+            &author.unwrap_or_else(map_history::default_author),
+        )?;
+        let reload = name.as_deref().is_none_or(|name| {
+            MapLibrary::check_name(name).ok().as_deref() == Some(library.active())
+        });
+        (versions, reload)
+    };
+    if reload {
+        *state.register_map.lock().map_err(|e| e.to_string())? = None;
+    }
+    Ok(versions)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RegisterMapImport {
+    // "ipxact" | "systemrdl"
+    format: String,
+    map: RegisterMap,
+    // 변환하지 못하고 건너뛴 구성
+    warnings: Vec<MapIssue>,
+    // 변환된 맵의 검증 오류 (update_register_map 으로 저장하기 전에 수정 필요)
+    issues: Vec<MapIssue>,
 }
 
+// IP-XACT(.xml) / SystemRDL(.rdl) 파일을 앱 레지스터 모델로 변환 (저장은 update_register_map 으로)
+// format 이 없으면 확장자, 그다음 내용으로 판단
 #[tauri::command]
-async fn list_llm_models(base_url: String, api_key: Option<String>) -> Result<Vec<String>, String> {
-    let url = normalize_models_url(&base_url);
+fn import_register_map(path: String, format: Option<String>) -> Result<RegisterMapImport, String> {
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let extension = std::path::Path::new(&path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+    let format = match format.as_deref().or(extension.as_deref()) {
+        Some("ipxact" | "ip-xact" | "xml") => "ipxact",
+        Some("systemrdl" | "rdl") => "systemrdl",
+        _ if content.trim_start().starts_with('<') => "ipxact",
+        _ => "systemrdl",
+    };
+    let imported = match format {
+        "ipxact" => ipxact::import(&content),
+        _ => systemrdl::import(&content),
+    };
+    let (mut map, warnings) =
+        imported.map_err(|issue| format!("Failed to import {}: {}", path, issue))?;
+    map.normalize();
+    let issues = register_map::validate(&map, None);
+    Ok(RegisterMapImport {
+        format: format.to_string(),
+        map,
+        warnings,
+        issues,
+    })
+}
+
+// 요청에 지정된 제공자 ID 의 API 키 (ID 가 없거나 저장된 키가 없으면 키 없이 요청)
+fn llm_api_key(state: &AppState, credential: Option<&str>) -> Result<Option<String>, String> {
+    match credential.map(str::trim).filter(|id| !id.is_empty()) {
+        Some(id) => state
+            .credentials
+            .lock()
+            .map_err(|_| "Credential store is unavailable".to_string())?
+            .get(id),
+        None => Ok(None),
+    }
+}
+
+// 응답 하나의 토큰 사용량 기록 (기록에 실패해도 요청은 그대로 진행)
+fn record_llm_usage(state: &AppState, model: &str, usage: Option<llm::Usage>) {
+    let result = state
+        .usage
+        .lock()
+        .map_err(|_| "Usage tracker is unavailable".to_string())
+        .and_then(|mut tracker| tracker.record(model, usage));
+    if let Err(e) = result {
+        eprintln!("Failed to record LLM usage: {}", e);
+    }
+}
+
+fn llm_client(timeout: Duration) -> Result<reqwest::Client, LlmError> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| {
+            LlmError::new(
+                LlmErrorKind::Network,
+                format!("Failed to build HTTP client: {}", e),
+            )
+        })
+}
+
+// 실패하면 { kind, status, message, retries } (LlmError)
+#[tauri::command]
+async fn llm_chat(
+    request: ChatCompletionRequest,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, LlmError> {
+    let url = request.url();
+    let client = llm_client(Duration::from_secs(90))?;
+
+    let body = request.body(false);
+    let api_key = llm_api_key(&state, request.credential.as_deref())
+        .map_err(|e| LlmError::new(LlmErrorKind::Credential, e))?;
+    let response =
+        llm::send_chat(&client, request.provider, &url, &body, api_key.as_deref()).await?;
+
+    let payload = response.json::<serde_json::Value>().await.map_err(|e| {
+        LlmError::new(
+            LlmErrorKind::InvalidResponse,
+            format!("Failed to parse LLM response: {}", e),
+        )
+    })?;
+
+    let parsed = request.provider.parse_response(&payload);
+    record_llm_usage(
+        &state,
+        &request.model,
+        parsed.as_ref().ok().and_then(|(_, u)| *u),
+    );
+    let (content, _) = parsed.map_err(|e| LlmError::new(LlmErrorKind::InvalidResponse, e))?;
+    Ok(content)
+}
+
+// 스트리밍 요청을 시작하고 요청 ID를 바로 돌려줌
+// 받은 텍스트는 llm-delta, 끝나면(완료/취소/실패) llm-done 이벤트로 전달
+#[tauri::command]
+async fn llm_chat_stream(
+    request: ChatCompletionRequest,
+    state: tauri::State<'_, AppState>,
+    app: AppHandle,
+) -> Result<u64, String> {
+    let url = request.url();
+    // 응답 전체가 아니라 연결과 청크 사이 대기 시간만 제한
     let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(30))
+        .connect_timeout(Duration::from_secs(30))
         .build()
         .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let body = request.body(true);
+    let api_key = llm_api_key(&state, request.credential.as_deref())?;
+    let provider = request.provider;
+    let model = request.model;
+
+    let (request_id, cancel) = state.llm.start();
+    tauri::async_runtime::spawn(async move {
+        let mut content = String::new();
+        let mut usage = None;
+        let mut error_kind = None;
+        let sent = tokio::select! {
+            _ = cancel.notified() => Ok(None),
+            response = llm::send_chat(&client, provider, &url, &body, api_key.as_deref()) => response.map(Some),
+        };
+        let result = match sent {
+            Ok(Some(response)) => {
+                let result = llm::read_stream(
+                    response,
+                    provider,
+                    &cancel,
+                    &mut content,
+                    &mut usage,
+                    |delta| {
+                        let _ = app.emit(
+                            "llm-delta",
+                            LlmDelta {
+                                request_id,
+                                delta: delta.to_string(),
+                            },
+                        );
+                    },
+                )
+                .await;
+                // 취소되거나 중간에 실패해도 서버가 받은 요청은 집계
+                record_llm_usage(&app.state::<AppState>(), &model, usage);
+                result
+            }
+            Ok(None) => Ok(false),
+            Err(e) => {
+                error_kind = Some(e.kind);
+                Err(e.to_string())
+            }
+        };
+        app.state::<AppState>().llm.finish(request_id);
+        let _ = app.emit(
+            "llm-done",
+            LlmDone {
+                request_id,
+                content,
+                cancelled: matches!(result, Ok(false)),
+                error: result.err(),
+                error_kind,
+                usage,
+            },
+        );
+    });
+    Ok(request_id)
+}
 
-    let api_key = api_key.and_then(|key| {
-        let trimmed = key.trim().to_string();
-        if trimmed.is_empty() {
-            None
-        } else {
-            Some(trimmed)
+// 진행 중인 스트리밍 요청을 중단 (이미 끝났으면 false)
+#[tauri::command]
+fn cancel_llm_request(id: u64, state: tauri::State<AppState>) -> bool {
+    state.llm.cancel(id)
+}
+
+// 도구 호출 에이전트: 모델이 요청한 도구를 실행하고 결과를 돌려주기를 최종 답까지 반복
+// 실행한 도구마다 llm-tool-call, 확인이 필요한 도구는 llm-tool-confirm 이벤트 (confirm_llm_tool 로 응답)
+// 확인 여부는 요청으로 끌 수 없음 (tool_confirmation 참고)
+// 진행 중에는 cancel_llm_request 로 중단할 수 있음
+#[tauri::command]
+async fn llm_agent(
+    request: AgentRequest,
+    state: tauri::State<'_, AppState>,
+    app: AppHandle,
+) -> Result<AgentResult, String> {
+    // 도구 호출 형식은 제공자마다 달라 chat completions 형식만 지원
+    if request.chat.provider != Provider::Chat {
+        return Err(format!(
+            "Tool calling is not supported for the {} provider",
+            request.chat.provider.label()
+        ));
+    }
+    let url = request.chat.url();
+    let client = llm_client(Duration::from_secs(90))?;
+    let api_key = llm_api_key(&state, request.chat.credential.as_deref())?;
+    let mut body = request.chat.body(false);
+    body["tools"] = serde_json::json!(agent::tool_definitions());
+    let mut messages: Vec<serde_json::Value> = request
+        .chat
+        .messages
+        .iter()
+        .map(|message| serde_json::json!(message))
+        .collect();
+    let max_steps = request.max_steps.unwrap_or(agent::DEFAULT_MAX_STEPS).max(1);
+
+    let (request_id, cancel) = state.llm.start();
+    let mut result = AgentResult {
+        request_id,
+        content: String::new(),
+        finished: false,
+        calls: Vec::new(),
+    };
+    let outcome: Result<(), String> = async {
+        for _ in 0..max_steps {
+            body["messages"] = serde_json::json!(messages);
+            let response = tokio::select! {
+                _ = cancel.notified() => None,
+                response = llm::send_chat(&client, Provider::Chat, &url, &body, api_key.as_deref()) => Some(response?),
+            };
+            let Some(response) = response else {
+                return Err("LLM request was cancelled".to_string());
+            };
+            let payload = response
+                .json::<serde_json::Value>()
+                .await
+                .map_err(|e| format!("Failed to parse LLM response: {}", e))?;
+            let usage = Provider::Chat.parse_response(&payload).ok().and_then(|(_, u)| u);
+            record_llm_usage(&state, &request.chat.model, usage);
+            let message = agent::response_message(&payload)?;
+            let calls = agent::tool_calls(&message);
+            if calls.is_empty() {
+                result.content = message["content"].as_str().unwrap_or_default().to_string();
+                result.finished = true;
+                return Ok(());
+            }
+            messages.push(message);
+            for call in calls {
+                let record = run_tool_call(&state, &app, request_id, &call, &cancel).await;
+                let _ = app.emit("llm-tool-call", record.clone());
+                messages.push(agent::tool_message(&record));
+                result.calls.push(record);
+                if !state.llm.is_active(request_id) {
+                    return Err("LLM request was cancelled".to_string());
+                }
+            }
         }
-    });
+        Ok(())
+    }
+    .await;
+    state.llm.finish(request_id);
+    outcome.map(|_| result)
+}
+
+// 도구 하나 실행. 실패나 거부도 모델에 돌려줄 수 있도록 기록으로 남김
+async fn run_tool_call(
+    state: &tauri::State<'_, AppState>,
+    app: &AppHandle,
+    request_id: u64,
+    call: &ToolCall,
+    cancel: &tokio::sync::Notify,
+) -> ToolCallRecord {
+    let mut record = call.record(request_id);
+    let args = match &call.arguments {
+        Ok(args) => args.clone(),
+        Err(e) => {
+            record.status = "failed".into();
+            record.error = Some(e.clone());
+            return record;
+        }
+    };
+    if let Some(reason) = tool_confirmation(state, &call.name, &args) {
+        let (confirm_id, receiver) = state.tool_confirmations.request();
+        let _ = app.emit(
+            "llm-tool-confirm",
+            ToolConfirmRequest {
+                request_id,
+                confirm_id,
+                name: call.name.clone(),
+                arguments: args.clone(),
+                reason,
+            },
+        );
+        let approved = tokio::select! {
+            _ = cancel.notified() => false,
+            answer = tokio::time::timeout(agent::CONFIRM_TIMEOUT, receiver) => matches!(answer, Ok(Ok(true))),
+        };
+        state.tool_confirmations.discard(confirm_id);
+        if !approved {
+            record.status = "denied".into();
+            record.error = Some("The user declined this action".into());
+            return record;
+        }
+    }
+    match execute_tool(state, &call.name, &args).await {
+        Ok(value) => {
+            record.status = "executed".into();
+            record.result = Some(value);
+        }
+        Err(e) => {
+            record.status = "failed".into();
+            record.error = Some(e);
+        }
+    }
+    record
+}
 
-    let mut request_builder = client.get(url);
-    if let Some(key) = api_key.as_deref() {
-        request_builder = request_builder.bearer_auth(key);
+// 사용자 확인이 필요한 도구 호출이면 그 이유
+// 장치 상태를 바꾸는 도구와, 읽기만으로 값이 지워지는(RC) 레지스터 읽기
+fn tool_confirmation(state: &AppState, name: &str, args: &serde_json::Value) -> Option<String> {
+    if agent::is_destructive(name) {
+        return Some(format!("{} changes the device state", name));
     }
+    if name != "read_register" {
+        return None;
+    }
+    // 인자가 잘못됐으면 실행 단계에서 오류로 돌려줌
+    let address = agent::u32_arg(args, "address").ok().flatten()?;
+    let page = agent::u32_arg(args, "page").ok().flatten();
+    mapped_register(state, address, page)
+        .ok()
+        .flatten()
+        .filter(|reg| reg.has_read_side_effects())
+        .map(|reg| format!("Reading {} clears it (read-to-clear)", reg.name))
+}
+
+async fn execute_tool(
+    state: &tauri::State<'_, AppState>,
+    name: &str,
+    args: &serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    use serde_json::json;
+    match name {
+        "read_register" => {
+            let address = agent::u32_arg(args, "address")?.ok_or("Missing argument 'address'")?;
+            let page = agent::u32_arg(args, "page")?;
+            let WireValue(value) = read_register(state.clone(), address, page).await?;
+            Ok(json!({ "address": address, "value": value, "hex": format!("0x{:X}", value) }))
+        }
+        "write_register" => {
+            let address = agent::u32_arg(args, "address")?.ok_or("Missing argument 'address'")?;
+            let value = agent::required_number(args, "value")?;
+            let page = agent::u32_arg(args, "page")?;
+            write_register(state.clone(), address, WireValue(value), page, None).await?;
+            Ok(json!({ "address": address, "value": value }))
+        }
+        "set_voltage" => {
+            let value = args["value"]
+                .as_f64()
+                .ok_or("Argument 'value' must be a number")?;
+            set_voltage(state.clone(), value).await?;
+            Ok(json!({ "voltage": value }))
+        }
+        "set_frequency" => {
+            let value = agent::required_number(args, "value")?;
+            set_frequency(state.clone(), value).await?;
+            Ok(json!({ "frequency": value }))
+        }
+        "send_command" => {
+            let data = args["data"]
+                .as_str()
+                .ok_or("Argument 'data' must be a string")?;
+            send_serial_data(data.to_string(), state.clone())?;
+            Ok(json!({ "sent": data }))
+        }
+        "scan_devices" => Ok(json!(scan_serial_devices())),
+        "query_logs" => {
+            let filter = HistoryFilter {
+                op: args["op"].as_str().map(str::to_string),
+                address: agent::u32_arg(args, "address")?,
+                success: args["success"].as_bool(),
+                ..Default::default()
+            };
+            let limit = agent::log_limit(args)?;
+            let history = state.history.lock().map_err(|e| e.to_string())?;
+            let entries = history.query(&filter);
+            let skip = entries.len().saturating_sub(limit);
+            Ok(json!(entries[skip..]))
+        }
+        _ => Err(format!("Unknown tool '{}'", name)),
+    }
+}
+
+// llm-tool-confirm 확인 요청에 응답 (이미 답했거나 시간이 지났으면 false)
+#[tauri::command]
+fn confirm_llm_tool(confirm_id: u64, approved: bool, state: tauri::State<AppState>) -> bool {
+    state.tool_confirmations.resolve(confirm_id, approved)
+}
+
+#[tauri::command]
+async fn list_llm_models(
+    base_url: String,
+    credential: Option<String>,
+    provider: Option<Provider>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, LlmError> {
+    let provider = provider.unwrap_or_default();
+    let url = provider.models_url(&base_url);
+    let client = llm_client(Duration::from_secs(30))?;
+
+    let api_key = llm_api_key(&state, credential.as_deref())
+        .map_err(|e| LlmError::new(LlmErrorKind::Credential, e))?;
 
-    let response = request_builder
+    let response = provider
+        .authorize(client.get(url), api_key.as_deref())
         .send()
         .await
-        .map_err(|e| format!("Model request failed: {}", e))?;
+        .map_err(|e| LlmError::from_reqwest(&e))?;
 
     if !response.status().is_success() {
         let status = response.status();
         let detail = response.text().await.unwrap_or_default();
-        return Err(format!("Model request failed ({}): {}", status, detail));
+        return Err(LlmError::from_response(provider, status, &detail, None));
     }
 
-    let payload = response
-        .json::<ModelListResponse>()
-        .await
-        .map_err(|e| format!("Failed to parse model list: {}", e))?;
+    let payload = response.json::<serde_json::Value>().await.map_err(|e| {
+        LlmError::new(
+            LlmErrorKind::InvalidResponse,
+            format!("Failed to parse model list: {}", e),
+        )
+    })?;
 
-    let mut models: Vec<String> = payload.data.into_iter().map(|item| item.id).collect();
+    let mut models = provider.parse_models(&payload);
     models.sort();
     models.dedup();
     Ok(models)
 }
 
-async fn send_llm_request(
-    client: &reqwest::Client,
-    url: &str,
-    body: &serde_json::Value,
-    api_key: Option<&str>,
-) -> Result<reqwest::Response, String> {
-    let mut request_builder = client.post(url).json(body);
-    if let Some(key) = api_key {
-        request_builder = request_builder.bearer_auth(key);
-    }
-    request_builder
-        .send()
-        .await
-        .map_err(|e| format!("LLM request failed: {}", e))
+// 세션/날짜별 토큰 사용량. from/to 는 YYYY-MM-DD (포함), prices 를 주면 저장된 단가 대신 사용
+#[tauri::command]
+fn get_llm_usage(
+    from: Option<String>,
+    to: Option<String>,
+    prices: Option<BTreeMap<String, ModelPrice>>,
+    state: tauri::State<AppState>,
+) -> Result<UsageReport, String> {
+    state
+        .usage
+        .lock()
+        .map_err(|_| "Usage tracker is unavailable".to_string())?
+        .report(from.as_deref(), to.as_deref(), prices.as_ref())
+}
+
+// 모델별 단가(100만 토큰당) 저장 (기존 표를 바꿈)
+#[tauri::command]
+fn set_llm_prices(
+    prices: BTreeMap<String, ModelPrice>,
+    state: tauri::State<AppState>,
+) -> Result<(), String> {
+    state
+        .usage
+        .lock()
+        .map_err(|_| "Usage tracker is unavailable".to_string())?
+        .set_prices(prices)
+}
+
+// API 키를 암호화해 저장 (이후 요청은 제공자 ID 로 참조)
+#[tauri::command]
+fn set_llm_credential(
+    provider_id: String,
+    api_key: String,
+    state: tauri::State<AppState>,
+) -> Result<CredentialInfo, String> {
+    state
+        .credentials
+        .lock()
+        .map_err(|_| "Credential store is unavailable".to_string())?
+        .set(&provider_id, &api_key)
+}
+
+// 저장된 제공자 ID 와 키 끝자리만 돌려줌
+#[tauri::command]
+fn list_llm_credentials(state: tauri::State<AppState>) -> Vec<CredentialInfo> {
+    state
+        .credentials
+        .lock()
+        .map(|store| store.list())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+fn delete_llm_credential(
+    provider_id: String,
+    state: tauri::State<AppState>,
+) -> Result<bool, String> {
+    state
+        .credentials
+        .lock()
+        .map_err(|_| "Credential store is unavailable".to_string())?
+        .remove(&provider_id)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -673,6 +3227,22 @@ pub fn run() {
         .plugin(tauri_plugin_store::Builder::default().build())
         .manage(AppState {
             serial: Mutex::new(SerialState::new()),
+            recorder: Arc::new(SessionRecorder::new()),
+            history: Mutex::new(TransactionHistory::new()),
+            register_map: Mutex::new(None),
+            write_once: Mutex::new(HashMap::new()),
+            current_page: Mutex::new(None),
+            write_verify: Mutex::new(WriteVerifyOptions::default()),
+            watch: Mutex::new(WatchList::new()),
+            watch_started: AtomicBool::new(false),
+            map_library: Mutex::new(MapLibrary::new()),
+            llm: LlmRequests::default(),
+            tool_confirmations: Confirmations::default(),
+            credentials: Mutex::new(CredentialStore::new()),
+            usage: Mutex::new(UsageTracker::new()),
+            forwarded_rx: Mutex::new(Vec::new()),
+            forwarded_can: Mutex::new(Vec::new()),
+            pending_commands: AtomicUsize::new(0),
         })
         .invoke_handler(tauri::generate_handler![
             scan_serial_devices,
@@ -684,11 +3254,66 @@ pub fn run() {
             set_register,
             read_register,
             write_register,
+            send_can_frame,
+            set_can_filters,
             save_log_to_file,
+            start_recording,
+            stop_recording,
+            recording_status,
+            list_recordings,
+            export_recording_pcapng,
+            query_register_history,
+            export_register_history,
+            clear_register_history,
+            replay_status,
             load_register_map,
             save_register_map,
+            get_register_map,
+            validate_register_map,
+            update_register_map,
+            import_register_map,
+            generate_register_artifact,
+            export_register_artifact,
+            read_field,
+            write_field,
+            decode_field,
+            encode_field,
+            set_field_value,
+            refresh_registers,
+            dump_registers,
+            restore_snapshot,
+            list_snapshots,
+            load_snapshot,
+            diff_snapshots,
+            export_snapshot_diff,
+            get_write_verify,
+            set_write_verify,
+            add_watch,
+            remove_watch,
+            list_watches,
+            clear_watches,
+            list_register_maps,
+            select_register_map,
+            set_profile_register_map,
+            select_profile_register_map,
+            import_register_map_file,
+            rename_register_map,
+            delete_register_map,
+            list_register_map_versions,
+            get_register_map_version,
+            diff_register_map_versions,
+            rollback_register_map,
             llm_chat,
-            list_llm_models
+            llm_chat_stream,
+            cancel_llm_request,
+            llm_agent,
+            confirm_llm_tool,
+            list_llm_models,
+            set_llm_credential,
+            list_llm_credentials,
+            delete_llm_credential,
+            get_llm_usage,
+            set_llm_prices
         ])
         .setup(|app| {
             // DLL 검색 경로에 리소스 디렉토리 추가 (윈도우 전용)
@@ -709,6 +3334,32 @@ pub fn run() {
                 println!("Resource dir added to DLL search path: {:?}", resource_dir);
             }
 
+            // 레지스터 맵 라이브러리 (실패해도 내장 맵으로 동작)
+            match app_data_subdir(app.handle(), "register_maps")
+                .and_then(|dir| MapLibrary::open(dir, legacy_register_map_path().ok()))
+            {
+                Ok(library) => {
+                    *app.state::<AppState>().map_library.lock().unwrap() = library;
+                }
+                Err(e) => eprintln!("Register map library unavailable: {}", e),
+            }
+
+            // LLM 자격 증명 저장소 (실패하면 저장된 키 없이 동작)
+            match app_data_subdir(app.handle(), "credentials").and_then(CredentialStore::open) {
+                Ok(store) => {
+                    *app.state::<AppState>().credentials.lock().unwrap() = store;
+                }
+                Err(e) => eprintln!("Credential store unavailable: {}", e),
+            }
+
+            // LLM 사용량 (실패하면 이번 실행 동안 메모리에만 집계)
+            match app_data_subdir(app.handle(), "usage").and_then(UsageTracker::open) {
+                Ok(tracker) => {
+                    *app.state::<AppState>().usage.lock().unwrap() = tracker;
+                }
+                Err(e) => eprintln!("Usage tracker unavailable: {}", e),
+            }
+
             if let Some(window) = app.get_webview_window("main") {
                 let package_info = app.package_info();
                 let title = format!("IC 제어 앱 v{}", package_info.version);
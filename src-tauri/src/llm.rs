@@ -0,0 +1,747 @@
+// LLM 엔드포인트 호출 (제공자별 형식은 llm_provider)
+// 일반 요청과 server-sent events 스트리밍 요청, 스트리밍 요청 취소
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+pub use crate::llm_provider::{Provider, Usage};
+
+// 스트리밍 중 다음 데이터를 기다리는 최대 시간 (전체 응답 시간은 제한하지 않음)
+pub const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+#[derive(Deserialize)]
+pub struct ChatCompletionRequest {
+    pub base_url: String,
+    // 자격 증명 저장소의 제공자 ID (API 키는 웹뷰를 거치지 않음, 없으면 키 없이 요청)
+    pub credential: Option<String>,
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    #[serde(default)]
+    pub provider: Provider,
+}
+
+// 스트리밍 요청에서 새로 받은 텍스트 (llm-delta 이벤트)
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmDelta {
+    pub request_id: u64,
+    pub delta: String,
+}
+
+// 스트리밍 요청 종료 (llm-done 이벤트). 취소되었거나 실패해도 그때까지 받은 내용을 담음
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmDone {
+    pub request_id: u64,
+    pub content: String,
+    pub cancelled: bool,
+    pub error: Option<String>,
+    // 요청이 실패했을 때 실패 종류 (스트림 도중 오류는 None)
+    pub error_kind: Option<LlmErrorKind>,
+    pub usage: Option<Usage>,
+}
+
+impl ChatCompletionRequest {
+    pub fn url(&self) -> String {
+        self.provider.chat_url(&self.base_url)
+    }
+
+    pub fn body(&self, stream: bool) -> serde_json::Value {
+        self.provider.body(
+            &self.model,
+            &self.messages,
+            self.temperature,
+            self.max_tokens,
+            stream,
+        )
+    }
+}
+
+// 실패 종류 (llm-done 이벤트의 errorKind)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LlmErrorKind {
+    Auth,
+    Quota,
+    RateLimited,
+    ModelNotFound,
+    ContextTooLong,
+    InvalidRequest,
+    Server,
+    Network,
+    Timeout,
+    // 저장된 API 키를 읽지 못함
+    Credential,
+    // 성공 응답을 해석하지 못함
+    InvalidResponse,
+}
+
+impl LlmErrorKind {
+    fn label(self) -> &'static str {
+        match self {
+            LlmErrorKind::Auth => "LLM authentication failed",
+            LlmErrorKind::Quota => "LLM quota exceeded",
+            LlmErrorKind::RateLimited => "LLM rate limit exceeded",
+            LlmErrorKind::ModelNotFound => "LLM model not found",
+            LlmErrorKind::ContextTooLong => "LLM context too long",
+            LlmErrorKind::InvalidRequest => "LLM request rejected",
+            LlmErrorKind::Server => "LLM server error",
+            LlmErrorKind::Network => "LLM connection failed",
+            LlmErrorKind::Timeout => "LLM request timed out",
+            LlmErrorKind::Credential => "LLM credential unavailable",
+            LlmErrorKind::InvalidResponse => "LLM response could not be parsed",
+        }
+    }
+}
+
+// 명령 실패 시 프론트엔드로 { kind, status, message, retries } 형태로 전달
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmError {
+    pub kind: LlmErrorKind,
+    #[serde(serialize_with = "status_code")]
+    pub status: Option<reqwest::StatusCode>,
+    pub message: String,
+    // 다시 시도한 횟수
+    pub retries: u32,
+    // 서버가 Retry-After 로 알려 준 대기 시간
+    #[serde(skip)]
+    retry_after: Option<Duration>,
+}
+
+fn status_code<S: serde::Serializer>(
+    status: &Option<reqwest::StatusCode>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    status.map(|status| status.as_u16()).serialize(serializer)
+}
+
+impl LlmError {
+    // HTTP 응답과 관계없는 실패 (클라이언트 생성, 자격 증명, 응답 해석)
+    pub fn new(kind: LlmErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            status: None,
+            message: message.into(),
+            retries: 0,
+            retry_after: None,
+        }
+    }
+
+    pub fn from_response(
+        provider: Provider,
+        status: reqwest::StatusCode,
+        body: &str,
+        retry_after: Option<Duration>,
+    ) -> Self {
+        Self {
+            kind: classify(status, body),
+            status: Some(status),
+            message: provider.error_detail(body),
+            retries: 0,
+            retry_after,
+        }
+    }
+
+    pub fn from_reqwest(error: &reqwest::Error) -> Self {
+        Self {
+            kind: if error.is_timeout() {
+                LlmErrorKind::Timeout
+            } else {
+                LlmErrorKind::Network
+            },
+            status: None,
+            message: error.to_string(),
+            retries: 0,
+            retry_after: None,
+        }
+    }
+
+    // 잠시 뒤 다시 보내면 성공할 수 있는 오류
+    pub fn retryable(&self) -> bool {
+        matches!(
+            self.kind,
+            LlmErrorKind::RateLimited
+                | LlmErrorKind::Server
+                | LlmErrorKind::Network
+                | LlmErrorKind::Timeout
+        )
+    }
+}
+
+impl std::fmt::Display for LlmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.status {
+            Some(status) => write!(f, "{} ({}): {}", self.kind.label(), status, self.message)?,
+            None => write!(f, "{}: {}", self.kind.label(), self.message)?,
+        }
+        if self.retries > 0 {
+            write!(f, " (after {} retries)", self.retries)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<LlmError> for String {
+    fn from(error: LlmError) -> Self {
+        error.to_string()
+    }
+}
+
+// 상태 코드와 오류 본문으로 실패 종류 판단 (제공자마다 오류 코드가 달라 본문 문구도 봄)
+fn classify(status: reqwest::StatusCode, body: &str) -> LlmErrorKind {
+    let text = body.to_lowercase();
+    let mentions = |words: &[&str]| words.iter().any(|word| text.contains(word));
+    match status.as_u16() {
+        401 | 403 => LlmErrorKind::Auth,
+        402 => LlmErrorKind::Quota,
+        429 if mentions(&["insufficient_quota", "quota", "billing", "credit"]) => {
+            LlmErrorKind::Quota
+        }
+        429 => LlmErrorKind::RateLimited,
+        413 => LlmErrorKind::ContextTooLong,
+        _ if mentions(&[
+            "context_length_exceeded",
+            "context length",
+            "context window",
+            "prompt is too long",
+            "too many tokens",
+        ]) =>
+        {
+            LlmErrorKind::ContextTooLong
+        }
+        404 if mentions(&["model"]) => LlmErrorKind::ModelNotFound,
+        _ if mentions(&["model_not_found"]) => LlmErrorKind::ModelNotFound,
+        408 => LlmErrorKind::Timeout,
+        500..=599 => LlmErrorKind::Server,
+        _ => LlmErrorKind::InvalidRequest,
+    }
+}
+
+// 429/5xx/연결 실패 재시도 정책
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    // 첫 재시도 전 대기 시간 (이후 두 배씩)
+    pub base_delay: Duration,
+    // 이보다 오래 기다리라고 하면 재시도하지 않음
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    // retry 번째(0부터) 재시도 전 대기 시간. Retry-After 가 있으면 그대로 따름
+    fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(wait) = retry_after {
+            return wait;
+        }
+        let backoff = self
+            .base_delay
+            .saturating_mul(1 << retry.min(16))
+            .min(self.max_delay);
+        // 여러 요청이 동시에 다시 몰리지 않도록 0~25% 지연을 더함
+        let jitter = chrono::Utc::now().timestamp_subsec_nanos() % 250;
+        backoff + backoff * jitter / 1000
+    }
+}
+
+// Retry-After (초 또는 HTTP 날짜) 나 retry-after-ms 헤더
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    if let Some(ms) = header("retry-after-ms").and_then(|v| v.trim().parse::<u64>().ok()) {
+        return Some(Duration::from_millis(ms));
+    }
+    let value = header("retry-after")?.trim();
+    if let Ok(seconds) = value.parse::<f64>() {
+        return (seconds.is_finite() && seconds >= 0.0).then(|| Duration::from_secs_f64(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = date.timestamp_millis() - chrono::Utc::now().timestamp_millis();
+    Some(Duration::from_millis(wait.max(0) as u64))
+}
+
+// 모델이 거부한 파라미터를 고친 요청 본문 (고칠 것이 없으면 None)
+// temperature 를 지원하지 않는 모델은 빼고, chat completions 의 토큰 제한 이름은 서버가 받는 쪽으로 바꿈
+fn fallback_body(
+    provider: Provider,
+    body: &serde_json::Value,
+    detail: &str,
+    adjusted: &mut Vec<&'static str>,
+) -> Option<serde_json::Value> {
+    let mut retry = body.as_object()?.clone();
+    if detail.contains("temperature") && retry.remove("temperature").is_some() {
+        adjusted.push("temperature");
+    } else if provider == Provider::Chat && !adjusted.contains(&"max_tokens") {
+        // 예전 서버는 max_completion_tokens 를 모르고, 추론 모델은 max_tokens 를 거부함
+        let (from, to) = if retry.contains_key("max_completion_tokens") {
+            ("max_completion_tokens", "max_tokens")
+        } else {
+            ("max_tokens", "max_completion_tokens")
+        };
+        if !detail.contains(from) {
+            return None;
+        }
+        let value = retry.remove(from)?;
+        retry.insert(to.to_string(), value);
+        adjusted.push("max_tokens");
+    } else {
+        return None;
+    }
+    Some(serde_json::Value::Object(retry))
+}
+
+async fn send_llm_request(
+    client: &reqwest::Client,
+    provider: Provider,
+    url: &str,
+    body: &serde_json::Value,
+    api_key: Option<&str>,
+) -> Result<reqwest::Response, reqwest::Error> {
+    provider
+        .authorize(client.post(url).json(body), api_key)
+        .send()
+        .await
+}
+
+pub async fn send_chat(
+    client: &reqwest::Client,
+    provider: Provider,
+    url: &str,
+    body: &serde_json::Value,
+    api_key: Option<&str>,
+) -> Result<reqwest::Response, LlmError> {
+    send_chat_with(
+        client,
+        provider,
+        url,
+        body,
+        api_key,
+        &RetryPolicy::default(),
+    )
+    .await
+}
+
+// 성공 응답이 올 때까지 보냄
+// 400 으로 거부한 파라미터는 고쳐서 바로 다시 보내고, 429/5xx/연결 실패는 정책에 따라 기다렸다 재시도
+pub async fn send_chat_with(
+    client: &reqwest::Client,
+    provider: Provider,
+    url: &str,
+    body: &serde_json::Value,
+    api_key: Option<&str>,
+    policy: &RetryPolicy,
+) -> Result<reqwest::Response, LlmError> {
+    let mut body = body.clone();
+    let mut adjusted = Vec::new();
+    let mut retries = 0;
+    loop {
+        let mut error = match send_llm_request(client, provider, url, &body, api_key).await {
+            Ok(response) if response.status().is_success() => return Ok(response),
+            Ok(response) => {
+                let status = response.status();
+                let wait = retry_after(response.headers());
+                let detail = response.text().await.unwrap_or_default();
+                if status == reqwest::StatusCode::BAD_REQUEST {
+                    if let Some(retry_body) = fallback_body(provider, &body, &detail, &mut adjusted)
+                    {
+                        body = retry_body;
+                        continue;
+                    }
+                }
+                LlmError::from_response(provider, status, &detail, wait)
+            }
+            Err(e) => LlmError::from_reqwest(&e),
+        };
+        error.retries = retries;
+        if !error.retryable() || retries >= policy.max_retries {
+            return Err(error);
+        }
+        let delay = policy.delay(retries, error.retry_after);
+        if delay > policy.max_delay {
+            return Err(error);
+        }
+        retries += 1;
+        tokio::time::sleep(delay).await;
+    }
+}
+
+// server-sent events 디코더. 청크 경계가 줄이나 UTF-8 문자 중간에 걸려도 됨
+// json_lines 면 빈 줄이 아닌 줄 하나가 이벤트 하나 (Ollama)
+#[derive(Default)]
+pub struct SseDecoder {
+    json_lines: bool,
+    buffer: Vec<u8>,
+    data: Vec<String>,
+}
+
+impl SseDecoder {
+    pub fn new(json_lines: bool) -> Self {
+        Self {
+            json_lines,
+            ..Default::default()
+        }
+    }
+
+    // 받은 바이트를 넣고 완성된 이벤트의 data 를 돌려줌
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\r', '\n']);
+            if self.json_lines {
+                if !line.trim().is_empty() {
+                    events.push(line.to_string());
+                }
+            } else if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(self.data.join("\n"));
+                    self.data.clear();
+                }
+            } else if let Some(value) = line.strip_prefix("data:") {
+                self.data
+                    .push(value.strip_prefix(' ').unwrap_or(value).to_string());
+            }
+            // event:, id:, retry:, 주석(:) 줄은 무시
+        }
+        events
+    }
+
+    // 스트림이 빈 줄 없이 끝났을 때 남은 이벤트
+    pub fn finish(&mut self) -> Option<String> {
+        self.push(b"\n\n").pop()
+    }
+}
+
+// 스트림을 끝까지 읽으며 받은 텍스트를 content 에 붙이고 on_delta 로 넘김, 사용량은 usage 에 합침
+// cancel 이 알려지면 응답을 버려 HTTP 연결을 끊고 Ok(false)
+pub async fn read_stream(
+    mut response: reqwest::Response,
+    provider: Provider,
+    cancel: &Notify,
+    content: &mut String,
+    usage: &mut Option<Usage>,
+    mut on_delta: impl FnMut(&str),
+) -> Result<bool, String> {
+    let mut decoder = SseDecoder::new(provider.json_lines());
+    loop {
+        let chunk = tokio::select! {
+            _ = cancel.notified() => return Ok(false),
+            chunk = tokio::time::timeout(STREAM_IDLE_TIMEOUT, response.chunk()) => chunk,
+        };
+        let chunk = chunk
+            .map_err(|_| "LLM stream timed out".to_string())?
+            .map_err(|e| format!("LLM stream failed: {}", e))?;
+        let events = match &chunk {
+            Some(bytes) => decoder.push(bytes),
+            None => decoder.finish().into_iter().collect(),
+        };
+        for data in events {
+            let event = provider.parse_stream_event(&data)?;
+            if let Some(update) = event.usage {
+                usage.get_or_insert_with(Usage::default).merge(update);
+            }
+            if !event.delta.is_empty() {
+                content.push_str(&event.delta);
+                on_delta(&event.delta);
+            }
+            if event.done {
+                return Ok(true);
+            }
+        }
+        if chunk.is_none() {
+            return Ok(true);
+        }
+    }
+}
+
+// 진행 중인 스트리밍 요청 (요청 ID -> 취소 신호)
+#[derive(Default)]
+pub struct LlmRequests {
+    next_id: AtomicU64,
+    active: Mutex<HashMap<u64, Arc<Notify>>>,
+}
+
+impl LlmRequests {
+    pub fn start(&self) -> (u64, Arc<Notify>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let cancel = Arc::new(Notify::new());
+        if let Ok(mut active) = self.active.lock() {
+            active.insert(id, cancel.clone());
+        }
+        (id, cancel)
+    }
+
+    pub fn finish(&self, id: u64) {
+        if let Ok(mut active) = self.active.lock() {
+            active.remove(&id);
+        }
+    }
+
+    // 취소되었거나 끝난 요청이면 false
+    pub fn is_active(&self, id: u64) -> bool {
+        self.active
+            .lock()
+            .map(|active| active.contains_key(&id))
+            .unwrap_or(false)
+    }
+
+    // 이미 끝난 요청이면 false
+    pub fn cancel(&self, id: u64) -> bool {
+        let cancel = self
+            .active
+            .lock()
+            .ok()
+            .and_then(|mut active| active.remove(&id));
+        match cancel {
+            // notify_one 은 아직 기다리지 않는 쪽에도 신호를 남겨 둠
+            Some(cancel) => {
+                cancel.notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // 준비한 응답을 순서대로 하나씩 돌려주는 HTTP 서버. 받은 요청 본문을 모아 둠
+    async fn stub_server(
+        responses: Vec<(&'static str, &'static str, &'static str)>,
+    ) -> (String, Arc<Mutex<Vec<serde_json::Value>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "http://{}/v1/chat/completions",
+            listener.local_addr().unwrap()
+        );
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let log = received.clone();
+        tokio::spawn(async move {
+            for (status, headers, body) in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                let (head_end, length) = loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some(end) = text.find("\r\n\r\n") {
+                        let length = text[..end]
+                            .lines()
+                            .find_map(|line| {
+                                let lower = line.to_lowercase();
+                                lower.strip_prefix("content-length:")?.trim().parse().ok()
+                            })
+                            .unwrap_or(0);
+                        break (end + 4, length);
+                    }
+                };
+                while request.len() < head_end + length {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let body_json = serde_json::from_slice(&request[head_end..]).unwrap_or_default();
+                log.lock().unwrap().push(body_json);
+                let reply = format!(
+                    "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n{}\r\n{}",
+                    status,
+                    body.len(),
+                    headers,
+                    body
+                );
+                socket.write_all(reply.as_bytes()).await.unwrap();
+            }
+        });
+        (url, received)
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(10),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+
+    async fn send(url: &str, body: serde_json::Value) -> Result<reqwest::Response, LlmError> {
+        let client = reqwest::Client::new();
+        send_chat_with(&client, Provider::Chat, url, &body, None, &fast_policy()).await
+    }
+
+    #[tokio::test]
+    async fn retries_rate_limit_honoring_retry_after() {
+        let (url, received) = stub_server(vec![
+            (
+                "429 Too Many Requests",
+                "retry-after: 1\r\n",
+                r#"{"error":{"message":"slow down"}}"#,
+            ),
+            ("503 Service Unavailable", "", "busy"),
+            ("200 OK", "", r#"{"choices":[]}"#),
+        ])
+        .await;
+        let started = std::time::Instant::now();
+        let response = send(&url, json!({"model": "m"})).await.unwrap();
+        assert!(response.status().is_success());
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert_eq!(received.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let (url, _) = stub_server(vec![
+            ("500 Internal Server Error", "", "oops"),
+            ("502 Bad Gateway", "", "oops"),
+            (
+                "503 Service Unavailable",
+                "",
+                r#"{"error":{"message":"overloaded"}}"#,
+            ),
+        ])
+        .await;
+        let error = send(&url, json!({})).await.unwrap_err();
+        assert_eq!(error.kind, LlmErrorKind::Server);
+        assert_eq!(error.retries, 2);
+        assert_eq!(
+            error.to_string(),
+            "LLM server error (503 Service Unavailable): overloaded (after 2 retries)"
+        );
+        // 명령 오류로 프론트엔드에 가는 형태
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({ "kind": "server", "status": 503, "message": "overloaded", "retries": 2 })
+        );
+    }
+
+    #[tokio::test]
+    async fn does_not_wait_longer_than_max_delay() {
+        let (url, received) = stub_server(vec![(
+            "429 Too Many Requests",
+            "retry-after: 120\r\n",
+            "{}",
+        )])
+        .await;
+        let error = send(&url, json!({})).await.unwrap_err();
+        assert_eq!(error.kind, LlmErrorKind::RateLimited);
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_max_tokens_and_drops_temperature() {
+        let (url, received) = stub_server(vec![
+            (
+                "400 Bad Request",
+                "",
+                r#"{"error":{"message":"Unrecognized request argument supplied: max_completion_tokens"}}"#,
+            ),
+            (
+                "400 Bad Request",
+                "",
+                r#"{"error":{"message":"Unsupported value: 'temperature' does not support 0.2"}}"#,
+            ),
+            ("200 OK", "", "{}"),
+        ])
+        .await;
+        send(
+            &url,
+            json!({"model": "m", "temperature": 0.2, "max_completion_tokens": 100}),
+        )
+        .await
+        .unwrap();
+        let received = received.lock().unwrap();
+        assert_eq!(received[1]["max_tokens"], 100);
+        assert!(received[1].get("max_completion_tokens").is_none());
+        assert!(received[2].get("temperature").is_none());
+        assert_eq!(received[2]["max_tokens"], 100);
+    }
+
+    #[tokio::test]
+    async fn classifies_errors_without_retrying() {
+        let cases = [
+            (
+                "401 Unauthorized",
+                r#"{"error":{"message":"Incorrect API key","type":"invalid_request_error"}}"#,
+                LlmErrorKind::Auth,
+            ),
+            (
+                "429 Too Many Requests",
+                r#"{"error":{"message":"You exceeded your current quota","code":"insufficient_quota"}}"#,
+                LlmErrorKind::Quota,
+            ),
+            (
+                "404 Not Found",
+                r#"{"error":{"message":"The model `gpt-9` does not exist","code":"model_not_found"}}"#,
+                LlmErrorKind::ModelNotFound,
+            ),
+            (
+                "400 Bad Request",
+                r#"{"error":{"message":"This model's maximum context length is 8192 tokens","code":"context_length_exceeded"}}"#,
+                LlmErrorKind::ContextTooLong,
+            ),
+            (
+                "422 Unprocessable Entity",
+                r#"{"error":"invalid messages"}"#,
+                LlmErrorKind::InvalidRequest,
+            ),
+        ];
+        for (status, body, kind) in cases {
+            let (url, received) = stub_server(vec![(status, "", body)]).await;
+            let error = send(&url, json!({})).await.unwrap_err();
+            assert_eq!(error.kind, kind, "{}", status);
+            assert_eq!(error.retries, 0);
+            assert_eq!(received.lock().unwrap().len(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_connection_errors() {
+        // 아무도 듣지 않는 포트
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        drop(listener);
+        let error = send(&url, json!({})).await.unwrap_err();
+        assert_eq!(error.kind, LlmErrorKind::Network);
+        assert_eq!(error.retries, 2);
+    }
+
+    #[test]
+    fn parses_retry_after_headers() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("retry-after", "3".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(3)));
+        headers.insert("retry-after-ms", "250".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_millis(250)));
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            "retry-after",
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+    }
+}
@@ -0,0 +1,371 @@
+// LLM 제공자별 API 형식
+// OpenAI chat completions / OpenAI Responses / Anthropic Messages / Ollama 네이티브 API 의
+// 주소, 인증 헤더, 요청 본문, 응답/스트림 파싱, 사용량, 오류 메시지를 맞춤
+use crate::llm::ChatMessage;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+// Anthropic Messages API 는 max_tokens 가 필수
+const ANTHROPIC_DEFAULT_MAX_TOKENS: u32 = 4096;
+
+// URL 로 추측하지 않고 요청에서 명시 (기본값은 기존 동작인 chat completions)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    #[default]
+    #[serde(alias = "openai", alias = "chat-completions")]
+    Chat,
+    #[serde(alias = "openai-responses")]
+    Responses,
+    Anthropic,
+    Ollama,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Usage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl Usage {
+    // 스트림에서 나누어 오는 사용량 합치기 (Anthropic: 시작 때 입력, 끝에 출력)
+    pub fn merge(&mut self, other: Usage) {
+        self.prompt_tokens = self.prompt_tokens.max(other.prompt_tokens);
+        self.completion_tokens = self.completion_tokens.max(other.completion_tokens);
+    }
+}
+
+// 스트림 이벤트 하나에서 얻은 내용
+#[derive(Default)]
+pub struct StreamChunk {
+    pub delta: String,
+    pub usage: Option<Usage>,
+    pub done: bool,
+}
+
+fn model_supports_temperature(model: &str) -> bool {
+    let name = model.trim().to_lowercase();
+    !(name.starts_with("o1") || name.starts_with("o3"))
+}
+
+// base_url 뒤에 prefix(/v1 등)와 path 를 붙임. 이미 path 로 끝나면 그대로
+fn endpoint(base_url: &str, prefix: &str, path: &str) -> String {
+    let trimmed = base_url.trim_end_matches('/');
+    if trimmed.ends_with(path) {
+        trimmed.to_string()
+    } else if trimmed.ends_with(prefix) {
+        format!("{}{}", trimmed, path)
+    } else {
+        format!("{}{}{}", trimmed, prefix, path)
+    }
+}
+
+fn count(value: &Value) -> u64 {
+    value.as_u64().unwrap_or(0)
+}
+
+fn usage_from(value: &Value, prompt: &str, completion: &str) -> Option<Usage> {
+    if value.get(prompt).is_none() && value.get(completion).is_none() {
+        return None;
+    }
+    Some(Usage {
+        prompt_tokens: count(&value[prompt]),
+        completion_tokens: count(&value[completion]),
+    })
+}
+
+// "system" 메시지는 Anthropic 에서 별도 필드
+fn split_system(messages: &[ChatMessage]) -> (String, Vec<&ChatMessage>) {
+    let system = messages
+        .iter()
+        .filter(|m| m.role == "system")
+        .map(|m| m.content.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let rest = messages.iter().filter(|m| m.role != "system").collect();
+    (system, rest)
+}
+
+impl Provider {
+    pub fn label(self) -> &'static str {
+        match self {
+            Provider::Chat => "chat",
+            Provider::Responses => "responses",
+            Provider::Anthropic => "anthropic",
+            Provider::Ollama => "ollama",
+        }
+    }
+
+    pub fn chat_url(self, base_url: &str) -> String {
+        match self {
+            Provider::Chat => endpoint(base_url, "/v1", "/chat/completions"),
+            Provider::Responses => endpoint(base_url, "/v1", "/responses"),
+            Provider::Anthropic => endpoint(base_url, "/v1", "/messages"),
+            Provider::Ollama => endpoint(base_url, "/api", "/chat"),
+        }
+    }
+
+    pub fn models_url(self, base_url: &str) -> String {
+        match self {
+            Provider::Ollama => endpoint(base_url, "/api", "/tags"),
+            _ => endpoint(base_url, "/v1", "/models"),
+        }
+    }
+
+    pub fn authorize(
+        self,
+        builder: reqwest::RequestBuilder,
+        api_key: Option<&str>,
+    ) -> reqwest::RequestBuilder {
+        match (self, api_key) {
+            (Provider::Anthropic, key) => {
+                let builder = builder.header("anthropic-version", ANTHROPIC_VERSION);
+                match key {
+                    Some(key) => builder.header("x-api-key", key),
+                    None => builder,
+                }
+            }
+            (_, Some(key)) => builder.bearer_auth(key),
+            (_, None) => builder,
+        }
+    }
+
+    // Ollama 스트림은 SSE 가 아니라 줄마다 JSON 하나
+    pub fn json_lines(self) -> bool {
+        self == Provider::Ollama
+    }
+
+    pub fn body(
+        self,
+        model: &str,
+        messages: &[ChatMessage],
+        temperature: Option<f32>,
+        max_tokens: Option<u32>,
+        stream: bool,
+    ) -> Value {
+        let temperature = temperature.filter(|_| model_supports_temperature(model));
+        let mut body = match self {
+            Provider::Chat => {
+                let mut body = json!({ "model": model, "messages": messages });
+                if let Some(max_tokens) = max_tokens {
+                    body["max_completion_tokens"] = json!(max_tokens);
+                }
+                if stream {
+                    // 마지막 청크에 사용량 포함
+                    body["stream_options"] = json!({ "include_usage": true });
+                }
+                body
+            }
+            Provider::Responses => {
+                let mut body = json!({ "model": model, "input": messages, "store": false });
+                if let Some(max_tokens) = max_tokens {
+                    body["max_output_tokens"] = json!(max_tokens);
+                }
+                body
+            }
+            Provider::Anthropic => {
+                let (system, rest) = split_system(messages);
+                let mut body = json!({
+                    "model": model,
+                    "messages": rest,
+                    "max_tokens": max_tokens.unwrap_or(ANTHROPIC_DEFAULT_MAX_TOKENS),
+                });
+                if !system.is_empty() {
+                    body["system"] = json!(system);
+                }
+                body
+            }
+            Provider::Ollama => {
+                let mut options = json!({});
+                if let Some(temperature) = temperature {
+                    options["temperature"] = json!(temperature);
+                }
+                if let Some(max_tokens) = max_tokens {
+                    options["num_predict"] = json!(max_tokens);
+                }
+                // Ollama 는 stream 을 생략하면 스트리밍이 기본값
+                return json!({
+                    "model": model,
+                    "messages": messages,
+                    "stream": stream,
+                    "options": options,
+                });
+            }
+        };
+        if let Some(temperature) = temperature {
+            body["temperature"] = json!(temperature);
+        }
+        if stream {
+            body["stream"] = json!(true);
+        }
+        body
+    }
+
+    pub fn parse_response(self, value: &Value) -> Result<(String, Option<Usage>), String> {
+        if let Some(message) = self.error_in(value) {
+            return Err(format!("LLM request failed: {}", message));
+        }
+        let (content, usage) = match self {
+            Provider::Chat => {
+                if value.get("choices").is_none() {
+                    return Err("Failed to parse LLM response: missing choices".into());
+                }
+                (
+                    value["choices"][0]["message"]["content"]
+                        .as_str()
+                        .map(str::to_string),
+                    usage_from(&value["usage"], "prompt_tokens", "completion_tokens"),
+                )
+            }
+            Provider::Responses => {
+                let text: String = value["output"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter(|item| item["type"] == "message")
+                    .flat_map(|item| item["content"].as_array().into_iter().flatten())
+                    .filter(|part| part["type"] == "output_text")
+                    .filter_map(|part| part["text"].as_str())
+                    .collect();
+                (
+                    Some(text),
+                    usage_from(&value["usage"], "input_tokens", "output_tokens"),
+                )
+            }
+            Provider::Anthropic => {
+                let text: String = value["content"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter(|part| part["type"] == "text")
+                    .filter_map(|part| part["text"].as_str())
+                    .collect();
+                (
+                    Some(text),
+                    usage_from(&value["usage"], "input_tokens", "output_tokens"),
+                )
+            }
+            Provider::Ollama => (
+                value["message"]["content"].as_str().map(str::to_string),
+                usage_from(value, "prompt_eval_count", "eval_count"),
+            ),
+        };
+        Ok((content.unwrap_or_default(), usage))
+    }
+
+    pub fn parse_stream_event(self, data: &str) -> Result<StreamChunk, String> {
+        let data = data.trim();
+        if data == "[DONE]" {
+            return Ok(StreamChunk {
+                done: true,
+                ..Default::default()
+            });
+        }
+        let value: Value =
+            serde_json::from_str(data).map_err(|e| format!("Invalid stream chunk: {}", e))?;
+        if let Some(message) = self.error_in(&value) {
+            return Err(format!("LLM stream failed: {}", message));
+        }
+        let mut chunk = StreamChunk::default();
+        match self {
+            // {"choices":[{"delta":{"content":"..."}}]}, 마지막 청크: {"choices":[],"usage":{...}}
+            Provider::Chat => {
+                chunk.delta = value["choices"][0]["delta"]["content"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string();
+                chunk.usage = usage_from(&value["usage"], "prompt_tokens", "completion_tokens");
+            }
+            Provider::Responses => match value["type"].as_str().unwrap_or_default() {
+                "response.output_text.delta" => {
+                    chunk.delta = value["delta"].as_str().unwrap_or_default().to_string();
+                }
+                "response.completed" | "response.incomplete" => {
+                    chunk.usage =
+                        usage_from(&value["response"]["usage"], "input_tokens", "output_tokens");
+                    chunk.done = true;
+                }
+                "error" => {
+                    let message = value["message"].as_str().unwrap_or("stream error");
+                    return Err(format!("LLM stream failed: {}", message));
+                }
+                "response.failed" => {
+                    let message = value["response"]["error"]["message"]
+                        .as_str()
+                        .unwrap_or("response failed");
+                    return Err(format!("LLM stream failed: {}", message));
+                }
+                _ => {}
+            },
+            Provider::Anthropic => match value["type"].as_str().unwrap_or_default() {
+                "message_start" => {
+                    chunk.usage =
+                        usage_from(&value["message"]["usage"], "input_tokens", "output_tokens");
+                }
+                "content_block_delta" => {
+                    chunk.delta = value["delta"]["text"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string();
+                }
+                "message_delta" => {
+                    chunk.usage = usage_from(&value["usage"], "input_tokens", "output_tokens");
+                }
+                "message_stop" => chunk.done = true,
+                _ => {}
+            },
+            Provider::Ollama => {
+                chunk.delta = value["message"]["content"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string();
+                if value["done"].as_bool() == Some(true) {
+                    chunk.usage = usage_from(&value, "prompt_eval_count", "eval_count");
+                    chunk.done = true;
+                }
+            }
+        }
+        Ok(chunk)
+    }
+
+    pub fn parse_models(self, value: &Value) -> Vec<String> {
+        let (list, key) = match self {
+            Provider::Ollama => (&value["models"], "name"),
+            _ => (&value["data"], "id"),
+        };
+        list.as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|item| item[key].as_str().map(str::to_string))
+            .collect()
+    }
+
+    // 오류 응답 본문에서 사람이 읽을 메시지
+    // OpenAI: {"error":{"message":..}}, Anthropic: {"type":"error","error":{"type":..,"message":..}},
+    // Ollama: {"error":"..."}
+    fn error_in(self, value: &Value) -> Option<String> {
+        let error = value.get("error").filter(|e| !e.is_null())?;
+        if let Some(text) = error.as_str() {
+            return Some(text.to_string());
+        }
+        let message = error["message"].as_str().unwrap_or_default();
+        let kind = error["type"]
+            .as_str()
+            .or_else(|| error["code"].as_str())
+            .unwrap_or_default();
+        Some(match (kind.is_empty(), message.is_empty()) {
+            (_, true) => error.to_string(),
+            (true, false) => message.to_string(),
+            (false, false) => format!("{} ({})", message, kind),
+        })
+    }
+
+    pub fn error_detail(self, body: &str) -> String {
+        serde_json::from_str::<Value>(body)
+            .ok()
+            .and_then(|value| self.error_in(&value))
+            .unwrap_or_else(|| body.trim().to_string())
+    }
+}
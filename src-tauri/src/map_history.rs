@@ -0,0 +1,334 @@
+// 레지스터 맵 변경 이력
+// 맵을 저장할 때마다 history/<맵 이름>/<버전>.yaml 로 보관하고, 바뀐 레지스터/필드를 구조적으로 비교해 요약을 남김
+use crate::register_map::{self, Field, Register, RegisterMap};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const INDEX_FILE: &str = "versions.json";
+// 요약에 이름을 나열할 최대 레지스터 수
+const SUMMARY_NAMES: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+// 레지스터 하나의 변경 (레지스터는 이름으로 대응시킴 - 리비전마다 주소가 옮겨질 수 있음)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapChange {
+    pub kind: ChangeKind,
+    pub register: String,
+    pub address: u32,
+    // 예: "reset 0x00 -> 0x01", "field EN added", "field MODE: bit 1 -> 2"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapVersion {
+    pub version: u32,
+    pub timestamp: String,
+    pub author: String,
+    pub summary: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub registers: usize,
+    #[serde(default)]
+    pub changes: Vec<MapChange>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapVersionDiff {
+    pub name: String,
+    pub from: u32,
+    // None 이면 현재 저장된 맵과 비교
+    pub to: Option<u32>,
+    pub summary: String,
+    pub changes: Vec<MapChange>,
+}
+
+// 누가 바꿨는지 (지정하지 않으면 OS 사용자 이름)
+pub fn default_author() -> String {
+    std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .ok()
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| "unknown".into())
+}
+
+fn hex(value: Option<u64>) -> String {
+    value
+        .map(|v| format!("0x{:X}", v))
+        .unwrap_or_else(|| "-".into())
+}
+
+fn same_json<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+fn field_details(old: &Field, new: &Field) -> Vec<String> {
+    let mut details = Vec::new();
+    if old.bit != new.bit || old.size != new.size {
+        details.push(format!(
+            "bits [{}:{}] -> [{}:{}]",
+            old.msb(),
+            old.bit,
+            new.msb(),
+            new.bit
+        ));
+    }
+    if old.access != new.access {
+        let label = |f: &Field| f.access.map(|a| a.label()).unwrap_or("inherit");
+        details.push(format!("access {} -> {}", label(old), label(new)));
+    }
+    if old.reset != new.reset {
+        details.push(format!("reset {} -> {}", hex(old.reset), hex(new.reset)));
+    }
+    if old.volatile != new.volatile {
+        details.push(format!("volatile {} -> {}", old.volatile, new.volatile));
+    }
+    if old.description != new.description {
+        details.push("description".into());
+    }
+    if !same_json(&old.enums, &new.enums) {
+        details.push("enums".into());
+    }
+    if !same_json(&old.conversion, &new.conversion) {
+        details.push("conversion".into());
+    }
+    details
+}
+
+fn register_details(old: &Register, new: &Register) -> Vec<String> {
+    let mut details = Vec::new();
+    if old.address != new.address {
+        details.push(format!(
+            "address 0x{:04X} -> 0x{:04X}",
+            old.address, new.address
+        ));
+    }
+    if old.page != new.page {
+        details.push(format!(
+            "page {} -> {}",
+            hex(old.page.map(u64::from)),
+            hex(new.page.map(u64::from))
+        ));
+    }
+    if old.width != new.width {
+        details.push(format!("width {} -> {}", old.width, new.width));
+    }
+    if old.access != new.access {
+        details.push(format!(
+            "access {} -> {}",
+            old.access.label(),
+            new.access.label()
+        ));
+    }
+    if old.reset != new.reset {
+        details.push(format!("reset {} -> {}", hex(old.reset), hex(new.reset)));
+    }
+    if old.verify != new.verify {
+        details.push("verify".into());
+    }
+    if old.description != new.description {
+        details.push("description".into());
+    }
+    for field in &old.fields {
+        match new.fields.iter().find(|f| f.name == field.name) {
+            None => details.push(format!("field {} removed", field.name)),
+            Some(changed) => {
+                let field_changes = field_details(field, changed);
+                if !field_changes.is_empty() {
+                    details.push(format!(
+                        "field {}: {}",
+                        field.name,
+                        field_changes.join(", ")
+                    ));
+                }
+            }
+        }
+    }
+    for field in &new.fields {
+        if !old.fields.iter().any(|f| f.name == field.name) {
+            details.push(format!("field {} added", field.name));
+        }
+    }
+    details
+}
+
+// 두 맵의 구조 비교 (마지막으로 알려진 값 같은 캐시는 무시)
+pub fn diff(old: &RegisterMap, new: &RegisterMap) -> Vec<MapChange> {
+    let mut changes = Vec::new();
+    for register in &old.registers {
+        match new.registers.iter().find(|r| r.name == register.name) {
+            None => changes.push(MapChange {
+                kind: ChangeKind::Removed,
+                register: register.name.clone(),
+                address: register.address,
+                details: Vec::new(),
+            }),
+            Some(changed) => {
+                let details = register_details(register, changed);
+                if !details.is_empty() {
+                    changes.push(MapChange {
+                        kind: ChangeKind::Modified,
+                        register: register.name.clone(),
+                        address: changed.address,
+                        details,
+                    });
+                }
+            }
+        }
+    }
+    for register in &new.registers {
+        if !old.registers.iter().any(|r| r.name == register.name) {
+            changes.push(MapChange {
+                kind: ChangeKind::Added,
+                register: register.name.clone(),
+                address: register.address,
+                details: Vec::new(),
+            });
+        }
+    }
+    changes.sort_by_key(|c| c.address);
+    changes
+}
+
+// 예: "Added VOUT2; modified CTRL, STATUS"
+pub fn summarize(changes: &[MapChange]) -> String {
+    if changes.is_empty() {
+        return "No structural changes".into();
+    }
+    let mut parts = Vec::new();
+    for (kind, label) in [
+        (ChangeKind::Added, "added"),
+        (ChangeKind::Removed, "removed"),
+        (ChangeKind::Modified, "modified"),
+    ] {
+        let names: Vec<&str> = changes
+            .iter()
+            .filter(|c| c.kind == kind)
+            .map(|c| c.register.as_str())
+            .collect();
+        if names.is_empty() {
+            continue;
+        }
+        let mut text = format!(
+            "{} {}",
+            label,
+            names
+                .iter()
+                .take(SUMMARY_NAMES)
+                .copied()
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        if names.len() > SUMMARY_NAMES {
+            text.push_str(&format!(" and {} more", names.len() - SUMMARY_NAMES));
+        }
+        parts.push(text);
+    }
+    let summary = parts.join("; ");
+    let mut chars = summary.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => summary,
+    }
+}
+
+fn parse(content: &str) -> Result<RegisterMap, String> {
+    register_map::parse(content).map_err(|issue| issue.to_string())
+}
+
+pub fn diff_content(old: &str, new: &str) -> Result<Vec<MapChange>, String> {
+    Ok(diff(&parse(old)?, &parse(new)?))
+}
+
+fn version_path(directory: &Path, version: u32) -> PathBuf {
+    directory.join(format!("{}.yaml", version))
+}
+
+pub fn list(directory: &Path) -> Result<Vec<MapVersion>, String> {
+    let path = directory.join(INDEX_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+}
+
+pub fn load(directory: &Path, version: u32) -> Result<String, String> {
+    fs::read_to_string(version_path(directory, version))
+        .map_err(|e| format!("Register map version {} not found: {}", version, e))
+}
+
+// 새 버전 기록. 직전 버전과 내용이 같으면 기록하지 않고 None
+pub fn record(
+    directory: &Path,
+    content: &str,
+    author: &str,
+    note: Option<&str>,
+) -> Result<Option<MapVersion>, String> {
+    let mut versions = list(directory)?;
+    // 버전 파일이 지워졌으면 그 앞의 남아 있는 버전과 비교
+    let latest = versions
+        .iter()
+        .rev()
+        .find_map(|v| load(directory, v.version).ok());
+    if latest.as_deref() == Some(content) {
+        return Ok(None);
+    }
+    // 파싱되지 않는 내용도 저장은 되므로 버전으로 남김 (변경 목록만 계산할 수 없음)
+    // 변경 목록은 파싱되는 마지막 버전과 비교
+    let previous = versions
+        .iter()
+        .rev()
+        .find_map(|v| parse(&load(directory, v.version).ok()?).ok());
+    let map = parse(content).ok();
+    let registers = map.as_ref().map_or(0, |map| map.registers.len());
+    // 첫 버전은 변경 목록 없이 요약만 (레지스터 수천 개를 전부 "추가"로 남기지 않음)
+    let (summary, changes) = match (map, previous) {
+        (None, _) => ("Unparseable content".to_string(), Vec::new()),
+        (Some(map), Some(old)) => {
+            let changes = diff(&old, &map);
+            (summarize(&changes), changes)
+        }
+        (Some(_), None) if !versions.is_empty() => {
+            ("Replaced unreadable version".to_string(), Vec::new())
+        }
+        (Some(_), None) => (
+            format!("Initial version ({} registers)", registers),
+            Vec::new(),
+        ),
+    };
+    let version = MapVersion {
+        version: versions.last().map(|v| v.version + 1).unwrap_or(1),
+        timestamp: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+        author: author.to_string(),
+        summary,
+        note: note
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(str::to_string),
+        registers,
+        changes,
+    };
+
+    fs::create_dir_all(directory)
+        .map_err(|e| format!("Failed to create history directory: {}", e))?;
+    fs::write(version_path(directory, version.version), content)
+        .map_err(|e| format!("Failed to write register map version: {}", e))?;
+    versions.push(version.clone());
+    let json = serde_json::to_string_pretty(&versions).map_err(|e| e.to_string())?;
+    fs::write(directory.join(INDEX_FILE), json)
+        .map_err(|e| format!("Failed to write register map history: {}", e))?;
+    Ok(Some(version))
+}
@@ -0,0 +1,476 @@
+// 레지스터 맵 라이브러리
+// 앱 데이터 디렉터리의 register_maps/<이름>.yaml 여러 개를 관리하고, 기본 맵과 장치 프로필별 맵을 library.json 에 기록
+// 저장할 때마다 register_maps/history/<이름>/ 에 버전을 남김
+use crate::map_history::{self, MapVersion, MapVersionDiff};
+use crate::recorder::sanitize_name;
+use crate::register_map::DEFAULT_MAP;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+// 앱에 내장된 맵 (읽기 전용)
+pub const BUILTIN_MAP: &str = "default";
+// 예전 버전이 실행 파일 옆에 저장하던 사용자 맵을 옮겨 올 때 쓰는 이름
+const MIGRATED_MAP: &str = "user";
+const INDEX_FILE: &str = "library.json";
+const HISTORY_DIR: &str = "history";
+// 지운 맵의 이력 보관 위치 (맵 이름에는 '.' 이 들어갈 수 없어 겹치지 않음)
+const DELETED_DIR: &str = ".deleted";
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LibraryIndex {
+    // 앱을 시작할 때 쓰는 맵 (None 이면 내장 맵)
+    #[serde(default)]
+    active: Option<String>,
+    // 장치 프로필 이름 -> 맵 이름
+    #[serde(default)]
+    profiles: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapEntry {
+    pub name: String,
+    pub path: Option<String>,
+    pub builtin: bool,
+    pub active: bool,
+    pub modified: u64,
+    // 이 맵을 쓰도록 지정된 장치 프로필
+    pub profiles: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryInfo {
+    pub directory: Option<String>,
+    pub active: String,
+    // 이번 세션에서만 고른 맵이면 true (다음 실행 때는 기본 맵으로 돌아감)
+    pub session_only: bool,
+    pub maps: Vec<MapEntry>,
+    pub profiles: BTreeMap<String, String>,
+}
+
+pub struct MapLibrary {
+    // 앱 시작(setup) 전에는 None
+    dir: Option<PathBuf>,
+    index: LibraryIndex,
+    // 장치 프로필로 고른 맵 (저장하지 않음)
+    session: Option<String>,
+}
+
+impl MapLibrary {
+    pub fn new() -> Self {
+        Self {
+            dir: None,
+            index: LibraryIndex::default(),
+            session: None,
+        }
+    }
+
+    // 라이브러리 폴더를 열고, 처음 실행이면 예전 registers.user.yaml 을 "user" 맵으로 옮김
+    pub fn open(dir: PathBuf, legacy: Option<PathBuf>) -> Result<Self, String> {
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create register map directory: {}", e))?;
+        let index_path = dir.join(INDEX_FILE);
+        let first_launch = !index_path.exists();
+        let index = if first_launch {
+            LibraryIndex::default()
+        } else {
+            let content = fs::read_to_string(&index_path)
+                .map_err(|e| format!("Failed to read {}: {}", index_path.display(), e))?;
+            serde_json::from_str(&content)
+                .map_err(|e| format!("Invalid {}: {}", index_path.display(), e))?
+        };
+        let mut library = Self {
+            dir: Some(dir),
+            index,
+            session: None,
+        };
+        if first_launch {
+            if let Some(legacy) = legacy.filter(|path| path.is_file()) {
+                let content = fs::read_to_string(&legacy)
+                    .map_err(|e| format!("Failed to read {}: {}", legacy.display(), e))?;
+                // 원본은 읽기 전용 위치일 수 있으므로 복사만 함
+                library.write(
+                    MIGRATED_MAP,
+                    &content,
+                    true,
+                    &map_history::default_author(),
+                    Some("Migrated from registers.user.yaml"),
+                )?;
+                library.index.active = Some(MIGRATED_MAP.to_string());
+            }
+            library.save_index()?;
+        }
+        // 파일이 지워졌으면 내장 맵으로
+        if let Some(active) = library.index.active.clone() {
+            if !library.exists(&active) {
+                library.index.active = None;
+            }
+        }
+        Ok(library)
+    }
+
+    fn dir(&self) -> Result<&Path, String> {
+        self.dir
+            .as_deref()
+            .ok_or_else(|| "Register map library is not available".to_string())
+    }
+
+    fn save_index(&self) -> Result<(), String> {
+        let path = self.dir()?.join(INDEX_FILE);
+        let json = serde_json::to_string_pretty(&self.index).map_err(|e| e.to_string())?;
+        fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    // 저장 가능한 맵 이름 (파일 이름에 쓸 수 있는 문자만)
+    pub fn check_name(name: &str) -> Result<String, String> {
+        let file = sanitize_name(name);
+        if file.is_empty() {
+            return Err("Register map name is empty".into());
+        }
+        if file == BUILTIN_MAP {
+            return Err(format!("'{}' is the built-in register map", BUILTIN_MAP));
+        }
+        Ok(file)
+    }
+
+    // 라이브러리에 기록할 이름 (내장 맵은 그대로, 나머지는 파일 이름과 같게)
+    fn map_name(name: &str) -> Result<String, String> {
+        if name == BUILTIN_MAP {
+            Ok(BUILTIN_MAP.to_string())
+        } else {
+            Self::check_name(name)
+        }
+    }
+
+    pub fn path(&self, name: &str) -> Result<PathBuf, String> {
+        Ok(self
+            .dir()?
+            .join(format!("{}.yaml", Self::check_name(name)?)))
+    }
+
+    pub fn exists(&self, name: &str) -> bool {
+        name == BUILTIN_MAP || self.path(name).is_ok_and(|path| path.is_file())
+    }
+
+    pub fn active(&self) -> &str {
+        self.session
+            .as_deref()
+            .or(self.index.active.as_deref())
+            .unwrap_or(BUILTIN_MAP)
+    }
+
+    pub fn read(&self, name: &str) -> Result<String, String> {
+        if name == BUILTIN_MAP {
+            return Ok(DEFAULT_MAP.to_string());
+        }
+        let path = self.path(name)?;
+        fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read register map '{}': {}", name, e))
+    }
+
+    // 활성 맵 파일 경로 (내장 맵이면 None)
+    pub fn active_path(&self) -> Result<Option<PathBuf>, String> {
+        match self.active() {
+            BUILTIN_MAP => Ok(None),
+            name => self.path(name).map(Some),
+        }
+    }
+
+    fn history_dir(&self, name: &str) -> Result<PathBuf, String> {
+        Ok(self.dir()?.join(HISTORY_DIR).join(Self::check_name(name)?))
+    }
+
+    pub fn write(
+        &mut self,
+        name: &str,
+        content: &str,
+        overwrite: bool,
+        author: &str,
+        note: Option<&str>,
+    ) -> Result<PathBuf, String> {
+        let path = self.path(name)?;
+        if path.exists() && !overwrite {
+            return Err(format!("Register map '{}' already exists", name));
+        }
+        let history = self.history_dir(name)?;
+        // 이력이 생기기 전에 만든 맵은 덮어쓰기 전 내용을 첫 버전으로 보관
+        if path.is_file() && map_history::list(&history)?.is_empty() {
+            if let Ok(existing) = fs::read_to_string(&path) {
+                log_history(
+                    name,
+                    map_history::record(
+                        &history,
+                        &existing,
+                        "unknown",
+                        Some("Saved before history was enabled"),
+                    ),
+                );
+            }
+        }
+        fs::write(&path, content).map_err(|e| format!("Failed to write register map: {}", e))?;
+        // 이력을 남기지 못해도(디스크 오류 등) 파일 저장은 그대로
+        log_history(name, map_history::record(&history, content, author, note));
+        Ok(path)
+    }
+
+    // 활성 맵에 저장. 내장 맵은 바꿀 수 없으므로 비어 있는 "user" 이름으로 새 맵을 만들어 활성으로 바꿈
+    pub fn write_active(
+        &mut self,
+        content: &str,
+        author: &str,
+        note: Option<&str>,
+    ) -> Result<PathBuf, String> {
+        let active = self.active().to_string();
+        if active != BUILTIN_MAP {
+            return self.write(&active, content, true, author, note);
+        }
+        let name = self.free_name(MIGRATED_MAP)?;
+        let path = self.write(&name, content, false, author, note)?;
+        self.select(&name, true)?;
+        Ok(path)
+    }
+
+    // base, base-2, base-3 … 중 아직 없는 이름
+    fn free_name(&self, base: &str) -> Result<String, String> {
+        let base = Self::check_name(base)?;
+        (1..=999)
+            .map(|n| match n {
+                1 => base.clone(),
+                n => format!("{}-{}", base, n),
+            })
+            .find(|name| !self.exists(name))
+            .ok_or_else(|| format!("No free register map name for '{}'", base))
+    }
+
+    // name=None 이면 활성 맵
+    fn stored_name(&self, name: Option<&str>) -> Result<String, String> {
+        let name = name.unwrap_or(self.active());
+        if name == BUILTIN_MAP {
+            return Err("The built-in register map has no history".into());
+        }
+        Self::check_name(name)
+    }
+
+    pub fn versions(&self, name: Option<&str>) -> Result<Vec<MapVersion>, String> {
+        map_history::list(&self.history_dir(&self.stored_name(name)?)?)
+    }
+
+    pub fn version_content(&self, name: Option<&str>, version: u32) -> Result<String, String> {
+        map_history::load(&self.history_dir(&self.stored_name(name)?)?, version)
+    }
+
+    // to=None 이면 현재 저장된 맵과 비교
+    pub fn diff_versions(
+        &self,
+        name: Option<&str>,
+        from: u32,
+        to: Option<u32>,
+    ) -> Result<MapVersionDiff, String> {
+        let name = self.stored_name(name)?;
+        let old = self.version_content(Some(&name), from)?;
+        let new = match to {
+            Some(to) => self.version_content(Some(&name), to)?,
+            None => self.read(&name)?,
+        };
+        let changes = map_history::diff_content(&old, &new)?;
+        Ok(MapVersionDiff {
+            summary: map_history::summarize(&changes),
+            name,
+            from,
+            to,
+            changes,
+        })
+    }
+
+    // 예전 버전 내용을 새 버전으로 저장 (이력은 지우지 않음)
+    pub fn rollback(
+        &mut self,
+        name: Option<&str>,
+        version: u32,
+        author: &str,
+    ) -> Result<Vec<MapVersion>, String> {
+        let name = self.stored_name(name)?;
+        let content = self.version_content(Some(&name), version)?;
+        let note = format!("Rolled back to version {}", version);
+        self.write(&name, &content, true, author, Some(&note))?;
+        self.versions(Some(&name))
+    }
+
+    // persist=false 면 이번 세션에서만 사용
+    pub fn select(&mut self, name: &str, persist: bool) -> Result<(), String> {
+        if !self.exists(name) {
+            return Err(format!("Register map '{}' not found", name));
+        }
+        // rename/delete 가 파일 이름으로 비교하므로 정리된 이름을 기록
+        let name = Self::map_name(name)?;
+        if persist {
+            self.index.active = (name != BUILTIN_MAP).then_some(name);
+            self.session = None;
+            self.save_index()
+        } else {
+            self.session = Some(name);
+            Ok(())
+        }
+    }
+
+    // name=None 이면 프로필 지정 해제
+    pub fn bind_profile(&mut self, profile: &str, name: Option<&str>) -> Result<(), String> {
+        let profile = profile.trim();
+        if profile.is_empty() {
+            return Err("Device profile name is empty".into());
+        }
+        match name {
+            Some(name) => {
+                if !self.exists(name) {
+                    return Err(format!("Register map '{}' not found", name));
+                }
+                self.index
+                    .profiles
+                    .insert(profile.to_string(), Self::map_name(name)?);
+            }
+            None => {
+                self.index.profiles.remove(profile);
+            }
+        }
+        self.save_index()
+    }
+
+    pub fn profile_map(&self, profile: &str) -> Option<&str> {
+        self.index.profiles.get(profile.trim()).map(String::as_str)
+    }
+
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<String, String> {
+        let source = self.path(from)?;
+        if !source.is_file() {
+            return Err(format!("Register map '{}' not found", from));
+        }
+        let to = Self::check_name(to)?;
+        let target = self.path(&to)?;
+        if target.exists() {
+            return Err(format!("Register map '{}' already exists", to));
+        }
+        fs::rename(&source, &target)
+            .map_err(|e| format!("Failed to rename register map: {}", e))?;
+        let history = self.history_dir(from)?;
+        if history.is_dir() {
+            if let Err(e) = fs::rename(&history, self.history_dir(&to)?) {
+                eprintln!("Failed to move history of register map '{}': {}", from, e);
+            }
+        }
+        let from = Self::check_name(from)?;
+        if self.index.active.as_deref() == Some(from.as_str()) {
+            self.index.active = Some(to.clone());
+        }
+        if self.session.as_deref() == Some(from.as_str()) {
+            self.session = Some(to.clone());
+        }
+        for map in self.index.profiles.values_mut() {
+            if *map == from {
+                *map = to.clone();
+            }
+        }
+        self.save_index()?;
+        Ok(to)
+    }
+
+    // 맵을 지우고 이력은 history/.deleted/<이름>-<시각>/ 으로 옮겨 보관
+    // 지운 맵을 쓰던 기본값/프로필은 내장 맵으로 돌아감
+    pub fn delete(&mut self, name: &str) -> Result<(), String> {
+        let path = self.path(name)?;
+        if !path.is_file() {
+            return Err(format!("Register map '{}' not found", name));
+        }
+        let name = Self::check_name(name)?;
+        // 이력을 옮기지 못하면 맵도 지우지 않음 (같은 이름으로 새로 만든 맵이 예전 이력을 잇지 않게)
+        let history = self.history_dir(&name)?;
+        if history.is_dir() {
+            let archive = self.dir()?.join(HISTORY_DIR).join(DELETED_DIR);
+            fs::create_dir_all(&archive)
+                .map_err(|e| format!("Failed to create {}: {}", archive.display(), e))?;
+            let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S%3f");
+            fs::rename(&history, archive.join(format!("{}-{}", name, stamp)))
+                .map_err(|e| format!("Failed to archive register map history: {}", e))?;
+        }
+        fs::remove_file(&path).map_err(|e| format!("Failed to delete register map: {}", e))?;
+        if self.index.active.as_deref() == Some(name.as_str()) {
+            self.index.active = None;
+        }
+        if self.session.as_deref() == Some(name.as_str()) {
+            self.session = None;
+        }
+        self.index.profiles.retain(|_, map| *map != name);
+        self.save_index()
+    }
+
+    pub fn info(&self) -> Result<LibraryInfo, String> {
+        let active = self.active().to_string();
+        let profiles_of = |name: &str| -> Vec<String> {
+            self.index
+                .profiles
+                .iter()
+                .filter(|(_, map)| map.as_str() == name)
+                .map(|(profile, _)| profile.clone())
+                .collect()
+        };
+        let mut maps = vec![MapEntry {
+            name: BUILTIN_MAP.to_string(),
+            path: None,
+            builtin: true,
+            active: active == BUILTIN_MAP,
+            modified: 0,
+            profiles: profiles_of(BUILTIN_MAP),
+        }];
+
+        let mut stored = Vec::new();
+        if let Some(dir) = &self.dir {
+            let entries = fs::read_dir(dir)
+                .map_err(|e| format!("Failed to read register map directory: {}", e))?;
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if !path.is_file() || path.extension().is_none_or(|ext| ext != "yaml") {
+                    continue;
+                }
+                let Some(name) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+                    continue;
+                };
+                let modified = entry
+                    .metadata()
+                    .ok()
+                    .and_then(|m| m.modified().ok())
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or(0);
+                stored.push(MapEntry {
+                    active: active == name,
+                    profiles: profiles_of(&name),
+                    path: Some(path.display().to_string()),
+                    builtin: false,
+                    modified,
+                    name,
+                });
+            }
+        }
+        stored.sort_by(|a, b| a.name.cmp(&b.name));
+        maps.extend(stored);
+
+        Ok(LibraryInfo {
+            directory: self.dir.as_ref().map(|dir| dir.display().to_string()),
+            session_only: self.session.is_some(),
+            active,
+            maps,
+            profiles: self.index.profiles.clone(),
+        })
+    }
+}
+
+// 이력 기록 실패는 저장을 막지 않고 로그로 남김
+fn log_history(name: &str, result: Result<Option<MapVersion>, String>) {
+    if let Err(e) = result {
+        eprintln!("Register map history not recorded for '{}': {}", name, e);
+    }
+}
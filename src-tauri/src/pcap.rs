@@ -0,0 +1,394 @@
+// 세션 녹화를 Wireshark에서 열 수 있는 PCAPNG로 변환
+//
+// - UART/텍스트 바이트: LINKTYPE_USER0 + 1바이트 방향 헤더 (0 = RX, 1 = TX)
+// - FT260/FT2232 레지스터 트랜잭션: LINKTYPE_I2C_LINUX (bus + flags 의사 헤더)
+//   실제로는 RREG/WREG 텍스트 명령으로 접근하므로, 버스에서 캡처한 것이 아니라 합성한 패킷임을
+//   인터페이스 이름/설명과 패킷 주석에 표시
+// - CAN 프레임: LINKTYPE_CAN_SOCKETCAN
+use crate::recorder::{Direction, RecordEntry, RegisterRecord};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+const LINKTYPE_USER0: u16 = 147;
+const LINKTYPE_I2C_LINUX: u16 = 209;
+const LINKTYPE_CAN_SOCKETCAN: u16 = 227;
+
+const BLOCK_SHB: u32 = 0x0A0D_0D0A;
+const BLOCK_IDB: u32 = 0x0000_0001;
+const BLOCK_EPB: u32 = 0x0000_0006;
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+
+const OPT_END: u16 = 0;
+const OPT_COMMENT: u16 = 1;
+const OPT_SHB_USERAPPL: u16 = 4;
+const OPT_IF_NAME: u16 = 2;
+const OPT_IF_DESCRIPTION: u16 = 3;
+const OPT_IF_TSRESOL: u16 = 9;
+const OPT_EPB_FLAGS: u16 = 2;
+
+// epb_flags 방향 비트
+const EPB_INBOUND: u32 = 0b01;
+const EPB_OUTBOUND: u32 = 0b10;
+
+// Linux i2c_msg 의 I2C_M_RD
+const I2C_M_RD: u32 = 0x0001;
+const CAN_EFF_FLAG: u32 = 0x8000_0000;
+const CANFD_FDF: u8 = 0x04;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PcapOptions {
+    // 레지스터 트랜잭션을 I2C 패킷으로 변환할 때 사용할 7비트 슬레이브 주소
+    // 녹화에 I2C 레지스터 트랜잭션이 있으면 반드시 지정해야 함 (0 은 general call 주소라 쓸 수 없음)
+    #[serde(default)]
+    pub i2c_address: Option<u8>,
+    #[serde(default = "default_width")]
+    pub register_address_bytes: u8,
+    #[serde(default = "default_width")]
+    pub register_value_bytes: u8,
+}
+
+fn default_width() -> u8 {
+    1
+}
+
+impl Default for PcapOptions {
+    fn default() -> Self {
+        Self {
+            i2c_address: None,
+            register_address_bytes: default_width(),
+            register_value_bytes: default_width(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PcapInterface {
+    pub name: String,
+    pub link_type: u16,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PcapExportSummary {
+    pub path: String,
+    pub packets: usize,
+    pub skipped: usize,
+    pub interfaces: Vec<PcapInterface>,
+}
+
+fn is_i2c_transport(transport: &str) -> bool {
+    matches!(transport, "ft260" | "ft2232d" | "ft2232h")
+}
+
+struct Packet {
+    interface: usize,
+    timestamp_us: u64,
+    flags: u32,
+    data: Vec<u8>,
+    comment: Option<String>,
+}
+
+struct PcapBuilder {
+    interfaces: Vec<(String, u16)>,
+    index: HashMap<(String, u16), usize>,
+    packets: Vec<Packet>,
+}
+
+impl PcapBuilder {
+    fn interface(&mut self, name: &str, link_type: u16) -> usize {
+        let key = (name.to_string(), link_type);
+        if let Some(id) = self.index.get(&key) {
+            return *id;
+        }
+        let id = self.interfaces.len();
+        self.interfaces.push(key.clone());
+        self.index.insert(key, id);
+        id
+    }
+
+    fn push(&mut self, interface: usize, entry: &RecordEntry, flags: u32, data: Vec<u8>) {
+        self.packets.push(Packet {
+            interface,
+            timestamp_us: entry.timestamp_us,
+            flags,
+            data,
+            comment: None,
+        });
+    }
+}
+
+fn interface_name(entry: &RecordEntry) -> String {
+    if !entry.interface.is_empty() {
+        entry.interface.clone()
+    } else if !entry.transport.is_empty() {
+        entry.transport.clone()
+    } else {
+        "unknown".to_string()
+    }
+}
+
+fn direction_flags(direction: Direction) -> u32 {
+    match direction {
+        Direction::Rx => EPB_INBOUND,
+        _ => EPB_OUTBOUND,
+    }
+}
+
+fn push_be(buf: &mut Vec<u8>, value: u32, bytes: u8) {
+    let bytes = bytes.clamp(1, 4) as usize;
+    buf.extend_from_slice(&value.to_be_bytes()[4 - bytes..]);
+}
+
+// I2C_LINUX 의사 헤더: bus(1) + flags(4, BE) 다음에 주소 바이트와 데이터
+fn i2c_packet(address: u8, read: bool, payload: &[u8]) -> Vec<u8> {
+    let flags = if read { I2C_M_RD } else { 0 };
+    let mut data = vec![0u8];
+    data.extend_from_slice(&flags.to_be_bytes());
+    data.push((address << 1) | read as u8);
+    data.extend_from_slice(payload);
+    data
+}
+
+// 레지스터 읽기는 주소 쓰기 + 값 읽기 두 메시지, 쓰기는 주소+값 한 메시지
+fn i2c_messages(
+    register: &RegisterRecord,
+    address: u8,
+    options: &PcapOptions,
+) -> Vec<(bool, Vec<u8>)> {
+    let mut reg_addr = Vec::new();
+    if let Some(address) = register.address {
+        push_be(&mut reg_addr, address, options.register_address_bytes);
+    }
+    let value = register.value.unwrap_or(0);
+    match register.op.as_str() {
+        "read" => {
+            let mut read = Vec::new();
+            if register.error.is_none() {
+                push_be(&mut read, value, options.register_value_bytes);
+            }
+            vec![
+                (false, i2c_packet(address, false, &reg_addr)),
+                (true, i2c_packet(address, true, &read)),
+            ]
+        }
+        _ => {
+            let mut payload = reg_addr;
+            push_be(&mut payload, value, options.register_value_bytes);
+            vec![(false, i2c_packet(address, false, &payload))]
+        }
+    }
+}
+
+// SocketCAN 헤더: can_id(BE, EFF 플래그 포함) + len + fd flags + 예약 2바이트
+fn can_packet(frame: &crate::can::CanFrame) -> Vec<u8> {
+    let id = if frame.extended {
+        frame.id | CAN_EFF_FLAG
+    } else {
+        frame.id
+    };
+    let mut data = id.to_be_bytes().to_vec();
+    data.push(frame.data.len() as u8);
+    data.push(if frame.fd { CANFD_FDF } else { 0 });
+    data.extend_from_slice(&[0, 0]);
+    data.extend_from_slice(&frame.data);
+    data
+}
+
+type BuildResult = (Vec<(String, u16)>, Vec<Packet>, usize);
+
+fn build(entries: &[RecordEntry], options: &PcapOptions) -> Result<BuildResult, String> {
+    let mut builder = PcapBuilder {
+        interfaces: Vec::new(),
+        index: HashMap::new(),
+        packets: Vec::new(),
+    };
+    let mut skipped = 0;
+
+    for entry in entries {
+        let name = interface_name(entry);
+        if let Some(frame) = &entry.can {
+            let id = builder.interface(&name, LINKTYPE_CAN_SOCKETCAN);
+            builder.push(
+                id,
+                entry,
+                direction_flags(entry.direction),
+                can_packet(frame),
+            );
+            continue;
+        }
+        match entry.direction {
+            Direction::Tx | Direction::Rx => {
+                if entry.data.is_empty() {
+                    skipped += 1;
+                    continue;
+                }
+                let id = builder.interface(&name, LINKTYPE_USER0);
+                let mut data = vec![(entry.direction == Direction::Tx) as u8];
+                data.extend_from_slice(&entry.data);
+                builder.push(id, entry, direction_flags(entry.direction), data);
+            }
+            Direction::Reg => {
+                // 텍스트 프로토콜의 레지스터 명령은 이미 TX 바이트로 기록되어 있음
+                let Some(register) = entry
+                    .register
+                    .as_ref()
+                    .filter(|_| is_i2c_transport(&entry.transport))
+                else {
+                    skipped += 1;
+                    continue;
+                };
+                let address = options.i2c_address.ok_or_else(|| {
+                    format!(
+                        "I2C slave address is required to export register transactions recorded on {}",
+                        name
+                    )
+                })?;
+                let id = builder.interface(&format!("{} (synthesized)", name), LINKTYPE_I2C_LINUX);
+                let first = builder.packets.len();
+                for (read, data) in i2c_messages(register, address, options) {
+                    let flags = if read { EPB_INBOUND } else { EPB_OUTBOUND };
+                    builder.push(id, entry, flags, data);
+                }
+                let detail = match (&register.access, &register.error) {
+                    (Some(access), Some(error)) => format!(", {} ({})", error, access),
+                    (Some(access), None) => format!(", access {}", access),
+                    (None, Some(error)) => format!(", {}", error),
+                    (None, None) => String::new(),
+                };
+                // 읽기의 두 메시지 모두 합성한 패킷임을 표시
+                for packet in &mut builder.packets[first..] {
+                    packet.comment = Some(format!(
+                        "Synthesized from register {} command{}",
+                        register.op, detail
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok((builder.interfaces, builder.packets, skipped))
+}
+
+fn write_option(buf: &mut Vec<u8>, code: u16, value: &[u8]) {
+    buf.extend_from_slice(&code.to_le_bytes());
+    buf.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    buf.extend_from_slice(value);
+    pad4(buf);
+}
+
+fn pad4(buf: &mut Vec<u8>) {
+    while !buf.len().is_multiple_of(4) {
+        buf.push(0);
+    }
+}
+
+fn write_block(out: &mut impl Write, block_type: u32, body: &[u8]) -> std::io::Result<()> {
+    let total = (body.len() + 12) as u32;
+    out.write_all(&block_type.to_le_bytes())?;
+    out.write_all(&total.to_le_bytes())?;
+    out.write_all(body)?;
+    out.write_all(&total.to_le_bytes())
+}
+
+fn link_description(link_type: u16) -> &'static str {
+    match link_type {
+        LINKTYPE_I2C_LINUX => {
+            "I2C transactions synthesized from register commands (not captured on the bus)"
+        }
+        LINKTYPE_CAN_SOCKETCAN => "CAN frames",
+        _ => "UART bytes (1-byte direction header: 0=RX, 1=TX)",
+    }
+}
+
+fn write_pcapng(
+    path: &Path,
+    interfaces: &[(String, u16)],
+    packets: &[Packet],
+) -> Result<(), String> {
+    let file =
+        File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut out = BufWriter::new(file);
+    let io_err = |e: std::io::Error| format!("Failed to write PCAPNG: {}", e);
+
+    // Section Header Block
+    let mut shb = Vec::new();
+    shb.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+    shb.extend_from_slice(&1u16.to_le_bytes());
+    shb.extend_from_slice(&0u16.to_le_bytes());
+    shb.extend_from_slice(&(-1i64).to_le_bytes());
+    let appl = format!("IC 제어 앱 v{}", env!("CARGO_PKG_VERSION"));
+    write_option(&mut shb, OPT_SHB_USERAPPL, appl.as_bytes());
+    write_option(&mut shb, OPT_END, &[]);
+    write_block(&mut out, BLOCK_SHB, &shb).map_err(io_err)?;
+
+    // Interface Description Blocks (타임스탬프 해상도 10^-6)
+    for (name, link_type) in interfaces {
+        let mut idb = Vec::new();
+        idb.extend_from_slice(&link_type.to_le_bytes());
+        idb.extend_from_slice(&0u16.to_le_bytes());
+        idb.extend_from_slice(&0u32.to_le_bytes());
+        write_option(&mut idb, OPT_IF_NAME, name.as_bytes());
+        write_option(
+            &mut idb,
+            OPT_IF_DESCRIPTION,
+            link_description(*link_type).as_bytes(),
+        );
+        write_option(&mut idb, OPT_IF_TSRESOL, &[6]);
+        write_option(&mut idb, OPT_END, &[]);
+        write_block(&mut out, BLOCK_IDB, &idb).map_err(io_err)?;
+    }
+
+    // Enhanced Packet Blocks
+    for packet in packets {
+        let mut epb = Vec::new();
+        epb.extend_from_slice(&(packet.interface as u32).to_le_bytes());
+        epb.extend_from_slice(&((packet.timestamp_us >> 32) as u32).to_le_bytes());
+        epb.extend_from_slice(&(packet.timestamp_us as u32).to_le_bytes());
+        epb.extend_from_slice(&(packet.data.len() as u32).to_le_bytes());
+        epb.extend_from_slice(&(packet.data.len() as u32).to_le_bytes());
+        epb.extend_from_slice(&packet.data);
+        pad4(&mut epb);
+        write_option(&mut epb, OPT_EPB_FLAGS, &packet.flags.to_le_bytes());
+        if let Some(comment) = &packet.comment {
+            write_option(&mut epb, OPT_COMMENT, comment.as_bytes());
+        }
+        write_option(&mut epb, OPT_END, &[]);
+        write_block(&mut out, BLOCK_EPB, &epb).map_err(io_err)?;
+    }
+
+    out.flush().map_err(io_err)
+}
+
+pub fn export(
+    entries: &[RecordEntry],
+    destination: &Path,
+    options: &PcapOptions,
+) -> Result<PcapExportSummary, String> {
+    if let Some(address) = options.i2c_address {
+        if address == 0 || address > 0x7F {
+            return Err(format!(
+                "Invalid I2C slave address 0x{:02X} (expected 0x01-0x7F)",
+                address
+            ));
+        }
+    }
+    let (interfaces, packets, skipped) = build(entries, options)?;
+    if packets.is_empty() {
+        return Err("Recording contains no packets to export".into());
+    }
+    write_pcapng(destination, &interfaces, &packets)?;
+    Ok(PcapExportSummary {
+        path: destination.display().to_string(),
+        packets: packets.len(),
+        skipped,
+        interfaces: interfaces
+            .into_iter()
+            .map(|(name, link_type)| PcapInterface { name, link_type })
+            .collect(),
+    })
+}
@@ -0,0 +1,558 @@
+// 백엔드 세션 레코더
+// 모든 TX/RX 청크와 레지스터 트랜잭션을 JSON Lines 파일로 기록 (크기/시간 기준 로테이션, gzip 선택)
+use crate::can::CanFrame;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Tx,
+    Rx,
+    Reg,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterRecord {
+    pub op: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    // 레지스터 맵의 접근 타입 (RW, RO, W1C, RC 등)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access: Option<String>,
+}
+
+// 세션 파일의 한 줄
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordEntry {
+    pub timestamp_us: u64,
+    pub direction: Direction,
+    #[serde(default)]
+    pub transport: String,
+    #[serde(default)]
+    pub interface: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "hex_bytes")]
+    pub data: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub can: Option<CanFrame>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub register: Option<RegisterRecord>,
+}
+
+mod hex_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        hex::decode(text).map_err(serde::de::Error::custom)
+    }
+}
+
+pub fn now_us() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros() as u64)
+        .unwrap_or(0)
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingOptions {
+    pub directory: Option<String>,
+    pub name: Option<String>,
+    // 압축 전 바이트 기준
+    pub max_file_bytes: Option<u64>,
+    pub max_file_secs: Option<u64>,
+    #[serde(default)]
+    pub compress: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingStatus {
+    pub active: bool,
+    pub session: Option<String>,
+    pub directory: Option<String>,
+    pub current_file: Option<String>,
+    pub files: Vec<String>,
+    pub entries: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingInfo {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    pub modified: u64,
+    pub compressed: bool,
+}
+
+enum SessionWriter {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl SessionWriter {
+    fn create(path: &Path, compress: bool) -> Result<Self, String> {
+        // 이전 녹화 파일을 덮어쓰지 않음
+        let file = File::options()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .map_err(|e| format!("Failed to create recording {}: {}", path.display(), e))?;
+        let writer = BufWriter::new(file);
+        Ok(if compress {
+            SessionWriter::Gzip(GzEncoder::new(writer, Compression::default()))
+        } else {
+            SessionWriter::Plain(writer)
+        })
+    }
+
+    fn write_line(&mut self, line: &[u8]) -> std::io::Result<()> {
+        match self {
+            SessionWriter::Plain(w) => {
+                w.write_all(line)?;
+                w.write_all(b"\n")
+            }
+            SessionWriter::Gzip(w) => {
+                w.write_all(line)?;
+                w.write_all(b"\n")
+            }
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            SessionWriter::Plain(w) => w.flush(),
+            SessionWriter::Gzip(w) => w.flush(),
+        }
+    }
+
+    fn finish(self) -> std::io::Result<()> {
+        match self {
+            SessionWriter::Plain(mut w) => w.flush(),
+            SessionWriter::Gzip(w) => w.finish()?.flush(),
+        }
+    }
+}
+
+struct ActiveRecording {
+    directory: PathBuf,
+    session: String,
+    options: RecordingOptions,
+    index: u32,
+    writer: Option<SessionWriter>,
+    current_file: PathBuf,
+    opened_at: Instant,
+    bytes_written: u64,
+    last_flush: Instant,
+    files: Vec<PathBuf>,
+    entries: u64,
+}
+
+impl ActiveRecording {
+    fn file_path(&self, index: u32) -> PathBuf {
+        let ext = if self.options.compress {
+            "jsonl.gz"
+        } else {
+            "jsonl"
+        };
+        self.directory
+            .join(format!("{}_{:03}.{}", self.session, index, ext))
+    }
+
+    fn open_next(&mut self) -> Result<(), String> {
+        if let Some(writer) = self.writer.take() {
+            writer
+                .finish()
+                .map_err(|e| format!("Failed to close recording: {}", e))?;
+        }
+        self.index += 1;
+        let path = self.file_path(self.index);
+        self.writer = Some(SessionWriter::create(&path, self.options.compress)?);
+        self.current_file = path.clone();
+        self.files.push(path);
+        self.opened_at = Instant::now();
+        self.bytes_written = 0;
+        Ok(())
+    }
+
+    fn needs_rotation(&self) -> bool {
+        let size_limit = self
+            .options
+            .max_file_bytes
+            .is_some_and(|max| max > 0 && self.bytes_written >= max);
+        let time_limit = self
+            .options
+            .max_file_secs
+            .is_some_and(|secs| secs > 0 && self.opened_at.elapsed() >= Duration::from_secs(secs));
+        size_limit || time_limit
+    }
+
+    fn append(&mut self, entry: &RecordEntry) -> Result<(), String> {
+        if self.needs_rotation() {
+            self.open_next()?;
+        }
+        let line = serde_json::to_vec(entry).map_err(|e| e.to_string())?;
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or_else(|| "Recording file is not open".to_string())?;
+        writer
+            .write_line(&line)
+            .map_err(|e| format!("Failed to write recording: {}", e))?;
+        self.bytes_written += line.len() as u64 + 1;
+        self.entries += 1;
+
+        // gzip 압축률을 위해 flush는 최대 초당 1회
+        if self.last_flush.elapsed() >= Duration::from_secs(1) {
+            let _ = writer.flush();
+            self.last_flush = Instant::now();
+        }
+        Ok(())
+    }
+
+    fn status(&self) -> RecordingStatus {
+        RecordingStatus {
+            active: true,
+            session: Some(self.session.clone()),
+            directory: Some(self.directory.display().to_string()),
+            current_file: Some(self.current_file.display().to_string()),
+            files: self.files.iter().map(|p| p.display().to_string()).collect(),
+            entries: self.entries,
+        }
+    }
+}
+
+// 연결 정보(전송 방식/포트) + 활성 레코딩
+pub struct SessionRecorder {
+    source: Mutex<(String, String)>,
+    active: Mutex<Option<ActiveRecording>>,
+}
+
+impl SessionRecorder {
+    pub fn new() -> Self {
+        Self {
+            source: Mutex::new((String::new(), String::new())),
+            active: Mutex::new(None),
+        }
+    }
+
+    pub fn set_source(&self, transport: &str, interface: &str) {
+        if let Ok(mut source) = self.source.lock() {
+            *source = (transport.to_string(), interface.to_string());
+        }
+    }
+
+    pub fn start(
+        &self,
+        default_dir: PathBuf,
+        options: RecordingOptions,
+    ) -> Result<RecordingStatus, String> {
+        let mut active = self.active.lock().map_err(|e| e.to_string())?;
+        if active.is_some() {
+            return Err("Recording is already running".into());
+        }
+
+        let directory = options
+            .directory
+            .as_ref()
+            .map(PathBuf::from)
+            .unwrap_or(default_dir);
+        fs::create_dir_all(&directory)
+            .map_err(|e| format!("Failed to create recording directory: {}", e))?;
+
+        let session = options
+            .name
+            .as_ref()
+            .map(|n| sanitize_name(n))
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| format!("session-{}", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+        let session = unique_session(&directory, &session);
+
+        let mut recording = ActiveRecording {
+            directory,
+            session,
+            options,
+            index: 0,
+            writer: None,
+            current_file: PathBuf::new(),
+            opened_at: Instant::now(),
+            bytes_written: 0,
+            last_flush: Instant::now(),
+            files: Vec::new(),
+            entries: 0,
+        };
+        recording.open_next()?;
+        let status = recording.status();
+        *active = Some(recording);
+        Ok(status)
+    }
+
+    pub fn stop(&self) -> Result<RecordingStatus, String> {
+        let mut active = self.active.lock().map_err(|e| e.to_string())?;
+        let mut recording = active
+            .take()
+            .ok_or_else(|| "Recording is not running".to_string())?;
+        let mut status = recording.status();
+        status.active = false;
+        if let Some(writer) = recording.writer.take() {
+            writer
+                .finish()
+                .map_err(|e| format!("Failed to close recording: {}", e))?;
+        }
+        Ok(status)
+    }
+
+    pub fn status(&self) -> RecordingStatus {
+        match self.active.lock().ok().as_ref().and_then(|a| a.as_ref()) {
+            Some(recording) => recording.status(),
+            None => RecordingStatus {
+                active: false,
+                session: None,
+                directory: None,
+                current_file: None,
+                files: Vec::new(),
+                entries: 0,
+            },
+        }
+    }
+
+    fn append(
+        &self,
+        direction: Direction,
+        data: &[u8],
+        can: Option<&CanFrame>,
+        register: Option<RegisterRecord>,
+    ) {
+        let mut active = match self.active.lock() {
+            Ok(guard) => guard,
+            Err(_) => return,
+        };
+        let Some(recording) = active.as_mut() else {
+            return;
+        };
+        let (transport, interface) = self.source.lock().map(|s| s.clone()).unwrap_or_default();
+        let entry = RecordEntry {
+            timestamp_us: now_us(),
+            direction,
+            transport,
+            interface,
+            data: data.to_vec(),
+            can: can.cloned(),
+            register,
+        };
+        if let Err(e) = recording.append(&entry) {
+            eprintln!("Session recording failed: {}", e);
+        }
+    }
+
+    pub fn record_tx(&self, data: &[u8]) {
+        self.append(Direction::Tx, data, None, None);
+    }
+
+    pub fn record_rx(&self, data: &[u8]) {
+        self.append(Direction::Rx, data, None, None);
+    }
+
+    pub fn record_can(&self, direction: Direction, frame: &CanFrame) {
+        self.append(direction, &[], Some(frame), None);
+    }
+
+    pub fn record_register(
+        &self,
+        op: &str,
+        address: Option<u32>,
+        value: Option<u32>,
+        error: Option<String>,
+        access: Option<&str>,
+    ) {
+        self.append(
+            Direction::Reg,
+            &[],
+            None,
+            Some(RegisterRecord {
+                op: op.to_string(),
+                address,
+                value,
+                error,
+                access: access.map(str::to_string),
+            }),
+        );
+    }
+}
+
+pub fn sanitize_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+pub fn is_recording_file(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    name.ends_with(".jsonl") || name.ends_with(".jsonl.gz")
+}
+
+pub fn list_recordings(directory: &Path) -> Result<Vec<RecordingInfo>, String> {
+    if !directory.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(directory)
+        .map_err(|e| format!("Failed to read recording directory: {}", e))?;
+
+    let mut recordings = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() || !is_recording_file(&path) {
+            continue;
+        }
+        let meta = entry.metadata().map_err(|e| e.to_string())?;
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        recordings.push(RecordingInfo {
+            name: entry.file_name().to_string_lossy().to_string(),
+            path: path.display().to_string(),
+            size: meta.len(),
+            modified,
+            compressed: path.extension().is_some_and(|ext| ext == "gz"),
+        });
+    }
+    recordings.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(recordings)
+}
+
+// 같은 이름의 세션 파일이 이미 있으면 "-2", "-3" ... 을 붙인 이름
+// ('_' 를 쓰면 session_parts 가 파트 번호와 헷갈림)
+fn unique_session(directory: &Path, session: &str) -> String {
+    let taken = |name: &str| {
+        fs::read_dir(directory).is_ok_and(|entries| {
+            entries.flatten().any(|entry| {
+                let file = entry.file_name().to_string_lossy().to_string();
+                file.strip_prefix(&format!("{}_", name))
+                    .and_then(|rest| rest.split_once('.'))
+                    .is_some_and(|(index, _)| {
+                        index.len() == 3 && index.chars().all(|c| c.is_ascii_digit())
+                    })
+            })
+        })
+    };
+    if !taken(session) {
+        return session.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}-{}", session, n))
+        .find(|name| !taken(name))
+        .unwrap_or_else(|| session.to_string())
+}
+
+// 로테이션된 세션의 모든 파트 (session_001, session_002, ...) 를 순서대로 반환
+pub fn session_parts(path: &Path) -> Vec<PathBuf> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let (stem, ext) = match name.split_once('.') {
+        Some((stem, ext)) => (stem.to_string(), ext.to_string()),
+        None => return vec![path.to_path_buf()],
+    };
+    let session = match stem.rsplit_once('_') {
+        Some((session, index)) if index.len() == 3 && index.chars().all(|c| c.is_ascii_digit()) => {
+            session.to_string()
+        }
+        _ => return vec![path.to_path_buf()],
+    };
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut parts: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    let n = p
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    // 정확히 {session}_NNN.{ext} 만 (run 을 재생할 때 run_2_000 은 제외)
+                    n.strip_prefix(&format!("{}_", session))
+                        .and_then(|rest| rest.strip_suffix(&format!(".{}", ext)))
+                        .is_some_and(|index| {
+                            index.len() == 3 && index.chars().all(|c| c.is_ascii_digit())
+                        })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if parts.is_empty() {
+        return vec![path.to_path_buf()];
+    }
+    parts.sort();
+    parts
+}
+
+// 세션 파일 하나를 읽음 (.jsonl / .jsonl.gz)
+pub fn read_recording(path: &Path) -> Result<Vec<RecordEntry>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let reader: Box<dyn Read> = if path.extension().is_some_and(|ext| ext == "gz") {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+
+    let mut entries = Vec::new();
+    for (index, line) in BufReader::new(reader).lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            // 비정상 종료로 잘린 gzip 꼬리는 무시
+            Err(_) if !entries.is_empty() => break,
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str::<RecordEntry>(&line)
+            .map_err(|e| format!("{}:{}: invalid record: {}", path.display(), index + 1, e))?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+// 세션의 모든 파트를 이어서 읽음
+pub fn read_session(path: &Path) -> Result<Vec<RecordEntry>, String> {
+    let mut entries = Vec::new();
+    for part in session_parts(path) {
+        entries.extend(read_recording(&part)?);
+    }
+    Ok(entries)
+}
@@ -0,0 +1,1378 @@
+// 레지스터 맵 모델
+// registers.yaml 을 타입이 있는 구조로 파싱하고, 필드 겹침/폭 초과/주소·이름 중복을 줄 번호와 함께 검증
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+
+// 앱에 포함된 기본 레지스터 맵
+pub const DEFAULT_MAP: &str = include_str!("../../public/registers.yaml");
+
+pub const SUPPORTED_WIDTHS: [u32; 5] = [8, 16, 24, 32, 64];
+const SUPPORTED_BUS_WIDTHS: [u32; 3] = [8, 16, 32];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Access {
+    #[default]
+    #[serde(rename = "RW", alias = "rw")]
+    ReadWrite,
+    #[serde(rename = "RO", alias = "ro")]
+    ReadOnly,
+    #[serde(rename = "WO", alias = "wo")]
+    WriteOnly,
+    // 1을 쓴 비트만 0으로 지워짐
+    #[serde(rename = "W1C", alias = "w1c")]
+    WriteOneToClear,
+    // 1을 쓴 비트만 1로 설정됨
+    #[serde(rename = "W1S", alias = "w1s")]
+    WriteOneToSet,
+    // 읽으면 0으로 지워짐
+    #[serde(rename = "RC", alias = "rc")]
+    ReadClear,
+    // 리셋 후 한 번만 쓸 수 있음
+    #[serde(
+        rename = "WONCE",
+        alias = "wonce",
+        alias = "write-once",
+        alias = "writeOnce"
+    )]
+    WriteOnce,
+    // 예약 비트: 쓸 때 항상 리셋 값으로 채움
+    #[serde(rename = "RSVD", alias = "rsvd", alias = "reserved")]
+    Reserved,
+}
+
+impl Access {
+    pub fn label(self) -> &'static str {
+        match self {
+            Access::ReadWrite => "RW",
+            Access::ReadOnly => "RO",
+            Access::WriteOnly => "WO",
+            Access::WriteOneToClear => "W1C",
+            Access::WriteOneToSet => "W1S",
+            Access::ReadClear => "RC",
+            Access::WriteOnce => "WONCE",
+            Access::Reserved => "RSVD",
+        }
+    }
+
+    pub fn is_readable(self) -> bool {
+        self != Access::WriteOnly
+    }
+
+    pub fn is_writable(self) -> bool {
+        !matches!(
+            self,
+            Access::ReadOnly | Access::ReadClear | Access::Reserved
+        )
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Access::ReadOnly => "read-only",
+            Access::WriteOnly => "write-only",
+            Access::ReadClear => "read-to-clear",
+            Access::Reserved => "reserved",
+            _ => "writable",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RegisterMap {
+    // 버스 전송 방식 (주소 하나의 데이터 폭, 바이트 순서, 블록 전송)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bus: Option<Bus>,
+    // 페이지 선택 레지스터 (같은 주소 뒤에 여러 페이지가 겹쳐 있는 칩)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paging: Option<Paging>,
+    #[serde(default)]
+    pub registers: Vec<Register>,
+}
+
+// 여러 워드로 나뉜 레지스터에서 낮은 워드가 낮은 주소(little) 또는 높은 주소(big)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ByteOrder {
+    #[default]
+    #[serde(alias = "le")]
+    Little,
+    #[serde(alias = "be")]
+    Big,
+}
+
+// dataWidth 가 없으면 32비트까지의 레지스터는 RREG/WREG 한 번으로 전송 (폭은 장치가 처리)
+// 있으면(또는 32비트보다 넓으면) 레지스터를 연속된 주소의 워드로 나누어 전송
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bus {
+    #[serde(
+        default,
+        deserialize_with = "de_opt_number",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub data_width: Option<u32>,
+    #[serde(default)]
+    pub byte_order: ByteOrder,
+    // 장치가 주소 자동 증가 블록 전송(RBLK/WBLK)을 지원
+    #[serde(default)]
+    pub burst: bool,
+    // 블록 전송 한 번에 보내는 최대 워드 수
+    #[serde(default = "default_max_burst", deserialize_with = "de_number")]
+    pub max_burst: u32,
+}
+
+fn default_max_burst() -> u32 {
+    32
+}
+
+impl Bus {
+    // 폭이 width 인 레지스터가 차지하는 주소 수
+    pub fn words(&self, width: u32) -> u32 {
+        match self.data_width {
+            Some(data_width) if data_width > 0 => width.div_ceil(data_width).max(1),
+            _ => width.div_ceil(32).max(1),
+        }
+    }
+
+    // 레지스터 값 -> 주소 순서의 워드
+    pub fn split(&self, value: u64, width: u32, order: ByteOrder) -> Vec<u32> {
+        let words = self.words(width);
+        let data_width = self.data_width.unwrap_or(32).min(32);
+        let mut parts: Vec<u32> = (0..words)
+            .map(|i| ((value >> (i * data_width).min(63)) & bit_mask(data_width)) as u32)
+            .collect();
+        if order == ByteOrder::Big {
+            parts.reverse();
+        }
+        parts
+    }
+
+    // 주소 순서의 워드 -> 레지스터 값
+    pub fn join(&self, parts: &[u32], order: ByteOrder) -> u64 {
+        let data_width = self.data_width.unwrap_or(32).min(32);
+        let mask = bit_mask(data_width);
+        let ordered: Vec<u32> = match order {
+            ByteOrder::Little => parts.to_vec(),
+            ByteOrder::Big => parts.iter().rev().copied().collect(),
+        };
+        ordered.iter().enumerate().fold(0u64, |value, (i, part)| {
+            value | ((*part as u64 & mask) << (i as u32 * data_width).min(63))
+        })
+    }
+}
+
+// 페이지 번호를 (page & mask) << shift 로 페이지 선택 레지스터에 씀 (나머지 비트는 0)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Paging {
+    #[serde(deserialize_with = "de_number")]
+    pub address: u32,
+    #[serde(default, deserialize_with = "de_number")]
+    pub shift: u32,
+    #[serde(default = "default_page_mask", deserialize_with = "de_number")]
+    pub mask: u32,
+    // 페이지 이름 (비어 있으면 mask 안의 모든 번호 허용)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pages: Vec<Page>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Page {
+    #[serde(deserialize_with = "de_number")]
+    pub value: u32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+fn default_page_mask() -> u32 {
+    0xFF
+}
+
+impl Paging {
+    pub fn select_value(&self, page: u32) -> u32 {
+        (page & self.mask) << self.shift.min(31)
+    }
+
+    // 페이지 선택 레지스터에 쓴 값에서 페이지 번호
+    pub fn page_of(&self, value: u32) -> u32 {
+        (value >> self.shift.min(31)) & self.mask
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Register {
+    #[serde(deserialize_with = "de_number")]
+    pub address: u32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_width", deserialize_with = "de_number")]
+    pub width: u32,
+    #[serde(default)]
+    pub access: Access,
+    #[serde(
+        default,
+        deserialize_with = "de_opt_value",
+        serialize_with = "ser_opt_hex",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub reset: Option<u64>,
+    // 페이지 번호 (없으면 모든 페이지에서 보이는 레지스터)
+    #[serde(
+        default,
+        deserialize_with = "de_opt_number",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub page: Option<u32>,
+    // 여러 워드로 나뉘는 레지스터의 워드 순서 (없으면 bus.byteOrder)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byte_order: Option<ByteOrder>,
+    // 마지막으로 알려진 값 (프론트엔드 캐시)
+    #[serde(default, deserialize_with = "de_value", serialize_with = "ser_hex")]
+    pub value: u64,
+    // 기존 맵 호환: readOnly: true 는 access: RO 와 같음
+    #[serde(default)]
+    pub read_only: bool,
+    // 쓰기 후 다시 읽어 확인할지 (없으면 전역 설정)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify: Option<bool>,
+    #[serde(default)]
+    pub fields: Vec<Field>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Field {
+    #[serde(default)]
+    pub name: String,
+    // LSB 위치
+    #[serde(deserialize_with = "de_number")]
+    pub bit: u32,
+    #[serde(default = "default_size", deserialize_with = "de_number")]
+    pub size: u32,
+    #[serde(default)]
+    pub description: String,
+    // 지정하지 않으면 레지스터의 access 를 따름
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access: Option<Access>,
+    #[serde(
+        default,
+        deserialize_with = "de_opt_value",
+        serialize_with = "ser_opt_hex",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub reset: Option<u64>,
+    // 하드웨어가 스스로 바꾸는 상태 비트 (쓰기 검증에서 제외)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub volatile: bool,
+    // 값별 이름 (예: 0 = OFF, 1 = ON)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enums: Vec<FieldEnum>,
+    // 원시 코드 <-> 물리 값 변환 (예: VSEL 코드 -> mV)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversion: Option<Conversion>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldEnum {
+    #[serde(deserialize_with = "de_value", serialize_with = "ser_hex")]
+    pub value: u64,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Conversion {
+    // 물리 값 = offset + 코드 * step
+    Linear {
+        #[serde(default)]
+        unit: String,
+        #[serde(default)]
+        offset: f64,
+        step: f64,
+    },
+    // 코드별 물리 값 표 (표에 없는 코드는 변환하지 않음)
+    Lookup {
+        #[serde(default)]
+        unit: String,
+        table: Vec<LookupEntry>,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LookupEntry {
+    #[serde(deserialize_with = "de_value", serialize_with = "ser_hex")]
+    pub raw: u64,
+    pub value: f64,
+}
+
+impl Conversion {
+    pub fn unit(&self) -> &str {
+        match self {
+            Conversion::Linear { unit, .. } | Conversion::Lookup { unit, .. } => unit,
+        }
+    }
+
+    pub fn decode(&self, raw: u64) -> Option<f64> {
+        match self {
+            Conversion::Linear { offset, step, .. } => Some(offset + raw as f64 * step),
+            Conversion::Lookup { table, .. } => {
+                table.iter().find(|e| e.raw == raw).map(|e| e.value)
+            }
+        }
+    }
+}
+
+// SI 접두어가 붙은 단위 -> (배율, 기본 단위). 예: "mV" -> (0.001, "V")
+fn unit_scale(unit: &str) -> (f64, &str) {
+    let mut chars = unit.chars();
+    let scale = match chars.next() {
+        Some('p') => 1e-12,
+        Some('n') => 1e-9,
+        Some('u') | Some('µ') => 1e-6,
+        Some('m') => 1e-3,
+        Some('k') => 1e3,
+        Some('M') => 1e6,
+        Some('G') => 1e9,
+        _ => return (1.0, unit),
+    };
+    match chars.as_str() {
+        "" => (1.0, unit),
+        base => (scale, base),
+    }
+}
+
+// 다른 접두어의 같은 단위로 환산 (예: 1.2 V -> 1200 mV)
+fn convert_unit(value: f64, from: &str, to: &str) -> Result<f64, String> {
+    if from.is_empty() || from == to {
+        return Ok(value);
+    }
+    let (from_scale, from_base) = unit_scale(from);
+    let (to_scale, to_base) = unit_scale(to);
+    if from_base != to_base {
+        return Err(format!("Cannot convert {} to {}", from, to));
+    }
+    Ok(value * from_scale / to_scale)
+}
+
+fn format_physical(value: f64, unit: &str) -> String {
+    let value = (value * 1e6).round() / 1e6;
+    if unit.is_empty() {
+        value.to_string()
+    } else {
+        format!("{} {}", value, unit)
+    }
+}
+
+fn default_width() -> u32 {
+    32
+}
+
+fn default_size() -> u32 {
+    1
+}
+
+impl Field {
+    pub fn msb(&self) -> u32 {
+        self.bit.saturating_add(self.size.max(1) - 1)
+    }
+
+    pub fn mask(&self) -> u64 {
+        bit_mask(self.size).checked_shl(self.bit).unwrap_or(0)
+    }
+}
+
+impl Field {
+    // 필드가 명시하지 않으면 레지스터의 access 사용
+    pub fn effective_access(&self, register: &Register) -> Access {
+        self.access.unwrap_or(register.access)
+    }
+
+    pub fn enum_name(&self, value: u64) -> Option<&str> {
+        self.enums
+            .iter()
+            .find(|e| e.value == value)
+            .map(|e| e.name.as_str())
+    }
+
+    pub fn max_value(&self) -> u64 {
+        bit_mask(self.size)
+    }
+
+    pub fn extract(&self, register_value: u64) -> u64 {
+        (register_value & self.mask())
+            .checked_shr(self.bit)
+            .unwrap_or(0)
+    }
+
+    pub fn insert(&self, register_value: u64, value: u64) -> u64 {
+        let shifted = value.checked_shl(self.bit).unwrap_or(0);
+        (register_value & !self.mask()) | (shifted & self.mask())
+    }
+
+    // 원시 값 -> 물리 값 (변환이 없거나 표에 없는 코드면 None)
+    pub fn decode(&self, raw: u64) -> Option<f64> {
+        self.conversion.as_ref().and_then(|c| c.decode(raw))
+    }
+
+    // 물리 값(변환 단위 기준) -> 원시 값. 선형 변환은 가장 가까운 코드로 반올림하고 범위를 벗어나면 거부
+    pub fn encode(&self, value: f64) -> Result<u64, String> {
+        let conversion = self
+            .conversion
+            .as_ref()
+            .ok_or_else(|| format!("Field {} has no unit conversion", self.name))?;
+        let unit = conversion.unit();
+        match conversion {
+            Conversion::Linear { offset, step, .. } => {
+                if *step == 0.0 || !step.is_finite() {
+                    return Err(format!(
+                        "Field {} has an invalid conversion step",
+                        self.name
+                    ));
+                }
+                let code = ((value - offset) / step).round();
+                if !(0.0..=self.max_value() as f64).contains(&code) {
+                    let (a, b) = (offset + 0.0, offset + self.max_value() as f64 * step);
+                    return Err(format!(
+                        "{} is outside the range of {} ({} to {})",
+                        format_physical(value, unit),
+                        self.name,
+                        format_physical(a.min(b), unit),
+                        format_physical(a.max(b), unit)
+                    ));
+                }
+                Ok(code as u64)
+            }
+            Conversion::Lookup { table, .. } => {
+                let entries = table.iter().filter(|e| e.raw <= self.max_value());
+                let nearest = entries
+                    .min_by(|a, b| (a.value - value).abs().total_cmp(&(b.value - value).abs()));
+                match nearest {
+                    Some(entry) if (entry.value - value).abs() <= 1e-9 * value.abs().max(1.0) => {
+                        Ok(entry.raw)
+                    }
+                    Some(entry) => Err(format!(
+                        "{} is not a valid value for {} (nearest is {})",
+                        format_physical(value, unit),
+                        self.name,
+                        format_physical(entry.value, unit)
+                    )),
+                    None => Err(format!("Field {} has an empty lookup table", self.name)),
+                }
+            }
+        }
+    }
+
+    // 사용자 입력 -> 원시 값
+    // enum 이름("PWM"), 단위가 붙은 물리 값("1.2 V", "800mV"), 16진수 원시 값("0x1F"),
+    // 숫자만 쓰면 변환이 있는 필드는 물리 값, 없는 필드는 원시 값으로 해석
+    pub fn parse_value(&self, text: &str) -> Result<u64, String> {
+        let text = text.trim();
+        if let Some(item) = self
+            .enums
+            .iter()
+            .find(|e| e.name.eq_ignore_ascii_case(text))
+        {
+            return Ok(item.value);
+        }
+        let raw = if text.starts_with("0x") || text.starts_with("0X") {
+            parse_wide_number(text).ok_or_else(|| format!("Invalid value '{}'", text))?
+        } else {
+            let split = text
+                .find(|c: char| !(c.is_ascii_digit() || "+-.".contains(c)))
+                .unwrap_or(text.len());
+            let (number, unit) = (&text[..split], text[split..].trim());
+            let number: f64 = number.parse().map_err(|_| {
+                let names: Vec<&str> = self.enums.iter().map(|e| e.name.as_str()).collect();
+                if names.is_empty() {
+                    format!("Invalid value '{}' for field {}", text, self.name)
+                } else {
+                    format!(
+                        "Invalid value '{}' for field {} (expected a number or one of {})",
+                        text,
+                        self.name,
+                        names.join(", ")
+                    )
+                }
+            })?;
+            match &self.conversion {
+                Some(conversion) => {
+                    return self.encode(convert_unit(number, unit, conversion.unit())?)
+                }
+                None if !unit.is_empty() => {
+                    return Err(format!("Field {} has no unit conversion", self.name))
+                }
+                None if number.fract() != 0.0 || number < 0.0 => {
+                    return Err(format!(
+                        "Raw value '{}' must be a non-negative integer",
+                        text
+                    ))
+                }
+                None => number as u64,
+            }
+        };
+        if raw > self.max_value() {
+            return Err(format!(
+                "Value 0x{:X} does not fit in {}-bit field {} (max 0x{:X})",
+                raw,
+                self.size,
+                self.name,
+                self.max_value()
+            ));
+        }
+        Ok(raw)
+    }
+
+    // 원시 값을 사람이 읽는 형태로 (enum 이름, 물리 값)
+    pub fn describe_value(&self, raw: u64) -> Option<String> {
+        if let Some(name) = self.enum_name(raw) {
+            return Some(name.to_string());
+        }
+        let conversion = self.conversion.as_ref()?;
+        conversion
+            .decode(raw)
+            .map(|value| format_physical(value, conversion.unit()))
+    }
+}
+
+impl Register {
+    pub fn value_mask(&self) -> u64 {
+        bit_mask(self.width)
+    }
+
+    // 여러 워드로 나뉘는 레지스터의 워드 순서
+    pub fn byte_order(&self, bus: Option<&Bus>) -> ByteOrder {
+        self.byte_order
+            .or(bus.map(|b| b.byte_order))
+            .unwrap_or_default()
+    }
+
+    pub fn find_field(&self, name: &str) -> Option<&Field> {
+        self.fields.iter().find(|f| f.name == name).or_else(|| {
+            self.fields
+                .iter()
+                .find(|f| f.name.eq_ignore_ascii_case(name))
+        })
+    }
+
+    // 해당 access 를 가진 비트 (필드가 덮지 않는 비트는 레지스터 access 를 따름)
+    pub fn access_mask(&self, access: Access) -> u64 {
+        let covered = self.fields.iter().fold(0, |mask, f| mask | f.mask());
+        let mut mask = self
+            .fields
+            .iter()
+            .filter(|f| f.effective_access(self) == access)
+            .fold(0, |mask, f| mask | f.mask());
+        if self.access == access {
+            mask |= self.value_mask() & !covered;
+        }
+        mask & self.value_mask()
+    }
+
+    // 예약 비트에 써야 할 리셋 값
+    pub fn reserved_bits(&self) -> u64 {
+        let mut value = self.reset.unwrap_or(0);
+        for field in &self.fields {
+            if let (Access::Reserved, Some(reset)) = (field.effective_access(self), field.reset) {
+                value = field.insert(value, reset);
+            }
+        }
+        value & self.access_mask(Access::Reserved)
+    }
+
+    // page 에서 접근할 수 있는 레지스터인지 (page 가 None 이거나 페이지 없는 레지스터면 항상 true)
+    pub fn on_page(&self, page: Option<u32>) -> bool {
+        page.is_none() || self.page.is_none() || self.page == page
+    }
+
+    pub fn is_readable(&self) -> bool {
+        self.access.is_readable()
+    }
+
+    // 읽기만으로 상태가 바뀌는 비트(RC)가 있으면 일괄 새로고침/폴링에서 제외
+    pub fn has_read_side_effects(&self) -> bool {
+        self.access_mask(Access::ReadClear) != 0
+    }
+
+    // 쓰기 후 다시 읽어 비교할 비트: RW/WONCE 비트 중 volatile 이 아닌 것
+    // 읽을 수 없거나 읽기에 부작용이 있는 레지스터는 검증하지 않음 (0)
+    pub fn verify_mask(&self) -> u64 {
+        if !self.is_readable() || self.has_read_side_effects() {
+            return 0;
+        }
+        let volatile = self
+            .fields
+            .iter()
+            .filter(|f| f.volatile)
+            .fold(0, |mask, f| mask | f.mask());
+        (self.access_mask(Access::ReadWrite) | self.access_mask(Access::WriteOnce)) & !volatile
+    }
+
+    // 외부 형식에서 가져온 레지스터 정리
+    // 레지스터 access 가 없으면 모든 필드가 같을 때 그 값(아니면 RW)으로 정하고, 레지스터와 같은 필드 access 는 생략
+    // 레지스터 리셋 값이 없으면 필드 리셋 값으로 조합
+    pub fn finish_import(&mut self, declared: Option<Access>) {
+        let first = self.fields.first().and_then(|f| f.access);
+        let uniform = self.fields.iter().all(|f| f.access == first);
+        self.access = declared
+            .or(if uniform { first } else { None })
+            .unwrap_or_default();
+        for field in &mut self.fields {
+            if field.access == Some(self.access) {
+                field.access = None;
+            }
+        }
+        if self.reset.is_none() && self.fields.iter().any(|f| f.reset.is_some()) {
+            let reset = self
+                .fields
+                .iter()
+                .fold(0, |value, f| f.insert(value, f.reset.unwrap_or(0)));
+            self.reset = Some(reset);
+        }
+        self.value = self.reset.unwrap_or(0);
+        self.read_only = self.access == Access::ReadOnly;
+    }
+
+    // 읽은 뒤 칩에 남는 값 (RC 비트는 0으로 지워짐)
+    pub fn value_after_read(&self, value: u64) -> u64 {
+        value & !self.access_mask(Access::ReadClear)
+    }
+
+    // 레지스터 전체 쓰기: RO/RC/RSVD 레지스터는 거부, 예약 비트는 리셋 값으로 채움
+    pub fn prepare_write(&self, value: u64) -> Result<u64, String> {
+        if !self.access.is_writable() {
+            return Err(format!(
+                "Register {} is {}",
+                self.name,
+                self.access.describe()
+            ));
+        }
+        if value & !self.value_mask() != 0 {
+            return Err(format!(
+                "Value 0x{:X} does not fit in {}-bit register {}",
+                value, self.width, self.name
+            ));
+        }
+        let reserved = self.access_mask(Access::Reserved);
+        Ok((value & !reserved) | self.reserved_bits())
+    }
+
+    // 필드 읽고-수정-쓰기: (실제로 쓸 값, 쓰기 후 예상되는 레지스터 값)
+    // 다른 필드의 W1C/W1S 비트는 0으로 써서 의도치 않게 지우거나 설정하지 않음
+    pub fn prepare_field_write(
+        &self,
+        field: &Field,
+        current: u64,
+        value: u64,
+    ) -> Result<(u64, u64), String> {
+        let access = field.effective_access(self);
+        if !access.is_writable() {
+            return Err(format!(
+                "Field {}.{} is {}",
+                self.name,
+                field.name,
+                access.describe()
+            ));
+        }
+        if value > field.max_value() {
+            return Err(format!(
+                "Value 0x{:X} does not fit in {}-bit field {}.{} (max 0x{:X})",
+                value,
+                field.size,
+                self.name,
+                field.name,
+                field.max_value()
+            ));
+        }
+        let side_effects =
+            self.access_mask(Access::WriteOneToClear) | self.access_mask(Access::WriteOneToSet);
+        let reserved = self.access_mask(Access::Reserved);
+        let written = field.insert(current & !side_effects, value);
+        let written = ((written & !reserved) | self.reserved_bits()) & self.value_mask();
+
+        let bits = field.insert(0, value);
+        let expected = match access {
+            Access::WriteOneToClear => current & !bits,
+            Access::WriteOneToSet => current | bits,
+            _ => field.insert(current, value),
+        };
+        Ok((written, expected))
+    }
+}
+
+pub fn bit_mask(bits: u32) -> u64 {
+    if bits >= 64 {
+        u64::MAX
+    } else {
+        (1u64 << bits) - 1
+    }
+}
+
+// 숫자 또는 "0x1F" / "31" 같은 문자열 모두 허용
+struct NumberVisitor;
+
+impl<'de> Visitor<'de> for NumberVisitor {
+    type Value = u64;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an unsigned number or a hex string like 0x1F")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<u64, E> {
+        Ok(v)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<u64, E> {
+        u64::try_from(v).map_err(|_| E::custom(format!("number {} is out of range", v)))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<u64, E> {
+        parse_wide_number(v).ok_or_else(|| E::custom(format!("invalid number '{}'", v)))
+    }
+}
+
+// Option 필드용: null / 빈 값 (YAML `reset: ~`, `page:`) 은 None
+struct OptionalNumberVisitor;
+
+impl<'de> Visitor<'de> for OptionalNumberVisitor {
+    type Value = Option<u64>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        NumberVisitor.expecting(f)?;
+        f.write_str(" or null")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Option<u64>, E> {
+        Ok(None)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Option<u64>, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Option<u64>, D::Error> {
+        deserializer.deserialize_any(NumberVisitor).map(Some)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Option<u64>, E> {
+        NumberVisitor.visit_u64(v).map(Some)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Option<u64>, E> {
+        NumberVisitor.visit_i64(v).map(Some)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Option<u64>, E> {
+        NumberVisitor.visit_str(v).map(Some)
+    }
+}
+
+pub fn parse_number(text: &str) -> Option<u32> {
+    parse_wide_number(text).and_then(|value| u32::try_from(value).ok())
+}
+
+// 64비트 레지스터 값용
+pub fn parse_wide_number(text: &str) -> Option<u64> {
+    let text = text.trim();
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        u64::from_str_radix(&hex.replace('_', ""), 16).ok()
+    } else {
+        text.parse().ok()
+    }
+}
+
+fn de_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    let value = deserializer.deserialize_any(NumberVisitor)?;
+    u32::try_from(value).map_err(|_| de::Error::custom(format!("number {} is out of range", value)))
+}
+
+fn de_opt_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u32>, D::Error> {
+    de_opt_value(deserializer)?
+        .map(|value| {
+            u32::try_from(value)
+                .map_err(|_| de::Error::custom(format!("number {} is out of range", value)))
+        })
+        .transpose()
+}
+
+pub fn de_value<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    deserializer.deserialize_any(NumberVisitor)
+}
+
+pub fn de_opt_value<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    deserializer.deserialize_any(OptionalNumberVisitor)
+}
+
+// JS 숫자가 정확히 나타낼 수 있는 최대 정수 (2^53 - 1)
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+// IPC 로 주고받는 레지스터/필드 값
+// JS 숫자는 2^53 이상에서 정밀도를 잃으므로 항상 "0x1F" 같은 문자열로 보내고,
+// 받을 때는 문자열과 2^53 미만의 숫자만 허용 (더 큰 숫자는 이미 반올림됐을 수 있음)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WireValue(pub u64);
+
+impl Serialize for WireValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ser_hex(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for WireValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(WireVisitor).map(WireValue)
+    }
+}
+
+struct WireVisitor;
+
+impl<'de> Visitor<'de> for WireVisitor {
+    type Value = u64;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a hex string like 0x1F or a number below 2^53")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<u64, E> {
+        if v > MAX_SAFE_INTEGER {
+            return Err(E::custom(format!(
+                "number {} is not exact in JavaScript; pass it as a hex string",
+                v
+            )));
+        }
+        Ok(v)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<u64, E> {
+        let v = u64::try_from(v).map_err(|_| E::custom(format!("number {} is out of range", v)))?;
+        self.visit_u64(v)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<u64, E> {
+        NumberVisitor.visit_str(v)
+    }
+}
+
+// serialize_with 용: u64 값을 "0x..." 문자열로
+pub fn ser_hex<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&format_args!("0x{:X}", value))
+}
+
+pub fn ser_opt_hex<S: Serializer>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => ser_hex(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapIssue {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for MapIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+pub fn describe_issues(issues: &[MapIssue]) -> String {
+    issues
+        .iter()
+        .map(|issue| issue.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// YAML 텍스트 파싱 (문법/타입 오류는 serde_yaml 이 알려주는 위치로 보고)
+pub fn parse(text: &str) -> Result<RegisterMap, MapIssue> {
+    if text.trim().is_empty() {
+        return Ok(RegisterMap::default());
+    }
+    let mut map: RegisterMap = serde_yaml::from_str(text).map_err(|e| {
+        let location = e.location();
+        MapIssue {
+            line: location.as_ref().map(|l| l.line()),
+            column: location.as_ref().map(|l| l.column()),
+            path: String::new(),
+            message: e.to_string(),
+        }
+    })?;
+    map.normalize();
+    Ok(map)
+}
+
+// 파싱 + 검증. 파싱에 실패하면 맵 없이 오류만 돌려줌
+pub fn load(text: &str) -> (Option<RegisterMap>, Vec<MapIssue>) {
+    match parse(text) {
+        Ok(map) => {
+            let issues = validate(&map, Some(text));
+            (Some(map), issues)
+        }
+        Err(issue) => (None, vec![issue]),
+    }
+}
+
+pub fn to_yaml(map: &RegisterMap) -> Result<String, String> {
+    serde_yaml::to_string(map).map_err(|e| format!("Failed to serialize register map: {}", e))
+}
+
+impl RegisterMap {
+    // 이름(대소문자 무시) 또는 "0x10" 같은 주소로 레지스터 찾기
+    pub fn find_register(&self, key: &str) -> Option<&Register> {
+        self.registers
+            .iter()
+            .find(|r| r.name == key)
+            .or_else(|| {
+                self.registers
+                    .iter()
+                    .find(|r| r.name.eq_ignore_ascii_case(key))
+            })
+            .or_else(|| {
+                parse_number(key)
+                    .and_then(|address| self.registers.iter().find(|r| r.address == address))
+            })
+    }
+
+    // 주어진 페이지에서 보이는 레지스터 (페이지 없는 레지스터는 모든 페이지에서 보임)
+    // 없으면 주소가 같은 첫 레지스터 (다른 페이지에만 있는 레지스터일 수 있으므로 필요하면 on_page 로 확인)
+    pub fn register_in_page(&self, address: u32, page: Option<u32>) -> Option<&Register> {
+        let index = self.page_index(address, page)?;
+        self.registers.get(index)
+    }
+
+    pub fn register_in_page_mut(
+        &mut self,
+        address: u32,
+        page: Option<u32>,
+    ) -> Option<&mut Register> {
+        let index = self.page_index(address, page)?;
+        self.registers.get_mut(index)
+    }
+
+    fn page_index(&self, address: u32, page: Option<u32>) -> Option<usize> {
+        let at = |wanted: Option<u32>| {
+            self.registers
+                .iter()
+                .position(|r| r.address == address && r.page == wanted)
+        };
+        at(page)
+            .or_else(|| at(None))
+            .or_else(|| self.registers.iter().position(|r| r.address == address))
+    }
+
+    // readOnly 플래그와 access 를 일치시킴
+    pub fn normalize(&mut self) {
+        for reg in &mut self.registers {
+            if reg.read_only {
+                reg.access = Access::ReadOnly;
+            }
+            reg.read_only = reg.access == Access::ReadOnly;
+        }
+    }
+}
+
+pub fn validate(map: &RegisterMap, text: Option<&str>) -> Vec<MapIssue> {
+    let lines = text.map(LineIndex::build).unwrap_or_default();
+    let mut issues = Vec::new();
+    // 주소 -> (페이지, 레지스터 인덱스)
+    let mut addresses: HashMap<u32, Vec<(Option<u32>, usize)>> = HashMap::new();
+    let mut names: HashMap<&str, usize> = HashMap::new();
+
+    if let Some(bus) = &map.bus {
+        let mut report = |message: String| {
+            issues.push(MapIssue {
+                line: None,
+                column: None,
+                path: "bus".into(),
+                message,
+            })
+        };
+        if let Some(width) = bus.data_width {
+            if !SUPPORTED_BUS_WIDTHS.contains(&width) {
+                report(format!(
+                    "Unsupported bus data width {} (expected 8, 16 or 32)",
+                    width
+                ));
+            }
+        }
+        if bus.max_burst == 0 {
+            report("maxBurst must be at least 1".to_string());
+        }
+    }
+    let bus = map.bus.clone().unwrap_or_default();
+
+    if let Some(paging) = &map.paging {
+        let mut report = |message: String| {
+            issues.push(MapIssue {
+                line: None,
+                column: None,
+                path: "paging".into(),
+                message,
+            })
+        };
+        if paging.mask == 0 {
+            report("Page mask is zero".to_string());
+        }
+        if paging.shift >= 32 || (paging.mask as u64) << paging.shift > u32::MAX as u64 {
+            report(format!(
+                "Page mask 0x{:X} shifted by {} does not fit in 32 bits",
+                paging.mask, paging.shift
+            ));
+        }
+        let mut values = HashMap::new();
+        for page in &paging.pages {
+            if page.value & !paging.mask != 0 {
+                report(format!(
+                    "Page {} does not fit in page mask 0x{:X}",
+                    page.value, paging.mask
+                ));
+            }
+            if values.insert(page.value, ()).is_some() {
+                report(format!("Duplicate page {}", page.value));
+            }
+        }
+    }
+
+    for (i, reg) in map.registers.iter().enumerate() {
+        let path = format!("registers[{}]", i);
+        let line = lines.register(i);
+        let mut report = |message: String| {
+            issues.push(MapIssue {
+                line,
+                column: None,
+                path: path.clone(),
+                message,
+            })
+        };
+
+        if reg.name.trim().is_empty() {
+            report("Register name is empty".to_string());
+        } else if let Some(&other) = names.get(reg.name.as_str()) {
+            report(format!(
+                "Duplicate register name '{}' (first defined at {})",
+                reg.name,
+                lines.describe_register(other)
+            ));
+        } else {
+            names.insert(&reg.name, i);
+        }
+
+        // 같은 주소는 서로 다른 페이지에서만 허용 (페이지 없는 레지스터는 모든 페이지와 겹침)
+        // 여러 워드로 나뉘는 레지스터는 차지하는 주소 전체를 확인
+        let words = if SUPPORTED_WIDTHS.contains(&reg.width) {
+            bus.words(reg.width)
+        } else {
+            1
+        };
+        for offset in 0..words {
+            let Some(address) = reg.address.checked_add(offset) else {
+                report(format!(
+                    "Register at 0x{:02X} spans {} addresses past the end of the address space",
+                    reg.address, words
+                ));
+                break;
+            };
+            let used = addresses.entry(address).or_default();
+            if let Some(&(_, other)) = used
+                .iter()
+                .find(|(page, _)| page.is_none() || reg.page.is_none() || *page == reg.page)
+            {
+                let page = reg
+                    .page
+                    .map(|page| format!(" in page {}", page))
+                    .unwrap_or_default();
+                let other_reg = &map.registers[other];
+                report(if offset == 0 && other_reg.address == address {
+                    format!(
+                        "Duplicate address 0x{:02X}{} (already used by '{}' at {})",
+                        address,
+                        page,
+                        other_reg.name,
+                        lines.describe_register(other)
+                    )
+                } else {
+                    format!(
+                        "Address 0x{:02X}{} overlaps {}-bit register '{}' at 0x{:02X} ({})",
+                        address,
+                        page,
+                        other_reg.width,
+                        other_reg.name,
+                        other_reg.address,
+                        lines.describe_register(other)
+                    )
+                });
+                break;
+            }
+            used.push((reg.page, i));
+        }
+
+        if let Some(page) = reg.page {
+            match &map.paging {
+                None => report(format!(
+                    "Register is in page {} but the map has no paging definition",
+                    page
+                )),
+                Some(paging) if page & !paging.mask != 0 => report(format!(
+                    "Page {} does not fit in page mask 0x{:X}",
+                    page, paging.mask
+                )),
+                Some(paging)
+                    if !paging.pages.is_empty()
+                        && !paging.pages.iter().any(|p| p.value == page) =>
+                {
+                    report(format!("Page {} is not defined in paging.pages", page))
+                }
+                // 페이지 선택 레지스터는 어느 페이지에서든 접근할 수 있어야 함
+                Some(paging) if paging.address == reg.address => {
+                    report("The page select register must not belong to a page".to_string())
+                }
+                _ => {}
+            }
+        }
+
+        if !SUPPORTED_WIDTHS.contains(&reg.width) {
+            report(format!(
+                "Unsupported register width {} (expected 8, 16, 24, 32 or 64)",
+                reg.width
+            ));
+            continue;
+        }
+        if let Some(reset) = reg.reset {
+            if reset & !reg.value_mask() != 0 {
+                report(format!(
+                    "Reset value 0x{:X} does not fit in {} bits",
+                    reset, reg.width
+                ));
+            }
+        }
+
+        let mut field_names: HashMap<&str, usize> = HashMap::new();
+        for (j, field) in reg.fields.iter().enumerate() {
+            let path = format!("registers[{}].fields[{}]", i, j);
+            let line = lines.field(i, j).or(line);
+            let mut report = |message: String| {
+                issues.push(MapIssue {
+                    line,
+                    column: None,
+                    path: path.clone(),
+                    message,
+                })
+            };
+
+            if field.name.trim().is_empty() {
+                report("Field name is empty".to_string());
+            } else if let Some(&other) = field_names.get(field.name.as_str()) {
+                report(format!(
+                    "Duplicate field name '{}' in register '{}' (first defined at {})",
+                    field.name,
+                    reg.name,
+                    lines.describe_field(i, other)
+                ));
+            } else {
+                field_names.insert(&field.name, j);
+            }
+
+            if field.size == 0 {
+                report(format!("Field '{}' has zero size", field.name));
+                continue;
+            }
+            if field.bit as u64 + field.size as u64 > reg.width as u64 {
+                report(format!(
+                    "Field '{}' bits [{}:{}] exceed the {}-bit register width",
+                    field.name,
+                    field.msb(),
+                    field.bit,
+                    reg.width
+                ));
+                continue;
+            }
+            if let Some(reset) = field.reset {
+                if reset as u64 > bit_mask(field.size) {
+                    report(format!(
+                        "Field '{}' reset value 0x{:X} does not fit in {} bits",
+                        field.name, reset, field.size
+                    ));
+                }
+            }
+            let mut enum_values: HashMap<u64, &str> = HashMap::new();
+            for item in &field.enums {
+                if item.value > bit_mask(field.size) {
+                    report(format!(
+                        "Field '{}' enum '{}' value 0x{:X} does not fit in {} bits",
+                        field.name, item.name, item.value, field.size
+                    ));
+                } else if let Some(other) = enum_values.insert(item.value, &item.name) {
+                    report(format!(
+                        "Field '{}' enums '{}' and '{}' share value 0x{:X}",
+                        field.name, other, item.name, item.value
+                    ));
+                }
+            }
+            match &field.conversion {
+                Some(Conversion::Linear { step, offset, .. })
+                    if *step == 0.0 || !step.is_finite() || !offset.is_finite() =>
+                {
+                    report(format!(
+                        "Field '{}' linear conversion needs a finite, non-zero step",
+                        field.name
+                    ));
+                }
+                Some(Conversion::Lookup { table, .. }) => {
+                    let mut raws: HashMap<u64, f64> = HashMap::new();
+                    for entry in table {
+                        if entry.raw > bit_mask(field.size) {
+                            report(format!(
+                                "Field '{}' lookup code 0x{:X} does not fit in {} bits",
+                                field.name, entry.raw, field.size
+                            ));
+                        } else if raws.insert(entry.raw, entry.value).is_some() {
+                            report(format!(
+                                "Field '{}' lookup table lists code 0x{:X} twice",
+                                field.name, entry.raw
+                            ));
+                        }
+                    }
+                }
+                _ => {}
+            }
+            for (k, other) in reg.fields[..j].iter().enumerate() {
+                let in_range =
+                    other.size > 0 && other.bit as u64 + other.size as u64 <= reg.width as u64;
+                if in_range && other.mask() & field.mask() != 0 {
+                    report(format!(
+                        "Field '{}' bits [{}:{}] overlap field '{}' bits [{}:{}] at {}",
+                        field.name,
+                        field.msb(),
+                        field.bit,
+                        other.name,
+                        other.msb(),
+                        other.bit,
+                        lines.describe_field(i, k)
+                    ));
+                }
+            }
+        }
+    }
+    issues
+}
+
+// registers[i] / registers[i].fields[j] 항목이 시작하는 줄 번호 (블록 스타일 YAML 기준, 1부터)
+#[derive(Default)]
+struct LineIndex {
+    registers: Vec<(usize, Vec<usize>)>,
+}
+
+impl LineIndex {
+    fn build(text: &str) -> Self {
+        let mut index = LineIndex::default();
+        let mut registers_key: Option<usize> = None;
+        let mut register_indent: Option<usize> = None;
+        let mut fields_key: Option<usize> = None;
+        let mut field_indent: Option<usize> = None;
+
+        for (no, raw) in text.lines().enumerate() {
+            let trimmed = raw.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let indent = raw.len() - trimmed.len();
+            let is_item = trimmed == "-" || trimmed.starts_with("- ");
+            let body = if is_item {
+                trimmed[1..].trim_start()
+            } else {
+                trimmed
+            };
+
+            let Some(key_indent) = registers_key else {
+                if indent == 0 && body.starts_with("registers:") {
+                    registers_key = Some(0);
+                }
+                continue;
+            };
+            // registers 목록이 끝남
+            if indent <= key_indent && !is_item {
+                registers_key = None;
+                register_indent = None;
+                fields_key = None;
+                continue;
+            }
+
+            let new_register = is_item
+                && match register_indent {
+                    Some(ri) => indent == ri,
+                    None => true,
+                };
+            if new_register {
+                register_indent = Some(indent);
+                fields_key = None;
+                field_indent = None;
+                index.registers.push((no + 1, Vec::new()));
+                if body.starts_with("fields:") {
+                    fields_key = Some(indent + (trimmed.len() - body.len()));
+                }
+                continue;
+            }
+
+            if let Some(fk) = fields_key {
+                if is_item && indent >= fk && field_indent.is_none_or(|fi| fi == indent) {
+                    field_indent = Some(indent);
+                    if let Some((_, fields)) = index.registers.last_mut() {
+                        fields.push(no + 1);
+                    }
+                    continue;
+                }
+                if !is_item && indent <= fk {
+                    fields_key = None;
+                }
+            }
+            if !is_item && body.starts_with("fields:") {
+                fields_key = Some(indent);
+                field_indent = None;
+            }
+        }
+        index
+    }
+
+    fn register(&self, i: usize) -> Option<usize> {
+        self.registers.get(i).map(|(line, _)| *line)
+    }
+
+    fn field(&self, i: usize, j: usize) -> Option<usize> {
+        self.registers
+            .get(i)
+            .and_then(|(_, fields)| fields.get(j).copied())
+    }
+
+    fn describe_register(&self, i: usize) -> String {
+        match self.register(i) {
+            Some(line) => format!("line {}", line),
+            None => format!("registers[{}]", i),
+        }
+    }
+
+    fn describe_field(&self, i: usize, j: usize) -> String {
+        match self.field(i, j) {
+            Some(line) => format!("line {}", line),
+            None => format!("registers[{}].fields[{}]", i, j),
+        }
+    }
+}
@@ -0,0 +1,328 @@
+// 녹화 세션 재생용 가상 디바이스
+// RX는 원래 타이밍(또는 배속/최대 속도)으로 재생하고, 앱이 보내는 TX는 녹화된 TX와 비교
+use crate::recorder::{self, Direction, RecordEntry};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Instant;
+
+// 최대 속도 재생 시 한 번에 내보내는 엔트리 수
+const FAST_BATCH: usize = 256;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayOptions {
+    // 1.0 = 원래 속도, 2.0 = 2배속
+    #[serde(default = "default_speed")]
+    pub speed: f64,
+    #[serde(default)]
+    pub as_fast_as_possible: bool,
+    #[serde(default)]
+    pub validate_tx: bool,
+}
+
+fn default_speed() -> f64 {
+    1.0
+}
+
+impl Default for ReplayOptions {
+    fn default() -> Self {
+        Self {
+            speed: default_speed(),
+            as_fast_as_possible: false,
+            validate_tx: false,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxMismatch {
+    pub index: usize,
+    pub expected: Option<String>,
+    pub actual: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayStatus {
+    pub source: String,
+    pub rx_played: usize,
+    pub rx_total: usize,
+    pub tx_checked: usize,
+    pub tx_total: usize,
+    pub mismatches: usize,
+    pub finished: bool,
+}
+
+pub struct ReplayDevice {
+    source: String,
+    rx: Vec<RecordEntry>,
+    tx: Vec<RecordEntry>,
+    reads: Vec<(u32, u32)>,
+    rx_pos: usize,
+    tx_pos: usize,
+    read_pos: usize,
+    t0: u64,
+    started: Instant,
+    options: ReplayOptions,
+    mismatches: usize,
+    pending_mismatches: Vec<TxMismatch>,
+}
+
+impl ReplayDevice {
+    pub fn open(path: &str, options: ReplayOptions) -> Result<Self, String> {
+        if !options.as_fast_as_possible && options.speed <= 0.0 {
+            return Err("Replay speed must be greater than zero".into());
+        }
+        let entries = load_entries(Path::new(path))?;
+        if entries.is_empty() {
+            return Err(format!("Recording {} contains no entries", path));
+        }
+
+        let t0 = entries.first().map(|e| e.timestamp_us).unwrap_or(0);
+        let mut rx = Vec::new();
+        let mut tx = Vec::new();
+        let mut reads = Vec::new();
+        for entry in entries {
+            match entry.direction {
+                Direction::Rx => rx.push(entry),
+                Direction::Tx => tx.push(entry),
+                Direction::Reg => {
+                    if let Some(reg) = &entry.register {
+                        if let (true, Some(address), Some(value), None) =
+                            (reg.op == "read", reg.address, reg.value, &reg.error)
+                        {
+                            reads.push((address, value));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            source: path.to_string(),
+            rx,
+            tx,
+            reads,
+            rx_pos: 0,
+            tx_pos: 0,
+            read_pos: 0,
+            t0,
+            started: Instant::now(),
+            options,
+            mismatches: 0,
+            pending_mismatches: Vec::new(),
+        })
+    }
+
+    // 현재 시점까지 재생되어야 할 RX 엔트리
+    pub fn due_rx(&mut self) -> Vec<RecordEntry> {
+        let end = if self.options.as_fast_as_possible {
+            (self.rx_pos + FAST_BATCH).min(self.rx.len())
+        } else {
+            let elapsed_us = self.started.elapsed().as_micros() as f64 * self.options.speed;
+            let mut end = self.rx_pos;
+            while end < self.rx.len()
+                && (self.rx[end].timestamp_us.saturating_sub(self.t0) as f64) <= elapsed_us
+            {
+                end += 1;
+            }
+            end
+        };
+        let due = self.rx[self.rx_pos..end].to_vec();
+        self.rx_pos = end;
+        due
+    }
+
+    // 앱이 보낸 TX를 다음 녹화 TX와 비교
+    pub fn write(&mut self, data: &[u8]) {
+        if !self.options.validate_tx {
+            return;
+        }
+        let actual = entry_text(data);
+        let expected = self.tx.get(self.tx_pos).map(tx_text);
+        let index = self.tx_pos;
+        self.tx_pos += 1;
+        if expected.as_deref() != Some(actual.as_str()) {
+            self.mismatches += 1;
+            self.pending_mismatches.push(TxMismatch {
+                index,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    pub fn take_mismatches(&mut self) -> Vec<TxMismatch> {
+        std::mem::take(&mut self.pending_mismatches)
+    }
+
+    // 녹화된 레지스터 읽기 결과를 순서대로 돌려줌
+    pub fn read_register(&mut self, address: u32) -> Result<u32, String> {
+        let found = self.reads[self.read_pos..]
+            .iter()
+            .position(|(addr, _)| *addr == address);
+        match found {
+            Some(offset) => {
+                let (_, value) = self.reads[self.read_pos + offset];
+                self.read_pos += offset + 1;
+                Ok(value)
+            }
+            None => Err(format!(
+                "No recorded read of register 0x{:02X} left in replay",
+                address
+            )),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.rx_pos >= self.rx.len()
+    }
+
+    pub fn status(&self) -> ReplayStatus {
+        ReplayStatus {
+            source: self.source.clone(),
+            rx_played: self.rx_pos,
+            rx_total: self.rx.len(),
+            tx_checked: self.tx_pos,
+            tx_total: self.tx.len(),
+            mismatches: self.mismatches,
+            finished: self.is_finished(),
+        }
+    }
+}
+
+// 줄바꿈 차이로 인한 오탐을 피하기 위해 끝의 CR/LF 제거
+fn entry_text(data: &[u8]) -> String {
+    String::from_utf8_lossy(data)
+        .trim_end_matches(['\r', '\n'])
+        .to_string()
+}
+
+fn tx_text(entry: &RecordEntry) -> String {
+    match &entry.can {
+        Some(frame) => crate::can::format_frame(frame),
+        None => entry_text(&entry.data),
+    }
+}
+
+// 백엔드 세션 파일(.jsonl/.jsonl.gz) 또는 프론트엔드 로그(.csv/.json)
+pub fn load_entries(path: &Path) -> Result<Vec<RecordEntry>, String> {
+    let name = path.to_string_lossy().to_lowercase();
+    if recorder::is_recording_file(path) {
+        return recorder::read_session(path);
+    }
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if name.ends_with(".csv") {
+        parse_csv_log(&content)
+    } else if name.ends_with(".json") {
+        parse_json_log(&content)
+    } else {
+        Err(format!("Unsupported recording format: {}", path.display()))
+    }
+}
+
+#[derive(Deserialize)]
+struct SavedLogItem {
+    timestamp: String,
+    #[serde(rename = "type", default)]
+    kind: Option<String>,
+    #[serde(default)]
+    port: Option<String>,
+    data: String,
+}
+
+fn log_entry(timestamp: &str, kind: &str, port: &str, data: &str) -> Result<RecordEntry, String> {
+    let ts = chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map_err(|e| format!("Invalid log timestamp '{}': {}", timestamp, e))?;
+    let direction = if kind == "tx" {
+        Direction::Tx
+    } else {
+        Direction::Rx
+    };
+    Ok(RecordEntry {
+        timestamp_us: ts.timestamp_micros().max(0) as u64,
+        direction,
+        transport: "log".to_string(),
+        interface: port.to_string(),
+        data: data.as_bytes().to_vec(),
+        can: None,
+        register: None,
+    })
+}
+
+fn parse_json_log(content: &str) -> Result<Vec<RecordEntry>, String> {
+    let items: Vec<SavedLogItem> =
+        serde_json::from_str(content).map_err(|e| format!("Invalid JSON log: {}", e))?;
+    items
+        .iter()
+        .map(|item| {
+            log_entry(
+                &item.timestamp,
+                item.kind.as_deref().unwrap_or("rx"),
+                item.port.as_deref().unwrap_or(""),
+                &item.data,
+            )
+        })
+        .collect()
+}
+
+// "Timestamp,Type,Port,Data" 헤더의 CSV (따옴표 안 줄바꿈/"" 이스케이프 지원)
+fn parse_csv_log(content: &str) -> Result<Vec<RecordEntry>, String> {
+    let rows = parse_csv(content);
+    let mut entries = Vec::new();
+    for (index, row) in rows.iter().enumerate() {
+        if index == 0 && row.first().is_some_and(|c| c == "Timestamp") {
+            continue;
+        }
+        if row.len() < 4 {
+            if row.iter().all(|c| c.is_empty()) {
+                continue;
+            }
+            return Err(format!(
+                "Invalid CSV log row {}: expected 4 columns",
+                index + 1
+            ));
+        }
+        entries.push(log_entry(&row[0], &row[1], &row[2], &row[3])?);
+    }
+    Ok(entries)
+}
+
+fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
@@ -0,0 +1,325 @@
+// 레지스터 스냅샷
+// 읽을 수 있는 레지스터를 한 번에 읽어 이름 붙인 JSON 파일로 저장하고, 쓰기 가능한 레지스터를 정해진 순서로 복원
+use crate::recorder::sanitize_name;
+use crate::register_map::{Access, Register, RegisterMap};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+// 레지스터 하나의 읽기 결과 (일괄 새로고침/스냅샷 공통)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterRead {
+    pub address: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "crate::register_map::de_opt_value",
+        serialize_with = "crate::register_map::ser_opt_hex",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub value: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+}
+
+impl RegisterRead {
+    pub fn new(address: u32, register: Option<&Register>) -> Self {
+        Self {
+            address,
+            page: register.and_then(|r| r.page),
+            name: register.map(|r| r.name.clone()).unwrap_or_default(),
+            access: register.map(|r| r.access.label().to_string()),
+            value: None,
+            error: None,
+            skipped: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    pub name: String,
+    pub created: String,
+    #[serde(default)]
+    pub transport: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub registers: Vec<RegisterRead>,
+}
+
+impl Snapshot {
+    pub fn new(
+        name: &str,
+        transport: &str,
+        description: Option<String>,
+        registers: Vec<RegisterRead>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            created: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+            transport: transport.to_string(),
+            description,
+            registers,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    pub name: String,
+    pub path: String,
+    pub created: String,
+    pub modified: u64,
+    pub registers: usize,
+    pub captured: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+impl SnapshotInfo {
+    fn new(snapshot: &Snapshot, path: &Path, modified: u64) -> Self {
+        let count =
+            |f: fn(&RegisterRead) -> bool| snapshot.registers.iter().filter(|r| f(r)).count();
+        Self {
+            name: snapshot.name.clone(),
+            path: path.display().to_string(),
+            created: snapshot.created.clone(),
+            modified,
+            registers: snapshot.registers.len(),
+            captured: count(|r| r.value.is_some()),
+            failed: count(|r| r.error.is_some()),
+            skipped: count(|r| r.skipped.is_some()),
+        }
+    }
+}
+
+// 덤프/복원 진행 이벤트 ("snapshot-progress")
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotProgress {
+    pub operation: String,
+    pub done: usize,
+    pub total: usize,
+    pub address: u32,
+    pub name: String,
+}
+
+fn snapshot_path(directory: &Path, name: &str) -> Result<PathBuf, String> {
+    let file = sanitize_name(name);
+    if file.is_empty() {
+        return Err("Snapshot name is empty".into());
+    }
+    Ok(directory.join(format!("{}.json", file)))
+}
+
+pub fn save(
+    directory: &Path,
+    snapshot: &Snapshot,
+    overwrite: bool,
+) -> Result<SnapshotInfo, String> {
+    let path = snapshot_path(directory, &snapshot.name)?;
+    if path.exists() && !overwrite {
+        return Err(format!("Snapshot '{}' already exists", snapshot.name));
+    }
+    fs::create_dir_all(directory)
+        .map_err(|e| format!("Failed to create snapshot directory: {}", e))?;
+    let json = serde_json::to_string_pretty(snapshot).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write snapshot: {}", e))?;
+    Ok(SnapshotInfo::new(snapshot, &path, now_ms()))
+}
+
+// 이름 또는 파일 경로로 스냅샷 불러오기
+pub fn load(directory: &Path, name: &str) -> Result<Snapshot, String> {
+    let path = if Path::new(name).is_file() {
+        PathBuf::from(name)
+    } else {
+        snapshot_path(directory, name)?
+    };
+    read_file(&path)
+}
+
+fn read_file(path: &Path) -> Result<Snapshot, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read snapshot {}: {}", path.display(), e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid snapshot {}: {}", path.display(), e))
+}
+
+pub fn list(directory: &Path) -> Result<Vec<SnapshotInfo>, String> {
+    if !directory.exists() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        fs::read_dir(directory).map_err(|e| format!("Failed to read snapshot directory: {}", e))?;
+
+    let mut snapshots = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() || path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        // 다른 JSON 파일이나 손상된 파일은 목록에서 제외
+        let Ok(snapshot) = read_file(&path) else {
+            continue;
+        };
+        let modified = entry
+            .metadata()
+            .ok()
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        snapshots.push(SnapshotInfo::new(&snapshot, &path, modified));
+    }
+    snapshots.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(snapshots)
+}
+
+fn now_ms() -> u64 {
+    chrono::Local::now().timestamp_millis().max(0) as u64
+}
+
+// 복원할 레지스터 하나
+pub struct RestoreStep {
+    pub register: Register,
+    pub value: u64,
+    // 다시 읽어 비교할 비트 (0이면 검증하지 않음)
+    pub verify_mask: u64,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreResult {
+    pub address: u32,
+    pub name: String,
+    // "written" | "verified" | "mismatch" | "skipped" | "failed"
+    pub status: String,
+    #[serde(serialize_with = "crate::register_map::ser_opt_hex")]
+    pub value: Option<u64>,
+    #[serde(serialize_with = "crate::register_map::ser_opt_hex")]
+    pub read_back: Option<u64>,
+    pub message: Option<String>,
+}
+
+impl RestoreResult {
+    pub fn new(address: u32, name: &str, status: &str) -> Self {
+        Self {
+            address,
+            name: name.to_string(),
+            status: status.to_string(),
+            value: None,
+            read_back: None,
+            message: None,
+        }
+    }
+
+    fn skipped(address: u32, name: &str, reason: String) -> Self {
+        Self {
+            message: Some(reason),
+            ..Self::new(address, name, "skipped")
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    pub name: String,
+    pub written: usize,
+    pub verified: usize,
+    pub mismatched: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub results: Vec<RestoreResult>,
+}
+
+impl RestoreReport {
+    pub fn new(name: &str, results: Vec<RestoreResult>) -> Self {
+        let count = |status: &str| results.iter().filter(|r| r.status == status).count();
+        Self {
+            name: name.to_string(),
+            written: count("written") + count("verified") + count("mismatch"),
+            verified: count("verified"),
+            mismatched: count("mismatch"),
+            skipped: count("skipped"),
+            failed: count("failed"),
+            results,
+        }
+    }
+}
+
+// 복원 순서: order 에 나열한 주소를 먼저 그 순서대로, 나머지는 주소 오름차순
+// 쓸 수 있는 비트(RW/WO/WONCE)만 복원하고, W1C/W1S 비트는 0으로 써서 부작용을 피함
+pub fn restore_plan(
+    snapshot: &Snapshot,
+    map: &RegisterMap,
+    order: &[u32],
+) -> (Vec<RestoreStep>, Vec<RestoreResult>) {
+    let mut entries: Vec<&RegisterRead> = snapshot.registers.iter().collect();
+    let rank = |address: u32| {
+        order
+            .iter()
+            .position(|&a| a == address)
+            .unwrap_or(order.len())
+    };
+    entries.sort_by_key(|entry| (rank(entry.address), entry.address, entry.page));
+
+    let mut steps = Vec::new();
+    let mut skipped = Vec::new();
+    let mut seen = HashSet::new();
+    for entry in entries {
+        if !seen.insert((entry.address, entry.page)) {
+            continue;
+        }
+        let Some(value) = entry.value else {
+            skipped.push(RestoreResult::skipped(
+                entry.address,
+                &entry.name,
+                "Value was not captured".into(),
+            ));
+            continue;
+        };
+        let Some(register) = map
+            .register_in_page(entry.address, entry.page)
+            .filter(|register| register.on_page(entry.page))
+        else {
+            skipped.push(RestoreResult::skipped(
+                entry.address,
+                &entry.name,
+                "Register is not in the current register map".into(),
+            ));
+            continue;
+        };
+        let restorable = register.access_mask(Access::ReadWrite)
+            | register.access_mask(Access::WriteOnly)
+            | register.access_mask(Access::WriteOnce);
+        if !register.access.is_writable() || restorable == 0 {
+            skipped.push(RestoreResult::skipped(
+                entry.address,
+                &register.name,
+                format!("No restorable bits ({})", register.access.label()),
+            ));
+            continue;
+        }
+        let side_effects = register.access_mask(Access::WriteOneToClear)
+            | register.access_mask(Access::WriteOneToSet);
+        steps.push(RestoreStep {
+            register: register.clone(),
+            value: value & register.value_mask() & !side_effects,
+            verify_mask: register.verify_mask(),
+        });
+    }
+    (steps, skipped)
+}
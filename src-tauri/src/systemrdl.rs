@@ -0,0 +1,1322 @@
+// SystemRDL 가져오기 (실무에서 자주 쓰는 부분집합)
+// addrmap/regfile/reg/field/enum 정의와 인스턴스(@ 주소, += 간격, 배열), default 속성,
+// sw/onread/onwrite/reset/desc/encoding 속성, inst->reset/desc 동적 할당을 지원
+// 그 밖의 구성(mem, signal, 사용자 property, 파라미터, 전처리기 등)은 경고로 보고하고 건너뜀
+use crate::register_map::{
+    Access, Field, FieldEnum, MapIssue, Register, RegisterMap, SUPPORTED_WIDTHS,
+};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Tok {
+    Ident(String),
+    Number(u64),
+    Str(String),
+    Punct(&'static str),
+    Eof,
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    tok: Tok,
+    line: usize,
+    column: usize,
+}
+
+const PUNCTS: [&str; 16] = [
+    "->", "+=", "%=", "{", "}", "[", "]", "(", ")", ";", ":", ",", "=", "@", ".", "#",
+];
+
+fn issue(line: usize, column: usize, message: String) -> MapIssue {
+    MapIssue {
+        line: Some(line),
+        column: Some(column),
+        path: String::new(),
+        message,
+    }
+}
+
+fn tokenize(text: &str, warnings: &mut Vec<MapIssue>) -> Result<Vec<Token>, MapIssue> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let (mut i, mut line, mut line_start) = (0, 1, 0);
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i - line_start + 1;
+        if c == '\n' {
+            i += 1;
+            line += 1;
+            line_start = i;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+        // 주석
+        if rest == "//" {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+        if rest == "/*" {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                if chars[i] == '\n' {
+                    line += 1;
+                    line_start = i + 1;
+                }
+                i += 1;
+            }
+            i += 2;
+            continue;
+        }
+        // 전처리기 지시문 (`include, `define 등)과 Perl 전처리 구간은 처리하지 않음
+        if c == '`' || rest == "<%" {
+            warnings.push(issue(
+                line,
+                column,
+                "Preprocessor directives are not supported and were skipped".into(),
+            ));
+            if c == '`' {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            } else {
+                while i < chars.len() && !(chars[i] == '%' && chars.get(i + 1) == Some(&'>')) {
+                    if chars[i] == '\n' {
+                        line += 1;
+                        line_start = i + 1;
+                    }
+                    i += 1;
+                }
+                i += 2;
+            }
+            continue;
+        }
+        let start = i;
+        let tok = if c == '"' {
+            i += 1;
+            let mut value = String::new();
+            while i < chars.len() && chars[i] != '"' {
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    i += 1;
+                }
+                if chars[i] == '\n' {
+                    line += 1;
+                    line_start = i + 1;
+                }
+                value.push(chars[i]);
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err(issue(line, column, "Unterminated string".into()));
+            }
+            i += 1;
+            Tok::Str(value)
+        } else if c.is_ascii_digit() || c == '\'' {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || "_'".contains(chars[i])) {
+                i += 1;
+            }
+            let literal: String = chars[start..i].iter().collect();
+            Tok::Number(
+                parse_literal(&literal)
+                    .ok_or_else(|| issue(line, column, format!("Invalid number '{}'", literal)))?,
+            )
+        } else if c.is_alphabetic() || c == '_' || c == '\\' {
+            if c == '\\' {
+                i += 1;
+            }
+            let word_start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            Tok::Ident(chars[word_start..i].iter().collect())
+        } else if let Some(punct) = PUNCTS.iter().find(|p| rest.starts_with(**p)) {
+            i += punct.len();
+            Tok::Punct(punct)
+        } else {
+            return Err(issue(line, column, format!("Unexpected character '{}'", c)));
+        };
+        tokens.push(Token { tok, line, column });
+    }
+    tokens.push(Token {
+        tok: Tok::Eof,
+        line,
+        column: chars.len() - line_start + 1,
+    });
+    Ok(tokens)
+}
+
+// 10, 0x1F, 8'h1F, 'b101
+fn parse_literal(text: &str) -> Option<u64> {
+    let text = text.replace('_', "");
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        return u64::from_str_radix(hex, 16).ok();
+    }
+    if let Some((_, based)) = text.split_once('\'') {
+        let (radix, digits) = match based.chars().next()? {
+            'h' | 'H' => (16, &based[1..]),
+            'd' | 'D' => (10, &based[1..]),
+            'o' | 'O' => (8, &based[1..]),
+            'b' | 'B' => (2, &based[1..]),
+            _ => return None,
+        };
+        return u64::from_str_radix(digits, radix).ok();
+    }
+    text.parse().ok()
+}
+
+#[derive(Clone, Debug)]
+enum Value {
+    Number(u64),
+    Str(String),
+    Ident(String),
+}
+
+type Props = HashMap<String, Value>;
+
+// 인스턴스 배열 하나의 최대 원소 수
+const MAX_ARRAY: u64 = 4096;
+// 한 블록(addrmap/regfile)에 만들 수 있는 최대 레지스터 수
+const MAX_REGISTERS: u64 = 65536;
+
+// 값 없이 쓰면 true 인 불리언 속성 (woclr; 등)
+const BOOLEAN_PROPS: [&str; 8] = [
+    "woclr", "woset", "rclr", "rset", "intr", "counter", "hwclr", "hwset",
+];
+// 알고 있지만 앱 모델에 영향을 주지 않아 조용히 무시하는 속성
+const IGNORED_PROPS: [&str; 16] = [
+    "name",
+    "hw",
+    "we",
+    "wel",
+    "swmod",
+    "swacc",
+    "precedence",
+    "singlepulse",
+    "fieldwidth",
+    "accesswidth",
+    "addressing",
+    "littleendian",
+    "bigendian",
+    "lsb0",
+    "shared",
+    "sharedextbus",
+];
+
+#[derive(Clone)]
+struct RegDef {
+    width: u32,
+    description: String,
+    fields: Vec<Field>,
+}
+
+#[derive(Clone, Default)]
+struct BlockDef {
+    // 블록 기준 상대 주소
+    registers: Vec<Register>,
+    size: u64,
+}
+
+// 인스턴스 선언: name[N] 또는 name[msb:lsb], = reset, @ addr, += stride
+struct Instance {
+    name: String,
+    range: Option<(u64, Option<u64>)>,
+    reset: Option<u64>,
+    at: Option<u64>,
+    stride: Option<u64>,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Scope {
+    Root,
+    Block,
+    Reg,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    warnings: Vec<MapIssue>,
+    defaults: Vec<Props>,
+    fields: HashMap<String, Props>,
+    regs: HashMap<String, RegDef>,
+    blocks: HashMap<String, BlockDef>,
+    enums: HashMap<String, Vec<FieldEnum>>,
+    top: Option<BlockDef>,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn peek_at(&self, offset: usize) -> &Tok {
+        &self.tokens[(self.pos + offset).min(self.tokens.len() - 1)].tok
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if self.pos < self.tokens.len() - 1 {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn is_punct(&self, punct: &'static str) -> bool {
+        self.peek().tok == Tok::Punct(punct)
+    }
+
+    fn eat(&mut self, punct: &'static str) -> bool {
+        if self.is_punct(punct) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, message: String) -> MapIssue {
+        let token = self.peek();
+        issue(token.line, token.column, message)
+    }
+
+    fn expect(&mut self, punct: &'static str) -> Result<(), MapIssue> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(self.error(format!(
+                "Expected '{}' but found {}",
+                punct,
+                describe(&self.peek().tok)
+            )))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, MapIssue> {
+        match self.peek().tok.clone() {
+            Tok::Ident(name) => {
+                self.pos += 1;
+                Ok(name)
+            }
+            other => Err(self.error(format!("Expected a name but found {}", describe(&other)))),
+        }
+    }
+
+    fn number(&mut self) -> Result<u64, MapIssue> {
+        match self.peek().tok {
+            Tok::Number(value) => {
+                self.pos += 1;
+                Ok(value)
+            }
+            ref other => Err(self.error(format!(
+                "Expected a number but found {} (expressions are not supported)",
+                describe(other)
+            ))),
+        }
+    }
+
+    fn warn_at(&mut self, line: usize, column: usize, message: String) {
+        self.warnings.push(issue(line, column, message));
+    }
+
+    fn warn(&mut self, message: String) {
+        let (line, column) = (self.peek().line, self.peek().column);
+        self.warn_at(line, column, message);
+    }
+
+    // 지원하지 않는 문장을 ';' 까지 (중괄호 블록 포함) 건너뜀
+    fn skip_statement(&mut self) {
+        let mut depth = 0usize;
+        loop {
+            match self.next().tok {
+                Tok::Eof => return,
+                Tok::Punct("{") => depth += 1,
+                Tok::Punct("}") => {
+                    if depth == 0 {
+                        // 바깥 블록의 닫는 괄호는 남겨둠
+                        self.pos -= 1;
+                        return;
+                    }
+                    depth -= 1;
+                }
+                Tok::Punct(";") if depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+
+    fn merged_defaults(&self) -> Props {
+        let mut props = Props::new();
+        for scope in &self.defaults {
+            props.extend(scope.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        props
+    }
+
+    // name [= value] ;
+    fn property(&mut self) -> Result<(String, Value), MapIssue> {
+        let name = self.ident()?;
+        let value = if self.eat("=") {
+            match self.next().tok {
+                Tok::Number(value) => Value::Number(value),
+                Tok::Str(value) => Value::Str(value),
+                Tok::Ident(value) => Value::Ident(value),
+                other => {
+                    self.pos -= 1;
+                    return Err(self.error(format!(
+                        "Unsupported value {} for property '{}'",
+                        describe(&other),
+                        name
+                    )));
+                }
+            }
+        } else {
+            Value::Ident("true".into())
+        };
+        self.expect(";")?;
+        Ok((name, value))
+    }
+
+    fn parse_file(&mut self) -> Result<(), MapIssue> {
+        while self.peek().tok != Tok::Eof {
+            self.item(Scope::Root, &mut BlockDef::default(), None)?;
+        }
+        Ok(())
+    }
+
+    // 한 문장 처리. block 은 addrmap/regfile 본문, reg 는 reg 본문일 때 채워짐
+    fn item(
+        &mut self,
+        scope: Scope,
+        block: &mut BlockDef,
+        reg: Option<&mut RegDef>,
+    ) -> Result<(), MapIssue> {
+        let token = self.peek().clone();
+        let keyword = match &token.tok {
+            Tok::Ident(word) => word.clone(),
+            Tok::Punct(";") => {
+                self.pos += 1;
+                return Ok(());
+            }
+            other => return Err(self.error(format!("Unexpected {}", describe(other)))),
+        };
+        match keyword.as_str() {
+            "external" | "internal" => {
+                self.pos += 1;
+                self.item(scope, block, reg)
+            }
+            "addrmap" | "regfile" if scope == Scope::Root || scope == Scope::Block => {
+                self.pos += 1;
+                let name = self.optional_name();
+                let def = self.block_body()?;
+                if let Some(name) = &name {
+                    self.blocks.insert(name.clone(), def.clone());
+                }
+                if scope == Scope::Root {
+                    if keyword == "addrmap" {
+                        self.top = Some(def);
+                    }
+                    // 최상위 인스턴스 이름은 의미가 없음
+                    if !self.is_punct(";") {
+                        self.instances()?;
+                    }
+                } else if !self.is_punct(";") {
+                    let instances = self.instances()?;
+                    self.place_block(block, &def, instances);
+                }
+                self.expect(";")
+            }
+            "reg" if scope != Scope::Reg => {
+                self.pos += 1;
+                let name = self.optional_name();
+                let def = self.reg_body(name.as_deref().unwrap_or(""))?;
+                if let Some(name) = &name {
+                    self.regs.insert(name.clone(), def.clone());
+                }
+                if !self.is_punct(";") {
+                    let instances = self.instances()?;
+                    if scope == Scope::Root {
+                        self.warn_at(
+                            token.line,
+                            token.column,
+                            "Register instances outside an addrmap were ignored".into(),
+                        );
+                    } else {
+                        self.place_regs(block, &def, instances);
+                    }
+                }
+                self.expect(";")
+            }
+            "field" => {
+                self.pos += 1;
+                let name = self.optional_name();
+                let props = self.field_body()?;
+                if let Some(name) = &name {
+                    self.fields.insert(name.clone(), props.clone());
+                }
+                if !self.is_punct(";") {
+                    let instances = self.instances()?;
+                    match reg {
+                        Some(reg) => self.place_fields(reg, &props, instances),
+                        None => self.warn_at(
+                            token.line,
+                            token.column,
+                            "Field instances outside a reg were ignored".into(),
+                        ),
+                    }
+                }
+                self.expect(";")
+            }
+            "enum" => {
+                self.pos += 1;
+                self.enum_body()
+            }
+            "default" => {
+                self.pos += 1;
+                let (name, value) = self.property()?;
+                if let Some(scope) = self.defaults.last_mut() {
+                    scope.insert(name, value);
+                }
+                Ok(())
+            }
+            "mem" | "signal" | "property" | "constraint" | "struct" => {
+                self.warn(format!("'{}' is not supported and was skipped", keyword));
+                self.skip_statement();
+                Ok(())
+            }
+            _ => {
+                if matches!(self.peek_at(1), Tok::Punct("->") | Tok::Punct(".")) {
+                    return self.dynamic_assignment(block, reg);
+                }
+                if matches!(self.peek_at(1), Tok::Punct("=") | Tok::Punct(";")) {
+                    return self.component_property(scope, reg);
+                }
+                // 앞에서 정의한 타입의 인스턴스
+                self.pos += 1;
+                let field_type = self.fields.get(&keyword).cloned();
+                let reg_type = self.regs.get(&keyword).cloned();
+                let block_type = self.blocks.get(&keyword).cloned();
+                if let (Some(reg), Some(props)) = (reg, field_type) {
+                    let instances = self.instances()?;
+                    self.place_fields(reg, &props, instances);
+                } else if let (Scope::Block, Some(def)) = (scope, reg_type) {
+                    let instances = self.instances()?;
+                    self.place_regs(block, &def, instances);
+                } else if let (Scope::Block, Some(def)) = (scope, block_type) {
+                    let instances = self.instances()?;
+                    self.place_block(block, &def, instances);
+                } else {
+                    self.warn_at(
+                        token.line,
+                        token.column,
+                        format!("Unknown type or keyword '{}' was skipped", keyword),
+                    );
+                    self.skip_statement();
+                    return Ok(());
+                }
+                self.expect(";")
+            }
+        }
+    }
+
+    fn optional_name(&mut self) -> Option<String> {
+        if self.is_punct("#") {
+            self.warn("Parameterized components are not supported".into());
+            self.pos += 1;
+            self.skip_parens();
+        }
+        match self.peek().tok.clone() {
+            Tok::Ident(name) => {
+                self.pos += 1;
+                if self.is_punct("#") {
+                    self.warn(format!(
+                        "Parameters of '{}' are not supported and were ignored",
+                        name
+                    ));
+                    self.pos += 1;
+                    self.skip_parens();
+                }
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    fn skip_parens(&mut self) {
+        if !self.eat("(") {
+            return;
+        }
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next().tok {
+                Tok::Punct("(") => depth += 1,
+                Tok::Punct(")") => depth -= 1,
+                Tok::Eof => return,
+                _ => {}
+            }
+        }
+    }
+
+    // 컴포넌트 자체 속성 (desc = "..."; regwidth = 16; sw = rw; woclr; ...)
+    fn component_property(
+        &mut self,
+        scope: Scope,
+        reg: Option<&mut RegDef>,
+    ) -> Result<(), MapIssue> {
+        let token = self.peek().clone();
+        let (name, value) = self.property()?;
+        match (scope, name.as_str(), &value, reg) {
+            // 32비트를 넘는 값은 지원하지 않는 폭으로 남겨 place_regs 에서 경고
+            (Scope::Reg, "regwidth", Value::Number(width), Some(reg)) => {
+                reg.width = u32::try_from(*width).unwrap_or(u32::MAX);
+            }
+            (Scope::Reg, "desc", Value::Str(desc), Some(reg)) => {
+                reg.description = desc.clone();
+            }
+            (_, "desc" | "name", _, _) => {}
+            (Scope::Block, "alignment", _, _) | (_, "msb0", _, _) => self.warn_at(
+                token.line,
+                token.column,
+                format!("Property '{}' is not supported and was ignored", name),
+            ),
+            (_, name, _, _) if IGNORED_PROPS.contains(&name) => {}
+            _ => self.warn_at(
+                token.line,
+                token.column,
+                format!("Property '{}' is not supported here and was ignored", name),
+            ),
+        }
+        Ok(())
+    }
+
+    // inst->prop = value;  inst.field->prop = value;  (reset, desc 만 지원)
+    fn dynamic_assignment(
+        &mut self,
+        block: &mut BlockDef,
+        reg: Option<&mut RegDef>,
+    ) -> Result<(), MapIssue> {
+        let token = self.peek().clone();
+        let mut path = vec![self.ident()?];
+        while self.eat(".") {
+            path.push(self.ident()?);
+        }
+        self.expect("->")?;
+        let (name, value) = self.property()?;
+
+        let mut applied = false;
+        match (reg, path.as_slice()) {
+            (Some(reg), [field]) => {
+                if let Some(field) = reg.fields.iter_mut().find(|f| &f.name == field) {
+                    applied = apply_field_property(field, &name, &value);
+                }
+            }
+            (None, [register]) => {
+                if let (Some(register), "desc", Value::Str(desc)) = (
+                    block.registers.iter_mut().find(|r| &r.name == register),
+                    name.as_str(),
+                    &value,
+                ) {
+                    register.description = desc.clone();
+                    applied = true;
+                }
+            }
+            (None, [register, field]) => {
+                if let Some(field) = block
+                    .registers
+                    .iter_mut()
+                    .find(|r| &r.name == register)
+                    .and_then(|r| r.fields.iter_mut().find(|f| &f.name == field))
+                {
+                    applied = apply_field_property(field, &name, &value);
+                }
+            }
+            _ => {}
+        }
+        if !applied {
+            self.warn_at(
+                token.line,
+                token.column,
+                format!(
+                    "Dynamic assignment {}->{} is not supported and was ignored",
+                    path.join("."),
+                    name
+                ),
+            );
+        }
+        Ok(())
+    }
+
+    fn block_body(&mut self) -> Result<BlockDef, MapIssue> {
+        self.expect("{")?;
+        self.defaults.push(Props::new());
+        let mut block = BlockDef::default();
+        while !self.eat("}") {
+            if self.peek().tok == Tok::Eof {
+                return Err(self.error("Unexpected end of file, expected '}'".into()));
+            }
+            self.item(Scope::Block, &mut block, None)?;
+        }
+        self.defaults.pop();
+        Ok(block)
+    }
+
+    fn reg_body(&mut self, type_name: &str) -> Result<RegDef, MapIssue> {
+        self.expect("{")?;
+        self.defaults.push(Props::new());
+        let mut reg = RegDef {
+            width: match self.merged_defaults().get("regwidth") {
+                Some(Value::Number(width)) => u32::try_from(*width).unwrap_or(u32::MAX),
+                _ => 32,
+            },
+            description: String::new(),
+            fields: Vec::new(),
+        };
+        while !self.eat("}") {
+            if self.peek().tok == Tok::Eof {
+                return Err(self.error("Unexpected end of file, expected '}'".into()));
+            }
+            self.item(Scope::Reg, &mut BlockDef::default(), Some(&mut reg))?;
+        }
+        self.defaults.pop();
+        if reg.fields.is_empty() {
+            self.warn(format!("Register '{}' has no fields", type_name));
+        }
+        reg.fields.sort_by_key(|f| f.bit);
+        Ok(reg)
+    }
+
+    fn field_body(&mut self) -> Result<Props, MapIssue> {
+        let mut props = self.merged_defaults();
+        self.expect("{")?;
+        while !self.eat("}") {
+            match self.peek().tok {
+                Tok::Eof => return Err(self.error("Unexpected end of file, expected '}'".into())),
+                Tok::Ident(ref word) if word == "enum" => {
+                    self.pos += 1;
+                    self.enum_body()?;
+                }
+                _ => {
+                    let (name, value) = self.property()?;
+                    props.insert(name, value);
+                }
+            }
+        }
+        Ok(props)
+    }
+
+    // enum NAME { A = 0 { desc = "..."; }; B; };
+    fn enum_body(&mut self) -> Result<(), MapIssue> {
+        let name = self.ident()?;
+        self.expect("{")?;
+        let mut entries = Vec::new();
+        let mut next = 0u64;
+        while !self.eat("}") {
+            let entry = self.ident()?;
+            if self.eat("=") {
+                next = self.number()?;
+            }
+            let mut description = String::new();
+            if self.eat("{") {
+                while !self.eat("}") {
+                    if self.peek().tok == Tok::Eof {
+                        return Err(self.error("Unexpected end of file, expected '}'".into()));
+                    }
+                    if let (key, Value::Str(text)) = self.property()? {
+                        if key == "desc" || (key == "name" && description.is_empty()) {
+                            description = text;
+                        }
+                    }
+                }
+            }
+            self.expect(";")?;
+            entries.push(FieldEnum {
+                value: next,
+                name: entry,
+                description,
+            });
+            next += 1;
+        }
+        self.expect(";")?;
+        self.enums.insert(name, entries);
+        Ok(())
+    }
+
+    // inst [N | msb:lsb] [= reset] [@ addr] [+= stride] [%= align], ...
+    fn instances(&mut self) -> Result<Vec<Instance>, MapIssue> {
+        let mut instances = Vec::new();
+        loop {
+            let token = self.peek().clone();
+            let name = self.ident()?;
+            let mut instance = Instance {
+                name,
+                range: None,
+                reset: None,
+                at: None,
+                stride: None,
+                line: token.line,
+                column: token.column,
+            };
+            if self.eat("[") {
+                let first = self.number()?;
+                let second = if self.eat(":") {
+                    Some(self.number()?)
+                } else {
+                    None
+                };
+                self.expect("]")?;
+                instance.range = Some((first, second));
+                if self.is_punct("[") {
+                    self.warn("Multi-dimensional arrays are not supported".into());
+                    while self.eat("[") {
+                        self.number()?;
+                        self.expect("]")?;
+                    }
+                }
+            }
+            if self.eat("=") {
+                instance.reset = Some(self.number()?);
+            }
+            if self.eat("@") {
+                instance.at = Some(self.number()?);
+            }
+            if self.eat("+=") {
+                instance.stride = Some(self.number()?);
+            }
+            if self.eat("%=") {
+                self.number()?;
+                self.warn("Alignment (%=) is not supported and was ignored".into());
+            }
+            instances.push(instance);
+            if !self.eat(",") {
+                return Ok(instances);
+            }
+        }
+    }
+
+    fn place_fields(&mut self, reg: &mut RegDef, props: &Props, instances: Vec<Instance>) {
+        for instance in instances {
+            let next_bit = reg.fields.iter().map(|f| f.msb() + 1).max().unwrap_or(0) as u64;
+            let (bit, size) = match (instance.range, instance.at) {
+                (Some((msb, Some(lsb))), _) => (msb.min(lsb), msb.abs_diff(lsb) + 1),
+                (Some((width, None)), at) => (at.unwrap_or(next_bit), width),
+                (None, at) => {
+                    let width = match props.get("fieldwidth") {
+                        Some(Value::Number(width)) => *width,
+                        _ => 1,
+                    };
+                    (at.unwrap_or(next_bit), width)
+                }
+            };
+            let (Ok(bit), Ok(size)) = (u32::try_from(bit), u32::try_from(size)) else {
+                self.warn_at(
+                    instance.line,
+                    instance.column,
+                    format!("Field '{}' bit range is out of range", instance.name),
+                );
+                continue;
+            };
+            let access = self.field_access(props, &instance);
+            let reset = instance.reset.or(match props.get("reset") {
+                Some(Value::Number(reset)) => Some(*reset),
+                Some(Value::Ident(signal)) => {
+                    self.warn_at(
+                        instance.line,
+                        instance.column,
+                        format!("Reset from signal '{}' is not supported", signal),
+                    );
+                    None
+                }
+                _ => None,
+            });
+            let enums = match props.get("encoding") {
+                Some(Value::Ident(name)) => match self.enums.get(name) {
+                    Some(entries) => entries.clone(),
+                    None => {
+                        self.warn_at(
+                            instance.line,
+                            instance.column,
+                            format!("Unknown enum '{}'", name),
+                        );
+                        Vec::new()
+                    }
+                },
+                _ => Vec::new(),
+            };
+            // 하드웨어가 값을 바꿀 수 있다고 명시된 필드
+            let volatile = matches!(props.get("hw"), Some(Value::Ident(hw)) if hw.contains('w'))
+                || ["intr", "counter", "hwclr", "hwset"]
+                    .iter()
+                    .any(|p| is_true(props.get(*p)));
+            for key in props.keys() {
+                let known = [
+                    "sw", "onread", "onwrite", "reset", "desc", "encoding", "regwidth",
+                ];
+                if !known.contains(&key.as_str())
+                    && !BOOLEAN_PROPS.contains(&key.as_str())
+                    && !IGNORED_PROPS.contains(&key.as_str())
+                {
+                    self.warn_at(
+                        instance.line,
+                        instance.column,
+                        format!("Field property '{}' is not supported and was ignored", key),
+                    );
+                }
+            }
+            reg.fields.push(Field {
+                name: instance.name,
+                bit,
+                size,
+                description: match props.get("desc") {
+                    Some(Value::Str(desc)) => desc.clone(),
+                    _ => String::new(),
+                },
+                access: Some(access),
+                reset,
+                volatile,
+                enums,
+                conversion: None,
+            });
+        }
+    }
+
+    fn field_access(&mut self, props: &Props, instance: &Instance) -> Access {
+        let mut warn = |message: String| {
+            self.warnings
+                .push(issue(instance.line, instance.column, message));
+        };
+        let onread = match props.get("onread") {
+            Some(Value::Ident(value)) => value.as_str(),
+            _ if is_true(props.get("rclr")) => "rclr",
+            _ if is_true(props.get("rset")) => "rset",
+            _ => "",
+        };
+        let onwrite = match props.get("onwrite") {
+            Some(Value::Ident(value)) => value.as_str(),
+            _ if is_true(props.get("woclr")) => "woclr",
+            _ if is_true(props.get("woset")) => "woset",
+            _ => "",
+        };
+        match onread {
+            "" => {}
+            "rclr" => return Access::ReadClear,
+            other => warn(format!("onread = {} is not supported", other)),
+        }
+        match onwrite {
+            "" => {}
+            "woclr" => return Access::WriteOneToClear,
+            "woset" => return Access::WriteOneToSet,
+            other => warn(format!("onwrite = {} is not supported", other)),
+        }
+        match props.get("sw") {
+            None => Access::ReadWrite,
+            Some(Value::Ident(sw)) => match sw.as_str() {
+                "rw" | "wr" => Access::ReadWrite,
+                "r" => Access::ReadOnly,
+                "w" => Access::WriteOnly,
+                "rw1" | "w1" => Access::WriteOnce,
+                "na" => Access::Reserved,
+                other => {
+                    warn(format!("sw = {} is not supported", other));
+                    Access::ReadWrite
+                }
+            },
+            Some(_) => {
+                warn("Invalid sw value".into());
+                Access::ReadWrite
+            }
+        }
+    }
+
+    // 배열 크기와 만들어질 레지스터 수 제한 (잘못된 입력으로 레지스터를 수백만 개 만들지 않게)
+    // per_element: 원소 하나에 들어 있는 레지스터 수, existing: 블록에 이미 있는 레지스터 수
+    fn check_array(
+        &mut self,
+        instance: &Instance,
+        count: u64,
+        per_element: usize,
+        existing: usize,
+    ) -> bool {
+        if count > MAX_ARRAY {
+            self.warn_at(
+                instance.line,
+                instance.column,
+                format!(
+                    "Array '{}' has {} elements (at most {} are supported) and was skipped",
+                    instance.name, count, MAX_ARRAY
+                ),
+            );
+            return false;
+        }
+        let total = (per_element as u64)
+            .saturating_mul(count)
+            .saturating_add(existing as u64);
+        if total > MAX_REGISTERS {
+            self.warn_at(
+                instance.line,
+                instance.column,
+                format!(
+                    "'{}' would create more than {} registers and was skipped",
+                    instance.name, MAX_REGISTERS
+                ),
+            );
+            return false;
+        }
+        true
+    }
+
+    // 배열 원소의 주소 (계산이 넘쳤으면 None). 32비트 주소를 벗어나면 경고하고 None
+    fn element_address(
+        &mut self,
+        instance: &Instance,
+        index: u64,
+        address: Option<u64>,
+    ) -> Option<u32> {
+        let address = address.and_then(|address| u32::try_from(address).ok());
+        if address.is_none() {
+            self.warn_at(
+                instance.line,
+                instance.column,
+                format!(
+                    "Address of '{}' element {} does not fit in 32 bits; remaining elements were skipped",
+                    instance.name, index
+                ),
+            );
+        }
+        address
+    }
+
+    fn place_regs(&mut self, block: &mut BlockDef, def: &RegDef, instances: Vec<Instance>) {
+        if !SUPPORTED_WIDTHS.contains(&def.width) {
+            if let Some(instance) = instances.first() {
+                self.warn_at(
+                    instance.line,
+                    instance.column,
+                    format!("{}-bit registers are not supported", def.width),
+                );
+            }
+            return;
+        }
+        let bytes = (def.width / 8) as u64;
+        for instance in instances {
+            if instance.reset.is_some() {
+                self.warn_at(
+                    instance.line,
+                    instance.column,
+                    "Register instance reset values are not supported".into(),
+                );
+            }
+            let (count, array) = match instance.range {
+                Some((count, None)) => (count, true),
+                Some(_) => {
+                    self.warn_at(
+                        instance.line,
+                        instance.column,
+                        "Bit ranges on register instances are invalid".into(),
+                    );
+                    (1, false)
+                }
+                None => (1, false),
+            };
+            if !self.check_array(&instance, count, 1, block.registers.len()) {
+                continue;
+            }
+            let start = instance.at.unwrap_or(block.size.div_ceil(bytes) * bytes);
+            let stride = instance.stride.unwrap_or(bytes);
+            for index in 0..count {
+                let offset = index.checked_mul(stride).and_then(|o| o.checked_add(start));
+                let Some(address) = self.element_address(&instance, index, offset) else {
+                    break;
+                };
+                let offset = address as u64;
+                let name = if array {
+                    format!("{}_{}", instance.name, index)
+                } else {
+                    instance.name.clone()
+                };
+                let mut register = Register {
+                    address,
+                    name,
+                    description: def.description.clone(),
+                    width: def.width,
+                    access: Access::default(),
+                    reset: None,
+                    page: None,
+                    byte_order: None,
+                    value: 0,
+                    read_only: false,
+                    verify: None,
+                    fields: def.fields.clone(),
+                };
+                register.finish_import(None);
+                block.registers.push(register);
+                block.size = block.size.max(offset + bytes);
+            }
+        }
+    }
+
+    fn place_block(&mut self, block: &mut BlockDef, def: &BlockDef, instances: Vec<Instance>) {
+        for instance in instances {
+            let (count, array) = match instance.range {
+                Some((count, None)) => (count, true),
+                _ => (1, false),
+            };
+            if !self.check_array(&instance, count, def.registers.len(), block.registers.len()) {
+                continue;
+            }
+            let start = instance.at.unwrap_or(block.size);
+            let stride = instance.stride.unwrap_or(def.size);
+            'elements: for index in 0..count {
+                let base = index.checked_mul(stride).and_then(|o| o.checked_add(start));
+                let Some(base) = self.element_address(&instance, index, base) else {
+                    break;
+                };
+                let prefix = if array {
+                    format!("{}_{}", instance.name, index)
+                } else {
+                    instance.name.clone()
+                };
+                let mut placed = Vec::with_capacity(def.registers.len());
+                for register in &def.registers {
+                    let address = base as u64 + register.address as u64;
+                    let Some(address) = self.element_address(&instance, index, Some(address))
+                    else {
+                        break 'elements;
+                    };
+                    let mut register = register.clone();
+                    register.address = address;
+                    register.name = format!("{}_{}", prefix, register.name);
+                    placed.push(register);
+                }
+                block.registers.extend(placed);
+                block.size = block.size.max(base as u64 + def.size);
+            }
+        }
+    }
+}
+
+fn apply_field_property(field: &mut Field, name: &str, value: &Value) -> bool {
+    match (name, value) {
+        ("reset", Value::Number(reset)) => field.reset = Some(*reset),
+        ("desc", Value::Str(desc)) => field.description = desc.clone(),
+        _ => return false,
+    }
+    true
+}
+
+fn is_true(value: Option<&Value>) -> bool {
+    matches!(value, Some(Value::Ident(v)) if v == "true")
+        || matches!(value, Some(Value::Number(n)) if *n != 0)
+}
+
+fn describe(tok: &Tok) -> String {
+    match tok {
+        Tok::Ident(name) => format!("'{}'", name),
+        Tok::Number(value) => format!("number {}", value),
+        Tok::Str(_) => "a string".into(),
+        Tok::Punct(punct) => format!("'{}'", punct),
+        Tok::Eof => "end of file".into(),
+    }
+}
+
+// SystemRDL -> 레지스터 맵 (마지막 최상위 addrmap 기준) + 변환하지 못한 구성에 대한 경고
+pub fn import(text: &str) -> Result<(RegisterMap, Vec<MapIssue>), MapIssue> {
+    let mut warnings = Vec::new();
+    let tokens = tokenize(text, &mut warnings)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        warnings,
+        defaults: vec![Props::new()],
+        fields: HashMap::new(),
+        regs: HashMap::new(),
+        blocks: HashMap::new(),
+        enums: HashMap::new(),
+        top: None,
+    };
+    parser.parse_file()?;
+    let top = parser
+        .top
+        .take()
+        .ok_or_else(|| issue(1, 1, "No top-level addrmap found".into()))?;
+    let mut map = RegisterMap {
+        bus: None,
+        paging: None,
+        registers: top.registers,
+    };
+    for register in &mut map.registers {
+        // 동적 할당으로 바뀐 필드 리셋 값을 레지스터 리셋에 반영
+        register.reset = None;
+        let access = register.access;
+        register.finish_import(Some(access));
+    }
+    Ok((map, parser.warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::register_map::validate;
+
+    fn messages(warnings: &[MapIssue]) -> Vec<String> {
+        warnings.iter().map(|w| w.message.clone()).collect()
+    }
+
+    fn has_warning(warnings: &[MapIssue], text: &str) -> bool {
+        warnings.iter().any(|w| w.message.contains(text))
+    }
+
+    const PMIC: &str = r#"
+enum mode_e { OFF = 0 { desc = "off"; }; LOW; HIGH = 3; };
+field status_f { sw = r; hw = w; };
+addrmap pmic {
+    default regwidth = 8;
+    reg ctrl_r {
+        desc = "Control";
+        field { sw = rw; encoding = mode_e; } MODE[1:0] = 2;
+        field { woclr; } FAULT[4:4];
+        status_f STAT[2];
+    };
+    ctrl_r CTRL @ 0x0;
+    ctrl_r CH[2] @ 0x10 += 0x4;
+    reg { field { sw = r; } ID[8] = 0x5A; } ID;
+    CTRL.MODE->reset = 1;
+    regfile bank_rf { reg { field {} X[8]; } A; reg { field {} Y[8]; } B; };
+    bank_rf BANK[2] @ 0x20 += 0x10;
+    reg { regwidth = 64; field {} W[64]; } WIDE;
+};
+"#;
+
+    #[test]
+    fn imports_supported_subset() {
+        let (map, warnings) = import(PMIC).unwrap();
+        assert!(warnings.is_empty(), "{:?}", messages(&warnings));
+        let names: Vec<_> = map.registers.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "CTRL", "CH_0", "CH_1", "ID", "BANK_0_A", "BANK_0_B", "BANK_1_A", "BANK_1_B",
+                "WIDE"
+            ]
+        );
+        // WIDE 는 64비트라 8바이트 경계(0x38)에 놓임
+        let addresses: Vec<_> = map.registers.iter().map(|r| r.address).collect();
+        assert_eq!(
+            addresses,
+            [0x00, 0x10, 0x14, 0x15, 0x20, 0x21, 0x30, 0x31, 0x38]
+        );
+
+        let ctrl = &map.registers[0];
+        assert_eq!((ctrl.width, ctrl.description.as_str()), (8, "Control"));
+        // 동적 할당(CTRL.MODE->reset)은 CTRL 에만 적용
+        assert_eq!(ctrl.find_field("MODE").unwrap().reset, Some(1));
+        assert_eq!(map.registers[1].find_field("MODE").unwrap().reset, Some(2));
+        assert_eq!(ctrl.find_field("MODE").unwrap().enums.len(), 3);
+        assert_eq!(
+            ctrl.find_field("FAULT").unwrap().access,
+            Some(Access::WriteOneToClear)
+        );
+        let stat = ctrl.find_field("STAT").unwrap();
+        assert_eq!((stat.bit, stat.size, stat.volatile), (5, 2, true));
+        assert_eq!(stat.access, Some(Access::ReadOnly));
+
+        let id = &map.registers[3];
+        assert_eq!((id.access, id.reset), (Access::ReadOnly, Some(0x5A)));
+        assert_eq!(map.registers[8].width, 64);
+        assert!(validate(&map, None).is_empty());
+    }
+
+    #[test]
+    fn warns_on_unsupported_constructs() {
+        let text = r#"
+`include "common.rdl"
+property my_prop { type = boolean; component = field; };
+signal { } irq;
+addrmap top {
+    mem m { mementries = 16; };
+    reg { field { sw = rw; my_prop; } A[0:0]; field { onread = rset; } B[1:1]; } R;
+    reg { regwidth = 12; field {} C[4]; } ODD;
+};
+"#;
+        let (map, warnings) = import(text).unwrap();
+        assert_eq!(map.registers.len(), 1);
+        assert!(has_warning(&warnings, "Preprocessor directives"));
+        assert!(has_warning(&warnings, "'property' is not supported"));
+        assert!(has_warning(&warnings, "'signal' is not supported"));
+        assert!(has_warning(&warnings, "'mem' is not supported"));
+        assert!(has_warning(&warnings, "Field property 'my_prop'"));
+        assert!(has_warning(&warnings, "onread = rset"));
+        assert!(has_warning(&warnings, "12-bit registers are not supported"));
+    }
+
+    #[test]
+    fn reports_syntax_errors_with_location() {
+        let err = import("addrmap a {\n reg { field {} F[1:0] } R;\n};")
+            .err()
+            .unwrap();
+        assert_eq!(err.line, Some(2));
+        assert!(import("reg r { field {} a; };").is_err());
+    }
+
+    #[test]
+    fn skips_oversized_arrays() {
+        let text =
+            "addrmap top {\n reg { field {} F[8]; } R[100000];\n reg { field {} G[8]; } S;\n};";
+        let (map, warnings) = import(text).unwrap();
+        assert_eq!(map.registers.len(), 1);
+        assert_eq!(map.registers[0].name, "S");
+        assert!(has_warning(&warnings, "at most 4096"));
+        assert_eq!(warnings[0].line, Some(2));
+
+        // 배열 안의 배열이 레지스터 수 제한을 넘으면 건너뜀
+        let text =
+            "addrmap top {\n regfile rf { reg { field {} F[8]; } R[4096]; };\n rf BANK[4096];\n};";
+        let (map, warnings) = import(text).unwrap();
+        assert!(map.registers.is_empty());
+        assert!(has_warning(&warnings, "more than 65536 registers"));
+    }
+
+    #[test]
+    fn rejects_addresses_beyond_32_bits() {
+        let text = "addrmap top {\n reg { regwidth = 8; field {} F[8]; } R[4] @ 0xFFFFFFFE;\n};";
+        let (map, warnings) = import(text).unwrap();
+        let addresses: Vec<_> = map.registers.iter().map(|r| r.address).collect();
+        assert_eq!(addresses, [0xFFFF_FFFE, 0xFFFF_FFFF]);
+        assert!(has_warning(
+            &warnings,
+            "'R' element 2 does not fit in 32 bits"
+        ));
+
+        // start + index * stride 가 64비트를 넘쳐도 패닉하지 않음
+        let text = "addrmap top {\n reg { field {} F[8]; } R[2] @ 0 += 0xFFFFFFFFFFFFFFFF;\n};";
+        let (map, warnings) = import(text).unwrap();
+        assert_eq!(map.registers.len(), 1);
+        assert!(has_warning(&warnings, "element 1 does not fit"));
+
+        let text = "addrmap top {\n regfile rf { reg { field {} F[8]; } A @ 0x10; };\n rf B @ 0xFFFFFFF8;\n};";
+        let (map, warnings) = import(text).unwrap();
+        assert!(map.registers.is_empty());
+        assert!(has_warning(&warnings, "'B' element 0 does not fit"));
+    }
+
+    #[test]
+    fn rejects_field_ranges_beyond_32_bits() {
+        let text = "addrmap top {\n reg { field {} F[8] @ 0x100000000; field {} G[8]; } R;\n};";
+        let (map, warnings) = import(text).unwrap();
+        let names: Vec<_> = map.registers[0]
+            .fields
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(names, ["G"]);
+        assert!(has_warning(
+            &warnings,
+            "Field 'F' bit range is out of range"
+        ));
+    }
+}
@@ -0,0 +1,240 @@
+// LLM 토큰 사용량 집계
+// 이번 실행(세션)과 날짜별로 모델마다 요청 수와 입력/출력 토큰을 모으고, 날짜별 합계는 usage/usage.json 에 저장
+// 모델별 단가(100만 토큰당)를 지정하면 비용도 계산
+use crate::llm::Usage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+const USAGE_FILE: &str = "usage.json";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModelTotals {
+    requests: u64,
+    // 응답에 사용량이 없던 요청 수 (토큰 합계에 빠짐)
+    #[serde(default)]
+    unreported: u64,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+}
+
+impl ModelTotals {
+    fn add(&mut self, other: &ModelTotals) {
+        self.requests += other.requests;
+        self.unreported += other.unreported;
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+    }
+}
+
+// 모델별 단가 (100만 토큰당, 통화는 사용자가 정함)
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPrice {
+    pub prompt_per_million: f64,
+    pub completion_per_million: f64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct UsageFile {
+    // 날짜(YYYY-MM-DD) -> 모델 -> 합계
+    #[serde(default)]
+    days: BTreeMap<String, BTreeMap<String, ModelTotals>>,
+    #[serde(default)]
+    prices: BTreeMap<String, ModelPrice>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelUsage {
+    pub model: String,
+    pub requests: u64,
+    pub unreported: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    // 단가가 없으면 None
+    pub cost: Option<f64>,
+}
+
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageSummary {
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    // 단가를 아는 모델의 비용 합계 (단가를 아는 모델이 없으면 None)
+    pub cost: Option<f64>,
+    pub models: Vec<ModelUsage>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyUsage {
+    pub date: String,
+    pub usage: UsageSummary,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    pub session: UsageSummary,
+    pub days: Vec<DailyUsage>,
+    // days 전체 합계
+    pub total: UsageSummary,
+    pub prices: BTreeMap<String, ModelPrice>,
+}
+
+pub struct UsageTracker {
+    // 앱 시작(setup) 전이나 열기에 실패하면 None (저장하지 않고 메모리에만 집계)
+    dir: Option<PathBuf>,
+    file: UsageFile,
+    session: BTreeMap<String, ModelTotals>,
+}
+
+impl UsageTracker {
+    pub fn new() -> Self {
+        Self {
+            dir: None,
+            file: UsageFile::default(),
+            session: BTreeMap::new(),
+        }
+    }
+
+    pub fn open(dir: PathBuf) -> Result<Self, String> {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create usage directory: {}", e))?;
+        let path = dir.join(USAGE_FILE);
+        let file = if path.exists() {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?
+        } else {
+            UsageFile::default()
+        };
+        Ok(Self {
+            dir: Some(dir),
+            file,
+            session: BTreeMap::new(),
+        })
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        let path = dir.join(USAGE_FILE);
+        let json = serde_json::to_string_pretty(&self.file)
+            .map_err(|e| format!("Failed to serialize usage: {}", e))?;
+        fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    // 응답 하나의 사용량 기록 (usage 가 None 이면 요청 수만 셈)
+    pub fn record(&mut self, model: &str, usage: Option<Usage>) -> Result<(), String> {
+        let model = model.trim();
+        let model = if model.is_empty() { "unknown" } else { model };
+        let totals = ModelTotals {
+            requests: 1,
+            unreported: u64::from(usage.is_none()),
+            prompt_tokens: usage.map_or(0, |u| u.prompt_tokens),
+            completion_tokens: usage.map_or(0, |u| u.completion_tokens),
+        };
+        self.session
+            .entry(model.to_string())
+            .or_default()
+            .add(&totals);
+        let today = chrono::Local::now().format(DATE_FORMAT).to_string();
+        self.file
+            .days
+            .entry(today)
+            .or_default()
+            .entry(model.to_string())
+            .or_default()
+            .add(&totals);
+        self.save()
+    }
+
+    pub fn set_prices(&mut self, prices: BTreeMap<String, ModelPrice>) -> Result<(), String> {
+        let valid = |value: f64| value.is_finite() && value >= 0.0;
+        if let Some((model, _)) = prices.iter().find(|(_, price)| {
+            !valid(price.prompt_per_million) || !valid(price.completion_per_million)
+        }) {
+            return Err(format!("Invalid price for model '{}'", model));
+        }
+        self.file.prices = prices;
+        self.save()
+    }
+
+    // from/to (YYYY-MM-DD, 포함) 사이 날짜별 사용량. prices 가 없으면 저장된 단가 사용
+    pub fn report(
+        &self,
+        from: Option<&str>,
+        to: Option<&str>,
+        prices: Option<&BTreeMap<String, ModelPrice>>,
+    ) -> Result<UsageReport, String> {
+        let from = from.map(parse_date).transpose()?;
+        let to = to.map(parse_date).transpose()?;
+        let prices = prices.unwrap_or(&self.file.prices);
+
+        let mut total = BTreeMap::<String, ModelTotals>::new();
+        let mut days = Vec::new();
+        for (date, models) in &self.file.days {
+            // 날짜 형식이 고정이라 문자열 비교로 범위를 판단
+            if from.as_ref().is_some_and(|from| date < from)
+                || to.as_ref().is_some_and(|to| date > to)
+            {
+                continue;
+            }
+            for (model, totals) in models {
+                total.entry(model.clone()).or_default().add(totals);
+            }
+            days.push(DailyUsage {
+                date: date.clone(),
+                usage: summarize(models, prices),
+            });
+        }
+        Ok(UsageReport {
+            session: summarize(&self.session, prices),
+            days,
+            total: summarize(&total, prices),
+            prices: prices.clone(),
+        })
+    }
+}
+
+fn parse_date(text: &str) -> Result<String, String> {
+    chrono::NaiveDate::parse_from_str(text.trim(), DATE_FORMAT)
+        .map(|date| date.format(DATE_FORMAT).to_string())
+        .map_err(|_| format!("Invalid date '{}' (expected YYYY-MM-DD)", text))
+}
+
+fn summarize(
+    models: &BTreeMap<String, ModelTotals>,
+    prices: &BTreeMap<String, ModelPrice>,
+) -> UsageSummary {
+    let mut summary = UsageSummary::default();
+    for (model, totals) in models {
+        let cost = prices.get(model).map(|price| {
+            (totals.prompt_tokens as f64 * price.prompt_per_million
+                + totals.completion_tokens as f64 * price.completion_per_million)
+                / 1_000_000.0
+        });
+        summary.requests += totals.requests;
+        summary.prompt_tokens += totals.prompt_tokens;
+        summary.completion_tokens += totals.completion_tokens;
+        if let Some(cost) = cost {
+            *summary.cost.get_or_insert(0.0) += cost;
+        }
+        summary.models.push(ModelUsage {
+            model: model.clone(),
+            requests: totals.requests,
+            unreported: totals.unreported,
+            prompt_tokens: totals.prompt_tokens,
+            completion_tokens: totals.completion_tokens,
+            cost,
+        });
+    }
+    summary
+}
@@ -0,0 +1,364 @@
+// 레지스터 감시 목록
+// 레지스터/필드를 주기적으로 읽어 값이 바뀔 때만 register-changed, 조건(FAULT != 0 등)이 바뀔 때 register-alert 이벤트를 냄
+use crate::register_map::{parse_wide_number, Field, Register};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+const MIN_INTERVAL_MS: u64 = 20;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchRequest {
+    // 레지스터 이름 또는 주소 ("STATUS", "0x10")
+    pub register: String,
+    #[serde(default)]
+    pub field: Option<String>,
+    #[serde(default = "default_interval")]
+    pub interval_ms: u64,
+    // 예: "FAULT != 0", "!= 0", "> 0x10", "& 0x4" (비트 중 하나라도 1)
+    #[serde(default)]
+    pub condition: Option<String>,
+}
+
+fn default_interval() -> u64 {
+    500
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    AnyBits,
+}
+
+#[derive(Clone, Debug)]
+struct Condition {
+    op: CompareOp,
+    value: u64,
+    // 왼쪽에 적은 필드 (없으면 감시 값 그대로 비교)
+    field: Option<Field>,
+    text: String,
+}
+
+impl Condition {
+    // 왼쪽 이름은 감시하는 레지스터의 필드여야 함 (필드를 감시 중이면 그 필드만)
+    fn parse(text: &str, register: &Register, watched: Option<&Field>) -> Result<Self, String> {
+        let text = text.trim();
+        let start = text
+            .find(['!', '=', '<', '>', '&'])
+            .ok_or_else(|| format!("Condition '{}' has no comparison operator", text))?;
+        let name = text[..start].trim();
+        // 이름이 없거나 레지스터 이름이면 감시 값 그대로
+        let field = if name.is_empty()
+            || (watched.is_none() && name.eq_ignore_ascii_case(&register.name))
+        {
+            None
+        } else {
+            let field = register.find_field(name).ok_or_else(|| {
+                format!(
+                    "Unknown field '{}' in condition '{}' for register {}",
+                    name, text, register.name
+                )
+            })?;
+            if let Some(watched) = watched {
+                if watched.name != field.name {
+                    return Err(format!(
+                        "Condition '{}' refers to field {} but the watch is on field {}",
+                        text, field.name, watched.name
+                    ));
+                }
+            }
+            Some(field.clone())
+        };
+        let rest = &text[start..];
+        let (op, len) = match rest.get(..2) {
+            Some("!=") => (CompareOp::Ne, 2),
+            Some("==") => (CompareOp::Eq, 2),
+            Some("<=") => (CompareOp::Le, 2),
+            Some(">=") => (CompareOp::Ge, 2),
+            _ => match &rest[..1] {
+                "=" => (CompareOp::Eq, 1),
+                "<" => (CompareOp::Lt, 1),
+                ">" => (CompareOp::Gt, 1),
+                "&" => (CompareOp::AnyBits, 1),
+                _ => return Err(format!("Invalid operator in condition '{}'", text)),
+            },
+        };
+        let operand = rest[len..].trim();
+        let value = parse_wide_number(operand)
+            .ok_or_else(|| format!("Invalid value '{}' in condition '{}'", operand, text))?;
+        Ok(Self {
+            op,
+            value,
+            field,
+            text: text.to_string(),
+        })
+    }
+
+    fn matches(&self, value: u64) -> bool {
+        match self.op {
+            CompareOp::Eq => value == self.value,
+            CompareOp::Ne => value != self.value,
+            CompareOp::Lt => value < self.value,
+            CompareOp::Le => value <= self.value,
+            CompareOp::Gt => value > self.value,
+            CompareOp::Ge => value >= self.value,
+            CompareOp::AnyBits => value & self.value != 0,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchInfo {
+    pub id: u64,
+    pub register: String,
+    pub address: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    pub field: Option<String>,
+    pub interval_ms: u64,
+    pub condition: Option<String>,
+    #[serde(serialize_with = "crate::register_map::ser_opt_hex")]
+    pub value: Option<u64>,
+    pub alert_active: bool,
+    pub last_error: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterChanged {
+    pub id: u64,
+    pub register: String,
+    pub address: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    pub field: Option<String>,
+    #[serde(serialize_with = "crate::register_map::ser_hex")]
+    pub value: u64,
+    // 첫 읽기에서는 None
+    #[serde(serialize_with = "crate::register_map::ser_opt_hex")]
+    pub previous: Option<u64>,
+    #[serde(serialize_with = "crate::register_map::ser_hex")]
+    pub register_value: u64,
+    pub timestamp: u64,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterAlert {
+    pub id: u64,
+    pub register: String,
+    pub address: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    pub field: Option<String>,
+    #[serde(serialize_with = "crate::register_map::ser_hex")]
+    pub value: u64,
+    pub condition: String,
+    // true: 조건이 새로 만족됨, false: 조건이 해제됨
+    pub active: bool,
+    pub timestamp: u64,
+}
+
+pub enum WatchEvent {
+    Changed(RegisterChanged),
+    Alert(RegisterAlert),
+}
+
+struct Watch {
+    id: u64,
+    register: Register,
+    field: Option<Field>,
+    interval: Duration,
+    condition: Option<Condition>,
+    next_poll: Instant,
+    value: Option<u64>,
+    alert_active: bool,
+    last_error: Option<String>,
+}
+
+impl Watch {
+    fn info(&self) -> WatchInfo {
+        WatchInfo {
+            id: self.id,
+            register: self.register.name.clone(),
+            address: self.register.address,
+            page: self.register.page,
+            field: self.field.as_ref().map(|f| f.name.clone()),
+            interval_ms: self.interval.as_millis() as u64,
+            condition: self.condition.as_ref().map(|c| c.text.clone()),
+            value: self.value,
+            alert_active: self.alert_active,
+            last_error: self.last_error.clone(),
+        }
+    }
+}
+
+pub struct WatchList {
+    watches: Vec<Watch>,
+    next_id: u64,
+    // 라운드로빈 위치 (주기가 짧은 항목이 다른 항목을 굶기지 않도록)
+    cursor: usize,
+}
+
+impl WatchList {
+    pub fn new() -> Self {
+        Self {
+            watches: Vec::new(),
+            next_id: 1,
+            cursor: 0,
+        }
+    }
+
+    // 읽기만으로 값이 지워지는 RC 레지스터와 쓰기 전용 레지스터는 감시할 수 없음
+    pub fn add(
+        &mut self,
+        register: Register,
+        field: Option<Field>,
+        request: &WatchRequest,
+    ) -> Result<WatchInfo, String> {
+        if !register.is_readable() {
+            return Err(format!("Register {} is write-only", register.name));
+        }
+        if register.has_read_side_effects() {
+            return Err(format!(
+                "Register {} has read-to-clear bits and cannot be polled",
+                register.name
+            ));
+        }
+        if request.interval_ms < MIN_INTERVAL_MS {
+            return Err(format!(
+                "Polling interval must be at least {} ms",
+                MIN_INTERVAL_MS
+            ));
+        }
+        let condition = request
+            .condition
+            .as_deref()
+            .filter(|c| !c.trim().is_empty())
+            .map(|text| Condition::parse(text, &register, field.as_ref()))
+            .transpose()?;
+
+        let watch = Watch {
+            id: self.next_id,
+            register,
+            field,
+            interval: Duration::from_millis(request.interval_ms),
+            condition,
+            next_poll: Instant::now(),
+            value: None,
+            alert_active: false,
+            last_error: None,
+        };
+        self.next_id += 1;
+        let info = watch.info();
+        self.watches.push(watch);
+        Ok(info)
+    }
+
+    pub fn remove(&mut self, id: u64) -> bool {
+        let before = self.watches.len();
+        self.watches.retain(|w| w.id != id);
+        self.watches.len() != before
+    }
+
+    pub fn clear(&mut self) {
+        self.watches.clear();
+    }
+
+    pub fn list(&self) -> Vec<WatchInfo> {
+        self.watches.iter().map(Watch::info).collect()
+    }
+
+    // 기한이 지난 항목 중 다음으로 읽을 레지스터 (주소, 페이지)
+    pub fn next_due(&mut self, now: Instant) -> Option<(u32, Option<u32>)> {
+        let count = self.watches.len();
+        for offset in 0..count {
+            let index = (self.cursor + offset) % count;
+            if self.watches[index].next_poll <= now {
+                self.cursor = index + 1;
+                let register = &self.watches[index].register;
+                return Some((register.address, register.page));
+            }
+        }
+        None
+    }
+
+    // 한 번 읽은 값을 같은 레지스터를 감시하는 모든 항목에 반영
+    pub fn apply(
+        &mut self,
+        address: u32,
+        page: Option<u32>,
+        result: Result<u64, String>,
+    ) -> Vec<WatchEvent> {
+        let now = Instant::now();
+        let timestamp = chrono::Local::now().timestamp_millis().max(0) as u64;
+        let mut events = Vec::new();
+        for watch in self
+            .watches
+            .iter_mut()
+            .filter(|w| w.register.address == address && w.register.page == page)
+        {
+            watch.next_poll = now + watch.interval;
+            let register_value = match &result {
+                Ok(value) => *value,
+                Err(e) => {
+                    watch.last_error = Some(e.clone());
+                    continue;
+                }
+            };
+            watch.last_error = None;
+            let value = match &watch.field {
+                Some(field) => field.extract(register_value),
+                None => register_value,
+            };
+            let field = watch.field.as_ref().map(|f| f.name.clone());
+
+            if watch.value != Some(value) {
+                events.push(WatchEvent::Changed(RegisterChanged {
+                    id: watch.id,
+                    register: watch.register.name.clone(),
+                    address,
+                    page,
+                    field: field.clone(),
+                    value,
+                    previous: watch.value,
+                    register_value,
+                    timestamp,
+                }));
+                watch.value = Some(value);
+            }
+
+            if let Some(condition) = &watch.condition {
+                let (value, field) = match &condition.field {
+                    Some(cond_field) => (
+                        cond_field.extract(register_value),
+                        Some(cond_field.name.clone()),
+                    ),
+                    None => (value, field),
+                };
+                let active = condition.matches(value);
+                if active != watch.alert_active {
+                    watch.alert_active = active;
+                    events.push(WatchEvent::Alert(RegisterAlert {
+                        id: watch.id,
+                        register: watch.register.name.clone(),
+                        address,
+                        page,
+                        field,
+                        value,
+                        condition: condition.text.clone(),
+                        active,
+                        timestamp,
+                    }));
+                }
+            }
+        }
+        events
+    }
+}
@@ -161,9 +161,10 @@ export const useRegisterStore = defineStore("registers", () => {
       }, 200);
     } else {
       try {
+        // Register values cross IPC as hex strings ("0x1F") to stay exact above 2^53
         const value = await invoke("read_register", { address });
         const reg = registers.value.find((r) => r.address === address);
-        if (reg) reg.value = value;
+        if (reg) reg.value = normalizeNumber(value, reg.value);
       } catch (error) {
         console.error(
           `Failed to read register 0x${address.toString(16)}:`,
@@ -184,7 +185,10 @@ export const useRegisterStore = defineStore("registers", () => {
       if (reg) reg.value = value;
     } else {
       try {
-        await invoke("write_register", { address, value });
+        await invoke("write_register", {
+          address,
+          value: `0x${value.toString(16).toUpperCase()}`,
+        });
         const reg = registers.value.find((r) => r.address === address);
         if (reg) reg.value = value;
       } catch (error) {